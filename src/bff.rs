@@ -1,4 +1,6 @@
-use crate::substrate::Substrate;
+use crate::substrate::{
+    CLASS_ARITHMETIC, CLASS_CONTROL, CLASS_MEMORY, CLASS_NOP, CLASS_POINTER, Substrate,
+};
 
 /// The BFF (Brainfuck Family) instruction set from Section 2 of the paper.
 ///
@@ -131,28 +133,41 @@ fn bff_step(state: &mut BffState, tape: &mut [u8]) -> bool {
     true
 }
 
-impl Substrate for Bff {
-    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
-        let len = tape.len();
-        if len == 0 {
-            return 0;
+/// Run a program from position 0, marking each dispatched position in
+/// `visited` if given.
+fn bff_run(tape: &mut [u8], step_limit: usize, mut visited: Option<&mut [bool]>) -> usize {
+    let len = tape.len();
+    if len == 0 {
+        return 0;
+    }
+
+    let mut state = BffState {
+        ip: 0,
+        head0: 0,
+        head1: 0,
+    };
+    let mut steps = 0;
+
+    while state.ip < len && steps < step_limit {
+        steps += 1;
+        if let Some(visited) = visited.as_deref_mut() {
+            visited[state.ip] = true;
+        }
+        if !bff_step(&mut state, tape) {
+            break;
         }
+    }
 
-        let mut state = BffState {
-            ip: 0,
-            head0: 0,
-            head1: 0,
-        };
-        let mut steps = 0;
+    steps
+}
 
-        while state.ip < len && steps < step_limit {
-            steps += 1;
-            if !bff_step(&mut state, tape) {
-                break;
-            }
-        }
+impl Substrate for Bff {
+    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
+        bff_run(tape, step_limit, None)
+    }
 
-        steps
+    fn execute_profiled(tape: &mut [u8], step_limit: usize, visited: &mut [bool]) -> Option<usize> {
+        Some(bff_run(tape, step_limit, Some(visited)))
     }
 
     fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
@@ -188,6 +203,39 @@ impl Substrate for Bff {
         )
     }
 
+    fn instruction_class(byte: u8) -> u8 {
+        match byte {
+            LESS | GREATER | LBRACE | RBRACE => CLASS_POINTER,
+            MINUS | PLUS => CLASS_ARITHMETIC,
+            DOT | COMMA => CLASS_MEMORY,
+            LBRACKET | RBRACKET => CLASS_CONTROL,
+            _ => CLASS_NOP,
+        }
+    }
+
+    fn opcode_table() -> &'static [(u8, &'static str, &'static str)] {
+        &[
+            (PLUS, "INC", "Increment the byte at head0"),
+            (COMMA, "COPY1->0", "Copy the byte at head1 to head0"),
+            (MINUS, "DEC", "Decrement the byte at head0"),
+            (DOT, "COPY0->1", "Copy the byte at head0 to head1"),
+            (LESS, "HEAD0--", "Move head0 left"),
+            (GREATER, "HEAD0++", "Move head0 right"),
+            (
+                LBRACKET,
+                "LOOP_START",
+                "If the byte at head0 is 0, jump past the matching ]",
+            ),
+            (
+                RBRACKET,
+                "LOOP_END",
+                "If the byte at head0 isn't 0, jump back to the matching [",
+            ),
+            (LBRACE, "HEAD1--", "Move head1 left"),
+            (RBRACE, "HEAD1++", "Move head1 right"),
+        ]
+    }
+
     fn disassemble(tape: &[u8]) -> String {
         use std::fmt::Write;
         let mut out = String::new();
@@ -216,6 +216,35 @@ impl Substrate for Echo {
         byte <= SET_RP
     }
 
+    fn opcode_table() -> &'static [(u8, &'static str, &'static str)] {
+        &[
+            (HALT, "HALT", "Stop"),
+            (ECHO, "ECHO", "Copy the byte at rp to rp + delay; rp++"),
+            (LOAD, "LOAD", "acc = byte at rp; rp++"),
+            (STORE, "STORE", "Write acc to rp + delay"),
+            (SKIP, "SKIP", "rp++"),
+            (SET_DELAY, "SET_DELAY n", "delay = the next byte"),
+            (INC, "INC", "acc++"),
+            (DEC, "DEC", "acc--"),
+            (XOR, "XOR", "acc ^= byte at rp"),
+            (ADD, "ADD", "acc += byte at rp; rp++"),
+            (JMP_REL, "JMP_REL n", "Jump by the signed next byte"),
+            (JZ, "JZ n", "JMP_REL if acc is 0, else skip the operand"),
+            (
+                JNZ,
+                "JNZ n",
+                "JMP_REL if acc isn't 0, else skip the operand",
+            ),
+            (
+                SKIP_EQ,
+                "SKIP_EQ",
+                "Skip the next byte if the bytes at rp and rp + delay match",
+            ),
+            (GET_DELAY, "GET_DELAY", "acc = delay"),
+            (SET_RP, "SET_RP", "rp = acc"),
+        ]
+    }
+
     fn disassemble(tape: &[u8]) -> String {
         use std::fmt::Write;
         let mut out = String::new();
@@ -340,6 +369,18 @@ mod tests {
         tape
     }
 
+    #[test]
+    fn test_opcode_table() {
+        let table = Echo::opcode_table();
+        assert!(
+            table
+                .iter()
+                .any(|&(op, name, _)| op == HALT && name == "HALT")
+        );
+        assert!(table.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(table.iter().all(|&(op, _, _)| Echo::is_instruction(op)));
+    }
+
     #[test]
     fn test_empty_tape() {
         let mut tape: Vec<u8> = vec![];
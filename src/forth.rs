@@ -1,4 +1,6 @@
-use crate::substrate::Substrate;
+use crate::substrate::{
+    CLASS_ARITHMETIC, CLASS_CONTROL, CLASS_DATA, CLASS_MEMORY, CLASS_NOP, Halt, Substrate,
+};
 
 /// The Forth (primordial soup) instruction set from Section 3.1.1 of the paper.
 ///
@@ -8,6 +10,10 @@ use crate::substrate::Substrate;
 /// - `01xx xxxx` (0x40-0x7F): push low 6 bits as unsigned value
 /// - `1Xxx xxxx` (0x80-0xFF): relative jump ±([low 6 bits]+1), bit 6 = sign
 ///
+/// The paper's "+64" operations (READ64, WRITE64, COPY, RCOPY) offset by
+/// half the tape length, so they address the partner program at any
+/// program size rather than only with 64-byte programs.
+///
 /// Stack values are u8. Stack underflow returns 0 (allowing the trivial
 /// one-byte self-replicator 0x0C to work on an empty stack, per the paper).
 /// Stack overflow silently drops the push.
@@ -79,6 +85,18 @@ impl FixedStack {
 struct ForthState {
     stack: FixedStack,
     pc: usize,
+    /// Writes to tape positions below this are dropped (see `Harvard`).
+    writable_from: usize,
+}
+
+impl ForthState {
+    /// Write `val` to `tape[addr]` unless `addr` is read-only.
+    #[inline(always)]
+    fn store(&self, tape: &mut [u8], addr: usize, val: u8) {
+        if addr >= self.writable_from {
+            tape[addr] = val;
+        }
+    }
 }
 
 /// Initialize a battle context with an empty stack and the given starting PC.
@@ -86,6 +104,7 @@ fn forth_init(_tape_len: usize, start_pc: usize) -> ForthState {
     ForthState {
         stack: FixedStack::new(),
         pc: start_pc,
+        writable_from: 0,
     }
 }
 
@@ -95,6 +114,7 @@ fn forth_init(_tape_len: usize, start_pc: usize) -> ForthState {
 /// halted (PC out of bounds or backward jump underflow).
 fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
     let len = tape.len();
+    let half = len / 2;
     if state.pc >= len {
         return false;
     }
@@ -113,9 +133,9 @@ fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
                     state.stack.push(val);
                 }
                 0x01 if instr < 0x10 => {
-                    // READ64: <top> = *(<top> + 64)
+                    // READ64: <top> = *(<top> + half)
                     let top = state.stack.pop();
-                    let addr = ((top as usize).wrapping_add(64)) % len;
+                    let addr = ((top as usize).wrapping_add(half)) % len;
                     let val = tape[addr];
                     state.stack.push(val);
                 }
@@ -124,14 +144,14 @@ fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
                     let addr_val = state.stack.pop();
                     let data_val = state.stack.pop();
                     let addr = addr_val as usize % len;
-                    tape[addr] = data_val;
+                    state.store(tape, addr, data_val);
                 }
                 0x03 if instr < 0x10 => {
-                    // WRITE64: *(<top> + 64) = <top-1>; pop; pop
+                    // WRITE64: *(<top> + half) = <top-1>; pop; pop
                     let addr_val = state.stack.pop();
                     let data_val = state.stack.pop();
-                    let addr = ((addr_val as usize).wrapping_add(64)) % len;
-                    tape[addr] = data_val;
+                    let addr = ((addr_val as usize).wrapping_add(half)) % len;
+                    state.store(tape, addr, data_val);
                 }
                 0x04 if instr < 0x10 => {
                     // DUP: push <top>
@@ -146,11 +166,9 @@ fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
                     // SWAP: swap <top> and <top-1>
                     state.stack.swap_top_two();
                 }
-                0x07 if instr < 0x10 => {
+                0x07 if instr < 0x10 && state.stack.top() != 0 => {
                     // SKIPNZ: if <top> != 0: pc++
-                    if state.stack.top() != 0 {
-                        state.pc += 1;
-                    }
+                    state.pc += 1;
                 }
                 0x08 if instr < 0x10 => {
                     // INC: <top> = <top> + 1
@@ -187,18 +205,20 @@ fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
                     }
                 }
                 0x0C if instr < 0x10 => {
-                    // COPY: *(<top> + 64) = *<top>; pop
+                    // COPY: *(<top> + half) = *<top>; pop
                     let addr_val = state.stack.pop();
                     let src = addr_val as usize % len;
-                    let dst = ((addr_val as usize).wrapping_add(64)) % len;
-                    tape[dst] = tape[src];
+                    let dst = ((addr_val as usize).wrapping_add(half)) % len;
+                    let val = tape[src];
+                    state.store(tape, dst, val);
                 }
                 0x0D if instr < 0x10 => {
-                    // RCOPY: *<top> = *(<top> + 64); pop
+                    // RCOPY: *<top> = *(<top> + half); pop
                     let addr_val = state.stack.pop();
                     let dst = addr_val as usize % len;
-                    let src = ((addr_val as usize).wrapping_add(64)) % len;
-                    tape[dst] = tape[src];
+                    let src = ((addr_val as usize).wrapping_add(half)) % len;
+                    let val = tape[src];
+                    state.store(tape, dst, val);
                 }
                 _ => {
                     // No-op
@@ -231,27 +251,71 @@ fn forth_step(state: &mut ForthState, tape: &mut [u8]) -> bool {
     true
 }
 
-impl Substrate for Forth {
-    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
-        let len = tape.len();
-        if len == 0 {
-            return 0;
-        }
+/// Run a program from position 0 like `Forth::execute`, dropping every
+/// write to the first `read_only` bytes of the tape and marking each
+/// dispatched position in `visited` if given.
+pub(crate) fn execute_with_read_only_prefix(
+    tape: &mut [u8],
+    step_limit: usize,
+    read_only: usize,
+    visited: Option<&mut [bool]>,
+) -> usize {
+    run_with_read_only_prefix(tape, step_limit, read_only, visited).0
+}
 
-        let mut state = ForthState {
-            stack: FixedStack::new(),
-            pc: 0,
-        };
-        let mut steps = 0;
+/// [`execute_with_read_only_prefix`], also reporting why execution stopped.
+pub(crate) fn run_with_read_only_prefix(
+    tape: &mut [u8],
+    step_limit: usize,
+    read_only: usize,
+    mut visited: Option<&mut [bool]>,
+) -> (usize, Halt) {
+    let len = tape.len();
+    if len == 0 {
+        return (0, Halt::OffTape);
+    }
 
-        while state.pc < len && steps < step_limit {
-            steps += 1;
-            if !forth_step(&mut state, tape) {
-                break;
-            }
+    let mut state = ForthState {
+        stack: FixedStack::new(),
+        pc: 0,
+        writable_from: read_only,
+    };
+    let mut steps = 0;
+
+    while state.pc < len && steps < step_limit {
+        steps += 1;
+        if let Some(visited) = visited.as_deref_mut() {
+            visited[state.pc] = true;
         }
+        if !forth_step(&mut state, tape) {
+            return (steps, Halt::Halted);
+        }
+    }
 
-        steps
+    let halt = if state.pc >= len {
+        Halt::OffTape
+    } else {
+        Halt::StepLimit
+    };
+    (steps, halt)
+}
+
+impl Substrate for Forth {
+    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
+        execute_with_read_only_prefix(tape, step_limit, 0, None)
+    }
+
+    fn execute_status(tape: &mut [u8], step_limit: usize) -> (usize, Halt) {
+        run_with_read_only_prefix(tape, step_limit, 0, None)
+    }
+
+    fn execute_profiled(tape: &mut [u8], step_limit: usize, visited: &mut [bool]) -> Option<usize> {
+        Some(execute_with_read_only_prefix(
+            tape,
+            step_limit,
+            0,
+            Some(visited),
+        ))
     }
 
     fn execute_battle(tape: &mut [u8], program_size: usize, step_limit: usize) -> usize {
@@ -289,6 +353,20 @@ impl Substrate for Forth {
         byte <= 0x0D || byte >= 0x40
     }
 
+    fn instruction_class(byte: u8) -> u8 {
+        match byte {
+            // READ, READ64, WRITE, WRITE64 / COPY, RCOPY
+            0x00..=0x03 | 0x0C | 0x0D => CLASS_MEMORY,
+            // DUP, POP, SWAP / PUSH
+            0x04..=0x06 | 0x40..=0x7F => CLASS_DATA,
+            // SKIPNZ / relative jumps
+            0x07 | 0x80..=0xFF => CLASS_CONTROL,
+            // INC, DEC, ADD, SUB
+            0x08..=0x0B => CLASS_ARITHMETIC,
+            _ => CLASS_NOP,
+        }
+    }
+
     fn disassemble(tape: &[u8]) -> String {
         use std::fmt::Write;
         let mut out = String::new();
@@ -673,6 +751,36 @@ mod tests {
         assert_eq!(steps, 100);
     }
 
+    #[test]
+    fn test_execute_status() {
+        for (program, limit, halt) in [
+            (&[0x0F, 0xC0][..], 100, Halt::StepLimit),
+            (&[0xC0], 100, Halt::Halted),
+            (&[], 8192, Halt::OffTape),
+        ] {
+            let mut tape = make_tape(program, 128);
+            let steps = Forth::execute(&mut tape.clone(), limit);
+            assert_eq!(Forth::execute_status(&mut tape, limit), (steps, halt));
+        }
+    }
+
+    #[test]
+    fn test_instruction_classes() {
+        use crate::substrate::CLASS_NOP;
+        // A forward jump and ADD land in different classes.
+        assert_ne!(
+            Forth::instruction_class(0x80),
+            Forth::instruction_class(0x0A)
+        );
+        assert_eq!(Forth::instruction_class(0x0F), CLASS_NOP);
+        for b in 0..=255u8 {
+            assert_eq!(
+                Forth::instruction_class(b) != CLASS_NOP,
+                Forth::is_instruction(b)
+            );
+        }
+    }
+
     // --- Trivial self-replicator test ---
 
     #[test]
@@ -687,6 +795,15 @@ mod tests {
         assert_eq!(tape[64], 0x0C); // Copied itself to the second program's first byte
     }
 
+    #[test]
+    fn test_copy_targets_partner_with_32_byte_programs() {
+        // Push 5, COPY: tape[5 + 32] = tape[5] on a 64-byte tape.
+        let mut tape = make_tape(&[0x45, 0x0C, 0x0F, 0x0F, 0x0F, 0x99], 64);
+        Forth::execute(&mut tape, 8192);
+        assert_eq!(tape[37], 0x99);
+        assert_eq!(tape[5], 0x99);
+    }
+
     // --- Edge cases ---
 
     #[test]
@@ -710,6 +827,117 @@ mod proptests {
     use super::*;
     use proptest::prelude::*;
 
+    /// `Forth::execute` written as plainly as possible, to check the
+    /// production interpreter against: a `Vec` stack, where popping an empty
+    /// stack reads 0 and pushing onto a full one does nothing.
+    fn reference_execute(tape: &mut [u8], step_limit: usize) -> usize {
+        fn pop(stack: &mut Vec<u8>) -> u8 {
+            stack.pop().unwrap_or(0)
+        }
+        fn push(stack: &mut Vec<u8>, val: u8) {
+            if stack.len() < MAX_STACK {
+                stack.push(val);
+            }
+        }
+        fn top(stack: &[u8]) -> u8 {
+            stack.last().copied().unwrap_or(0)
+        }
+
+        let len = tape.len();
+        let half = len / 2;
+        let mut stack = Vec::new();
+        let mut pc = 0;
+        let mut steps = 0;
+        while pc < len && steps < step_limit {
+            steps += 1;
+            let instr = tape[pc];
+            let mut next = pc + 1;
+            match instr {
+                // READ, READ64
+                0x00 | 0x01 => {
+                    let addr = pop(&mut stack) as usize + if instr == 0x01 { half } else { 0 };
+                    push(&mut stack, tape[addr % len]);
+                }
+                // WRITE, WRITE64
+                0x02 | 0x03 => {
+                    let addr = pop(&mut stack) as usize + if instr == 0x03 { half } else { 0 };
+                    tape[addr % len] = pop(&mut stack);
+                }
+                // DUP
+                0x04 => {
+                    let val = top(&stack);
+                    push(&mut stack, val);
+                }
+                // POP
+                0x05 => {
+                    pop(&mut stack);
+                }
+                // SWAP, only when there are two values to swap
+                0x06 => {
+                    if stack.len() >= 2 {
+                        let n = stack.len();
+                        stack.swap(n - 1, n - 2);
+                    }
+                }
+                // SKIPNZ
+                0x07 => {
+                    if top(&stack) != 0 {
+                        next += 1;
+                    }
+                }
+                // INC, DEC
+                0x08 => {
+                    let val = pop(&mut stack).wrapping_add(1);
+                    push(&mut stack, val);
+                }
+                0x09 => {
+                    let val = pop(&mut stack).wrapping_sub(1);
+                    push(&mut stack, val);
+                }
+                // ADD, SUB: the top value is the left operand.
+                0x0A => {
+                    let (a, b) = (pop(&mut stack), pop(&mut stack));
+                    push(&mut stack, a.wrapping_add(b));
+                }
+                0x0B => {
+                    let (a, b) = (pop(&mut stack), pop(&mut stack));
+                    push(&mut stack, a.wrapping_sub(b));
+                }
+                // COPY, RCOPY
+                0x0C => {
+                    let addr = pop(&mut stack) as usize;
+                    tape[(addr + half) % len] = tape[addr % len];
+                }
+                0x0D => {
+                    let addr = pop(&mut stack) as usize;
+                    tape[addr % len] = tape[(addr + half) % len];
+                }
+                0x0E..=0x3F => {}
+                0x40..=0x7F => push(&mut stack, instr & 0x3F),
+                // Jumps: bit 6 picks the direction, and a jump before the
+                // start of the tape halts.
+                0x80..=0xFF => {
+                    let offset = (instr & 0x3F) as usize + 1;
+                    if instr & 0x40 == 0 {
+                        next = pc + offset;
+                    } else if offset > pc {
+                        return steps;
+                    } else {
+                        next = pc - offset;
+                    }
+                }
+            }
+            pc = next;
+        }
+        steps
+    }
+
+    /// Bytes weighted toward the fixed opcodes and pushes, which random
+    /// bytes rarely hit.
+    fn forth_byte() -> impl Strategy<Value = u8> {
+        prop_oneof![0x00u8..0x0E, 0x40u8..0x80, any::<u8>()]
+    }
+
     proptest! {
         #[test]
         fn random_programs_never_panic(tape_data in prop::collection::vec(any::<u8>(), 1..256)) {
@@ -735,5 +963,18 @@ mod proptests {
             Forth::execute(&mut tape, 8192);
             prop_assert_eq!(tape.len(), original_len);
         }
+
+        #[test]
+        fn execute_matches_reference(
+            tape_data in prop::collection::vec(forth_byte(), 1..256),
+            limit in 1usize..2000
+        ) {
+            let mut expected = tape_data.clone();
+            let expected_steps = reference_execute(&mut expected, limit);
+            let mut tape = tape_data;
+            let steps = Forth::execute(&mut tape, limit);
+            prop_assert_eq!(steps, expected_steps);
+            prop_assert_eq!(tape, expected);
+        }
     }
 }
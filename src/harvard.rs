@@ -0,0 +1,136 @@
+use crate::forth::{self, Forth};
+use crate::substrate::{Halt, Substrate};
+
+/// Forth with a Harvard split between code and data.
+///
+/// The first half of the tape (the executing program) is read-only code;
+/// the second half (its partner) is writable data. Execution starts at
+/// position 0 as in Forth and can still run off the end of the code into
+/// the data half, but WRITE, WRITE64, COPY, and RCOPY targeting the code
+/// half are ignored. A program can copy itself into its partner, yet can
+/// never rewrite itself — so the self-modification that drives much of the
+/// von Neumann substrates' dynamics is ruled out.
+pub struct Harvard;
+
+impl Substrate for Harvard {
+    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
+        forth::execute_with_read_only_prefix(tape, step_limit, tape.len() / 2, None)
+    }
+
+    fn execute_status(tape: &mut [u8], step_limit: usize) -> (usize, Halt) {
+        forth::run_with_read_only_prefix(tape, step_limit, tape.len() / 2, None)
+    }
+
+    fn execute_profiled(tape: &mut [u8], step_limit: usize, visited: &mut [bool]) -> Option<usize> {
+        let read_only = tape.len() / 2;
+        Some(forth::execute_with_read_only_prefix(
+            tape,
+            step_limit,
+            read_only,
+            Some(visited),
+        ))
+    }
+
+    fn is_instruction(byte: u8) -> bool {
+        Forth::is_instruction(byte)
+    }
+
+    fn instruction_class(byte: u8) -> u8 {
+        Forth::instruction_class(byte)
+    }
+
+    fn disassemble(tape: &[u8]) -> String {
+        Forth::disassemble(tape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_into_code_is_ignored() {
+        // PUSH 42, PUSH 10, WRITE: *10 = 42, but 10 is in the code half.
+        let mut tape = vec![0x0Fu8; 128];
+        tape[..3].copy_from_slice(&[0x40 | 42, 0x40 | 10, 0x02]);
+        let code = tape[..64].to_vec();
+        Harvard::execute(&mut tape, 8192);
+        assert_eq!(&tape[..64], &code[..]);
+
+        // The same program under plain Forth rewrites itself.
+        let mut tape = vec![0x0Fu8; 128];
+        tape[..3].copy_from_slice(&[0x40 | 42, 0x40 | 10, 0x02]);
+        Forth::execute(&mut tape, 8192);
+        assert_eq!(tape[10], 42);
+    }
+
+    #[test]
+    fn test_write_into_data_is_kept() {
+        // PUSH 42, PUSH 10, WRITE64: *(10 + 64) = 42.
+        let mut tape = vec![0x0Fu8; 128];
+        tape[..3].copy_from_slice(&[0x40 | 42, 0x40 | 10, 0x03]);
+        Harvard::execute(&mut tape, 8192);
+        assert_eq!(tape[74], 42);
+    }
+
+    #[test]
+    fn test_self_replicator_still_copies_out() {
+        let mut tape = vec![0u8; 128];
+        tape[0] = 0x0C;
+        Harvard::execute(&mut tape, 8192);
+        assert_eq!(tape[64], 0x0C);
+        assert_eq!(tape[0], 0x0C);
+    }
+
+    #[test]
+    fn test_rcopy_into_code_is_ignored() {
+        // PUSH 5, RCOPY: *5 = *(5 + 64).
+        let mut tape = vec![0x0Fu8; 128];
+        tape[..2].copy_from_slice(&[0x40 | 5, 0x0D]);
+        tape[69] = 0x99;
+        Harvard::execute(&mut tape, 8192);
+        assert_eq!(tape[5], 0x0F);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn random_programs_never_panic(tape_data in prop::collection::vec(any::<u8>(), 1..256)) {
+            let mut tape = tape_data;
+            let steps = Harvard::execute(&mut tape, 8192);
+            prop_assert!(steps <= 8192);
+        }
+
+        #[test]
+        fn random_programs_respect_step_limit(
+            tape_data in prop::collection::vec(any::<u8>(), 1..256),
+            limit in 1usize..1000
+        ) {
+            let mut tape = tape_data;
+            let steps = Harvard::execute(&mut tape, limit);
+            prop_assert!(steps <= limit);
+        }
+
+        #[test]
+        fn output_tape_same_length(tape_data in prop::collection::vec(any::<u8>(), 1..256)) {
+            let original_len = tape_data.len();
+            let mut tape = tape_data;
+            Harvard::execute(&mut tape, 8192);
+            prop_assert_eq!(tape.len(), original_len);
+        }
+
+        #[test]
+        fn code_half_never_changes(tape_data in prop::collection::vec(any::<u8>(), 2..256)) {
+            let mut tape = tape_data;
+            let half = tape.len() / 2;
+            let code = tape[..half].to_vec();
+            Harvard::execute(&mut tape, 8192);
+            prop_assert_eq!(&tape[..half], &code[..]);
+        }
+    }
+}
@@ -0,0 +1,2020 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use rand::rngs::SmallRng;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::log;
+use crate::metrics::{byte_frequency_histogram, parasite_fraction, takeover_epoch};
+use crate::substrate::{Halt, Substrate};
+use crate::surface::{
+    CellSite, ExecuteFn, GeodesicMetric, InteractionGraph, NO_PARTNER, NeighborRadius, SoupSurface,
+    SoupSurfaceConfig, SurfaceMesh, SurfaceSpec,
+};
+use crate::viz::{
+    ColorMode, EpochMetrics, SUBSTRATES, SubstrateKind, accumulate_glow, compute_metrics,
+    compute_metrics_surface, fill_colors_for_mode, grid_square_rgb,
+};
+
+/// File in a dump directory recording how to split dumps into programs.
+const MANIFEST: &str = "manifest.txt";
+
+/// Most genotypes `--catalog` prints before summarizing the rest.
+pub const CATALOG_LIMIT: usize = 32;
+
+/// HOE below which a headless run reports a replicator takeover. Random
+/// soups sit near 1.0; a population of copies compresses far below half.
+const TAKEOVER_HOE: f64 = 0.5;
+
+/// Which metric columns the headless CSV output contains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MetricsLevel {
+    /// `epoch,hoe` only.
+    #[default]
+    Basic,
+    /// `epoch,hoe,unique,zeros,chi2,parasites,active,consensus,quines`
+    /// (plus `flatness` with the `spectral` feature) followed by 256
+    /// byte-histogram columns.
+    Full,
+}
+
+/// How `--final-histogram` prints the final population's byte counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistogramFormat {
+    /// One bar per byte value that occurs, scaled to the terminal width;
+    /// instruction bytes are drawn with `#`, the rest with `.`.
+    Ascii,
+    /// `byte,count` with one row for each of the 256 byte values.
+    Csv,
+}
+
+/// Every parameter that determines a headless run, resolved from the command
+/// line. Saved as JSON alongside a run and loaded back, it reproduces the
+/// run exactly; output options (metrics level, dump directory) are not part
+/// of it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// Substrate name as accepted by `--substrate`.
+    pub substrate: String,
+    pub seed: u64,
+    pub epochs: usize,
+    pub surface: SurfaceSpec,
+    /// Synthetic neighbor table that replaces `surface` when set.
+    #[serde(default)]
+    pub graph: Option<InteractionGraph>,
+    pub neighbor_radius: Option<NeighborRadius>,
+    pub max_neighbors: Option<usize>,
+    #[serde(default)]
+    pub geodesic_metric: GeodesicMetric,
+    /// Whether programs live on faces or vertices.
+    #[serde(default)]
+    pub cells: CellSite,
+    /// Metrics interval; also paces adaptive mutation, so it affects the run.
+    pub metrics_interval: usize,
+    /// Programs metrics are estimated from instead of the whole population;
+    /// feeds adaptive mutation too.
+    #[serde(default)]
+    pub metrics_sample: Option<usize>,
+    pub config: SoupSurfaceConfig,
+    /// Programs written over single cells after the soup is seeded.
+    #[serde(default)]
+    pub inject: Vec<Injection>,
+    /// Commands from `--script`, applied as their epochs end.
+    #[serde(default)]
+    pub script: Vec<ScriptCommand>,
+    /// Substrates assigned across the cells, from `--mixed`.
+    #[serde(default)]
+    pub mixed: Option<Mixture>,
+}
+
+impl RunManifest {
+    /// Write the manifest to `path` as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize manifest: {e}"))?;
+        fs::write(path, json + "\n").map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Read a manifest written by [`RunManifest::save`].
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        serde_json::from_str(&json).map_err(|e| format!("Invalid manifest {}: {e}", path.display()))
+    }
+
+    /// Build the surface, neighbor table, and seeded soup this manifest
+    /// describes.
+    pub fn build_soup(&self) -> Result<SoupSurface, String> {
+        let mesh = match &self.graph {
+            Some(graph) => graph.build()?,
+            None => {
+                let mut mesh = self.surface.build()?;
+                mesh.set_geodesic_metric(self.geodesic_metric)?;
+                if !self.surface.builds_neighbors() {
+                    mesh.set_cell_site(self.cells)?;
+                    let radius = self.neighbor_radius.map(|r| r.resolve(&mesh));
+                    mesh.compute_neighbors(radius, self.max_neighbors);
+                } else if self.cells != CellSite::Face {
+                    return Err(format!(
+                        "--cells vertex isn't available on a {}",
+                        self.surface.label()
+                    ));
+                }
+                mesh
+            }
+        };
+        let mut soup = SoupSurface::new(mesh, self.config.clone(), self.seed);
+        if let Some(mixture) = &self.mixed {
+            mixture.apply(&mut soup)?;
+        }
+        for injection in &self.inject {
+            injection.apply(&mut soup)?;
+        }
+        Ok(soup)
+    }
+}
+
+/// A `--seed` value: a number, or `random` for one drawn from the OS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedArg {
+    Fixed(u64),
+    Random,
+}
+
+impl SeedArg {
+    /// Parse a seed number or `random`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s == "random" {
+            return Ok(SeedArg::Random);
+        }
+        s.parse()
+            .map(SeedArg::Fixed)
+            .map_err(|e| format!("Invalid seed '{s}': {e} (expected a number or random)"))
+    }
+
+    /// The seed to run with. A random seed is drawn from the OS and
+    /// reported, so the run can be repeated with `--seed N`.
+    pub fn resolve(self) -> u64 {
+        match self {
+            SeedArg::Fixed(seed) => seed,
+            SeedArg::Random => {
+                let seed = rand::rngs::OsRng.next_u64();
+                log::status!("Seed: {seed} (pass --seed {seed} to reproduce this run)");
+                seed
+            }
+        }
+    }
+}
+
+/// Weighted substrates for a mixed soup, from `--mixed bff:0.7,forth:0.3`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mixture(pub Vec<(String, f64)>);
+
+impl Mixture {
+    /// Parse comma-separated `SUBSTRATE:WEIGHT` entries.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let entries = s
+            .split(',')
+            .map(|entry| {
+                let (name, weight) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("Mixture entry '{entry}' is not SUBSTRATE:WEIGHT"))?;
+                let kind = SubstrateKind::parse(name)?;
+                if kind == SubstrateKind::Hybrid {
+                    return Err("The hybrid substrate can't be part of a mixture".into());
+                }
+                let weight: f64 = weight
+                    .parse()
+                    .map_err(|e| format!("Invalid weight '{weight}' in '{entry}': {e}"))?;
+                if !(weight.is_finite() && weight >= 0.0) {
+                    return Err(format!("Weight {weight} in '{entry}' must be non-negative"));
+                }
+                Ok((kind.name().to_string(), weight))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if entries.iter().all(|&(_, weight)| weight == 0.0) {
+            return Err(format!("Mixture '{s}' has no positive weight"));
+        }
+        Ok(Mixture(entries))
+    }
+
+    /// The substrate listed first, which runs everything an ensemble
+    /// doesn't dispatch per cell.
+    pub fn primary(&self) -> &str {
+        &self.0[0].0
+    }
+
+    /// Assign the soup's cells these substrates; see
+    /// [`SoupSurface::set_ensemble`].
+    pub fn apply(&self, soup: &mut SoupSurface) -> Result<(), String> {
+        let program_size = soup.config.program_size;
+        let mut executors = Vec::with_capacity(self.0.len());
+        for (name, _) in &self.0 {
+            let kind = SubstrateKind::parse(name)?;
+            if kind == SubstrateKind::Hybrid {
+                return Err("The hybrid substrate can't be part of a mixture".into());
+            }
+            let execute: ExecuteFn = crate::with_base_substrate!(kind, S => {
+                S::validate_program_size(program_size)?;
+                S::execute_status
+            });
+            executors.push(execute);
+        }
+        let weights: Vec<f64> = self.0.iter().map(|&(_, weight)| weight).collect();
+        soup.set_ensemble(executors, &weights);
+        Ok(())
+    }
+}
+
+/// Exact bytes placed into one cell at startup, from `--inject CELL:HEX`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Injection {
+    pub cell: usize,
+    pub program: Vec<u8>,
+}
+
+impl Injection {
+    /// Parse "CELL:HEX".
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (cell, hex) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Injection '{s}' is not CELL:HEX"))?;
+        let cell = cell
+            .parse()
+            .map_err(|e| format!("Invalid cell '{cell}' in '{s}': {e}"))?;
+        Ok(Self {
+            cell,
+            program: parse_hex(hex)?,
+        })
+    }
+
+    /// Overwrite the cell's program with these bytes, zero-padded or
+    /// truncated to the soup's program size.
+    pub fn apply(&self, soup: &mut SoupSurface) -> Result<(), String> {
+        let cells = soup.programs.len();
+        let program = soup.programs.get_mut(self.cell).ok_or_else(|| {
+            format!(
+                "Cannot inject into cell {}: cells are 0..{cells}",
+                self.cell
+            )
+        })?;
+        let n = self.program.len().min(program.len());
+        program.fill(0);
+        program[..n].copy_from_slice(&self.program[..n]);
+        Ok(())
+    }
+}
+
+/// One line of a `--script` file: `action` runs once `epoch` has finished.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScriptCommand {
+    pub epoch: usize,
+    pub action: ScriptAction,
+}
+
+/// What a [`ScriptCommand`] does to the soup.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScriptAction {
+    /// `set-mutation-rate,RATE`: replace the per-byte mutation rate.
+    SetMutationRate(f64),
+    /// `extinction,FRACTION`: refill that fraction of cells at random.
+    Extinction(f64),
+    /// `inject,CELL:HEX`: as `--inject`, mid-run.
+    Inject(Injection),
+    /// `dump,DIR`: save the population to DIR as `--dump` would.
+    Dump(PathBuf),
+}
+
+impl ScriptCommand {
+    /// Parse one `epoch,command,args` line.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut fields = line.splitn(3, ',').map(str::trim);
+        let epoch = fields.next().unwrap_or_default();
+        let epoch = epoch
+            .parse()
+            .map_err(|e| format!("Invalid epoch '{epoch}' in '{line}': {e}"))?;
+        let command = fields.next().unwrap_or_default();
+        let args = fields.next().unwrap_or_default();
+        let fraction = |what: &str| -> Result<f64, String> {
+            match args.parse::<f64>() {
+                Ok(f) if (0.0..=1.0).contains(&f) => Ok(f),
+                _ => Err(format!("{what} '{args}' in '{line}' is not in [0, 1]")),
+            }
+        };
+        let action = match command {
+            "set-mutation-rate" => ScriptAction::SetMutationRate(fraction("Mutation rate")?),
+            "extinction" => ScriptAction::Extinction(fraction("Extinction fraction")?),
+            "inject" => ScriptAction::Inject(Injection::parse(args)?),
+            "dump" if !args.is_empty() => ScriptAction::Dump(PathBuf::from(args)),
+            "dump" => return Err(format!("dump in '{line}' needs a directory")),
+            _ => {
+                return Err(format!(
+                    "Unknown script command '{command}' in '{line}': expected \
+                     set-mutation-rate, extinction, inject, or dump"
+                ));
+            }
+        };
+        Ok(Self { epoch, action })
+    }
+
+    /// Parse a script: one command per line, skipping blank lines, `#`
+    /// comments, and an optional `epoch,command,args` header.
+    pub fn parse_script(text: &str) -> Result<Vec<Self>, String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter(|line| !line.starts_with("epoch,"))
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// Read and parse the script at `path`.
+    pub fn load_script(path: &Path) -> Result<Vec<Self>, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse_script(&text)
+    }
+
+    /// Apply this command to `soup`, which has just finished `self.epoch`.
+    pub fn apply(&self, soup: &mut SoupSurface) -> Result<(), String> {
+        match &self.action {
+            ScriptAction::SetMutationRate(rate) => soup.set_mutation_rate(*rate),
+            ScriptAction::Extinction(fraction) => {
+                let wiped = soup.extinction(*fraction);
+                log::status!("epoch {}: extinction wiped {wiped} cells", self.epoch);
+            }
+            ScriptAction::Inject(injection) => injection.apply(soup)?,
+            ScriptAction::Dump(dir) => {
+                let mut pop_buf = Vec::new();
+                soup.population_bytes_into(&mut pop_buf);
+                write_dump(dir, soup, self.epoch, &pop_buf)
+                    .map_err(|e| format!("Failed to dump to {}: {e}", dir.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// First bytes of every interaction log: a tag and a format version.
+pub const INTERACTION_LOG_MAGIC: &[u8; 8] = b"CLPAIRS\x01";
+
+/// Where a headless run's pairings go, or come from.
+///
+/// An interaction log is [`INTERACTION_LOG_MAGIC`] followed by one record
+/// per epoch: the epoch as a little-endian `u64` and the pair count as a
+/// little-endian `u32`, then each pair's first and second cell as
+/// little-endian `u32`s, with `u32::MAX` standing for [`NO_PARTNER`].
+pub enum InteractionTrace {
+    /// Append every epoch's pairs to a log as they are drawn.
+    Record(Box<dyn Write>),
+    /// Run every epoch on the pairs a log recorded for it.
+    Replay(Box<dyn Read>),
+}
+
+impl InteractionTrace {
+    /// Start a new log at `path` to record into.
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let mut out = io::BufWriter::new(
+            fs::File::create(path)
+                .map_err(|e| format!("Failed to create {}: {e}", path.display()))?,
+        );
+        out.write_all(INTERACTION_LOG_MAGIC)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        Ok(Self::Record(Box::new(out)))
+    }
+
+    /// Open the log at `path` to replay.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut input = io::BufReader::new(
+            fs::File::open(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+        );
+        let mut magic = [0; INTERACTION_LOG_MAGIC.len()];
+        match input.read_exact(&mut magic) {
+            Ok(()) if &magic == INTERACTION_LOG_MAGIC => Ok(Self::Replay(Box::new(input))),
+            _ => Err(format!("{} is not an interaction log", path.display())),
+        }
+    }
+
+    /// Run `soup`'s next epoch, recording its pairs or replaying the
+    /// logged ones.
+    pub fn run_epoch<S: Substrate + Sync>(&mut self, soup: &mut SoupSurface) -> io::Result<()> {
+        match self {
+            Self::Record(out) => {
+                soup.run_epoch::<S>();
+                write_pairs(out, soup.epoch(), soup.pairs())
+            }
+            Self::Replay(input) => {
+                let epoch = soup.epoch() + 1;
+                let pairs = read_pairs(input, epoch, soup.programs.len())?;
+                soup.run_epoch_with_pairs::<S>(&pairs)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+        }
+    }
+
+    /// Flush a log being recorded.
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Record(out) => out.flush(),
+            Self::Replay(_) => Ok(()),
+        }
+    }
+}
+
+/// Append one epoch's record to an interaction log.
+fn write_pairs(out: &mut impl Write, epoch: usize, pairs: &[(usize, usize)]) -> io::Result<()> {
+    let cell = |cell: usize| -> io::Result<[u8; 4]> {
+        let cell = match cell {
+            NO_PARTNER => u32::MAX,
+            cell => u32::try_from(cell)
+                .ok()
+                .filter(|&cell| cell != u32::MAX)
+                .ok_or_else(|| io::Error::other(format!("Cell {cell} is too large to log")))?,
+        };
+        Ok(cell.to_le_bytes())
+    };
+    let count = u32::try_from(pairs.len()).map_err(io::Error::other)?;
+    out.write_all(&(epoch as u64).to_le_bytes())?;
+    out.write_all(&count.to_le_bytes())?;
+    for &(first, second) in pairs {
+        out.write_all(&cell(first)?)?;
+        out.write_all(&cell(second)?)?;
+    }
+    Ok(())
+}
+
+/// Read the next record of an interaction log, which must be `epoch`'s and
+/// hold no more pairs than a soup of `num_cells` cells can form.
+fn read_pairs(
+    input: &mut impl Read,
+    epoch: usize,
+    num_cells: usize,
+) -> io::Result<Vec<(usize, usize)>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut header = [0; 12];
+    input.read_exact(&mut header).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => {
+            invalid(format!("Interaction log ends before epoch {epoch}"))
+        }
+        _ => e,
+    })?;
+    let logged = u64::from_le_bytes(header[..8].try_into().unwrap());
+    if logged != epoch as u64 {
+        return Err(invalid(format!(
+            "Interaction log has epoch {logged} where epoch {epoch} was expected"
+        )));
+    }
+    let count = u32::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+    if count > num_cells {
+        return Err(invalid(format!(
+            "Interaction log has {count} pairs in epoch {epoch}, more than the soup's {num_cells} cells"
+        )));
+    }
+    let mut bytes = vec![0; count * 8];
+    input.read_exact(&mut bytes)?;
+    let cell = |bytes: &[u8]| match u32::from_le_bytes(bytes.try_into().unwrap()) {
+        u32::MAX => NO_PARTNER,
+        cell => cell as usize,
+    };
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|pair| (cell(&pair[..4]), cell(&pair[4..])))
+        .collect())
+}
+
+/// CSV header line (without trailing newline) for the given metrics level.
+pub fn csv_header(level: MetricsLevel) -> String {
+    let mut header = String::from("epoch,hoe");
+    if level == MetricsLevel::Full {
+        header.push_str(",unique,zeros,chi2,parasites,active,consensus,quines");
+        if cfg!(feature = "spectral") {
+            header.push_str(",flatness");
+        }
+        for b in 0..256 {
+            header.push_str(&format!(",h{b}"));
+        }
+    }
+    header
+}
+
+/// Format one metrics snapshot as a CSV row matching `csv_header(level)`.
+pub fn csv_row(metrics: &EpochMetrics, level: MetricsLevel) -> String {
+    let mut row = format!("{},{:.6}", metrics.epoch, metrics.hoe);
+    if level == MetricsLevel::Full {
+        row.push_str(&format!(
+            ",{},{},{:.3},{:.6}",
+            metrics.unique_count, metrics.zero_count, metrics.chi_square, metrics.parasite_fraction
+        ));
+        // Left empty for substrates that can't report executed positions.
+        row.push(',');
+        if let Some(active) = metrics.active_fraction {
+            row.push_str(&format!("{active:.4}"));
+        }
+        row.push_str(&format!(
+            ",{:.3},{}",
+            metrics.consensus_distance, metrics.quines
+        ));
+        if let Some(flatness) = metrics.spectral_flatness {
+            row.push_str(&format!(",{flatness:.6}"));
+        }
+        for count in &metrics.byte_histogram {
+            row.push_str(&format!(",{count}"));
+        }
+    }
+    row
+}
+
+/// Write `entries` as CSV: the full [`csv_header`], then a [`csv_row`] for
+/// each, in order.
+pub fn write_metrics_csv(entries: &[EpochMetrics], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", csv_header(MetricsLevel::Full))?;
+    for metrics in entries {
+        writeln!(out, "{}", csv_row(metrics, MetricsLevel::Full))?;
+    }
+    Ok(())
+}
+
+/// Epochs between metrics rows unless a run asks otherwise.
+pub const DEFAULT_METRICS_INTERVAL: usize = 25;
+
+/// How [`run_simulation`] reports on a run, and what it does alongside it.
+/// The default writes basic metrics every [`DEFAULT_METRICS_INTERVAL`]
+/// epochs and nothing else.
+pub struct RunOptions<'a> {
+    /// Metrics are emitted for epoch 0, every this many epochs, and the
+    /// final epoch; each emission also drives the soup's adaptive mutation
+    /// controller, if configured.
+    pub metrics_interval: usize,
+    pub level: MetricsLevel,
+    /// Estimate metrics from this many programs, as drawn by
+    /// [`sampled_metrics`].
+    pub sample: Option<usize>,
+    /// Also save the population at each metrics epoch here, for later
+    /// `analyze_dumps`.
+    pub dump_dir: Option<&'a Path>,
+    /// Record each epoch's pairs to, or replay them from, an interaction log.
+    pub trace: Option<&'a mut InteractionTrace>,
+    /// Draw the soup on stderr at each metrics epoch.
+    pub ascii: Option<&'a mut AsciiView>,
+    /// Commands run as their epoch ends, before that epoch's metrics.
+    pub script: &'a [ScriptCommand],
+}
+
+impl Default for RunOptions<'_> {
+    fn default() -> Self {
+        Self {
+            metrics_interval: DEFAULT_METRICS_INTERVAL,
+            level: MetricsLevel::Basic,
+            sample: None,
+            dump_dir: None,
+            trace: None,
+            ascii: None,
+            script: &[],
+        }
+    }
+}
+
+/// Run `epochs` epochs of `soup` without a GUI, writing CSV metrics to `out`
+/// as `options` asks. Progress, and the run's [`takeover_epoch`] at the end,
+/// are reported on stderr so they never mix with the CSV.
+pub fn run_simulation<S: Substrate + Sync>(
+    soup: &mut SoupSurface,
+    epochs: usize,
+    options: RunOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let hoe_series = run_collecting::<S>(soup, epochs, options, out)?;
+    match takeover_epoch(&hoe_series, TAKEOVER_HOE) {
+        Some(epoch) => log::status!("takeover epoch: {epoch} (HOE < {TAKEOVER_HOE})"),
+        None => log::status!("takeover epoch: none (HOE never stayed < {TAKEOVER_HOE})"),
+    }
+    Ok(())
+}
+
+/// The loop behind [`run_simulation`], returning the `(epoch, hoe)` of every
+/// epoch it wrote metrics for instead of reporting the takeover epoch.
+pub fn run_collecting<S: Substrate + Sync>(
+    soup: &mut SoupSurface,
+    epochs: usize,
+    options: RunOptions,
+    out: &mut impl Write,
+) -> io::Result<Vec<(usize, f64)>> {
+    let RunOptions {
+        metrics_interval,
+        level,
+        sample,
+        dump_dir,
+        mut trace,
+        mut ascii,
+        script,
+    } = options;
+    let mut pop_buf = Vec::new();
+    let run_script = |soup: &mut SoupSurface, epoch: usize| -> io::Result<()> {
+        script
+            .iter()
+            .filter(|command| command.epoch == epoch)
+            .try_for_each(|command| command.apply(soup).map_err(io::Error::other))
+    };
+
+    // Only the full CSV has a parasites column.
+    soup.record_copy_events(level == MetricsLevel::Full);
+    run_script(soup, 0)?;
+    writeln!(out, "{}", csv_header(level))?;
+    let initial = sampled_metrics::<S>(soup, 0, sample, &mut pop_buf);
+    writeln!(out, "{}", csv_row(&initial, level))?;
+    let mut hoe_series = vec![(0, initial.hoe)];
+    if let Some(dir) = dump_dir {
+        write_dump(dir, soup, 0, &pop_buf)?;
+    }
+    if let Some(ascii) = ascii.as_mut() {
+        ascii.draw::<S>(soup, 0, &mut io::stderr().lock())?;
+    }
+
+    for epoch in 1..=epochs {
+        if let Some(ascii) = ascii.as_mut() {
+            ascii.begin_epoch(soup);
+        }
+        match trace.as_mut() {
+            Some(trace) => trace.run_epoch::<S>(soup)?,
+            None => soup.run_epoch::<S>(),
+        }
+        if let Some(ascii) = ascii.as_mut() {
+            ascii.end_epoch(soup);
+        }
+        soup.mutate();
+        if let Some(draws) = soup.rng_draws() {
+            log::status!("epoch {epoch} rng draws: {draws}");
+        }
+        run_script(soup, epoch)?;
+
+        if epoch.is_multiple_of(metrics_interval) || epoch == epochs {
+            let metrics = sampled_metrics::<S>(soup, epoch, sample, &mut pop_buf);
+            writeln!(out, "{}", csv_row(&metrics, level))?;
+            // A sampled unique count is out of the sample, not the soup.
+            let counted = sample.map_or(soup.programs.len(), |k| k.min(soup.programs.len()));
+            soup.adapt_mutation(metrics.unique_count as f64 / counted.max(1) as f64);
+            hoe_series.push((epoch, metrics.hoe));
+            if let Some(dir) = dump_dir {
+                write_dump(dir, soup, epoch, &pop_buf)?;
+            }
+            if let Some(ascii) = ascii.as_mut() {
+                ascii.draw::<S>(soup, epoch, &mut io::stderr().lock())?;
+            }
+            log::progress(&format!("epoch {epoch}/{epochs}"));
+        }
+    }
+    log::end_progress();
+    if let Some(trace) = trace {
+        trace.finish()?;
+    }
+    Ok(hoe_series)
+}
+
+/// Most cells `--ascii` will draw; bigger soups don't fit a terminal.
+pub const ASCII_MAX_CELLS: usize = 4096;
+
+/// Characters from dark to bright; every cell gets a visible one.
+const ASCII_RAMP: &[u8] = b".:-=+*#%@";
+
+/// Cells per line when a soup isn't a grid and is drawn as a list.
+const ASCII_LIST_WIDTH: usize = 64;
+
+/// Terminal rendering of a small soup for `--ascii`: each cell becomes a
+/// character whose brightness and ANSI color follow the viewer's color for
+/// it, and each frame overwrites the last one.
+pub struct AsciiView {
+    mode: ColorMode,
+    /// `(width, height)` of a flat grid, drawn one character per quad.
+    grid: Option<(usize, usize)>,
+    glow: Vec<f32>,
+    before_epoch: Vec<Vec<u8>>,
+    colors: Vec<u8>,
+    /// Lines the previous frame took, to move back over.
+    drawn_lines: usize,
+}
+
+impl AsciiView {
+    /// A view of `num_cells` cells in `mode`, laid out as `grid` when the
+    /// soup sits on the faces of a flat grid.
+    pub fn new(
+        mode: ColorMode,
+        grid: Option<(usize, usize)>,
+        num_cells: usize,
+    ) -> Result<Self, String> {
+        let shown = grid.map_or(num_cells, |(width, height)| width * height);
+        if shown > ASCII_MAX_CELLS {
+            return Err(format!(
+                "--ascii draws at most {ASCII_MAX_CELLS} cells, this soup has {shown}"
+            ));
+        }
+        Ok(Self {
+            mode,
+            grid,
+            glow: vec![0.0; num_cells],
+            before_epoch: Vec::new(),
+            colors: Vec::new(),
+            drawn_lines: 0,
+        })
+    }
+
+    /// Note the programs an epoch starts from (temporal glow only).
+    fn begin_epoch(&mut self, soup: &SoupSurface) {
+        if self.mode == ColorMode::TemporalGlow {
+            self.before_epoch.clone_from(&soup.programs);
+        }
+    }
+
+    /// Fold the epoch that just ran into the temporal glow.
+    fn end_epoch(&mut self, soup: &SoupSurface) {
+        if self.mode == ColorMode::TemporalGlow {
+            accumulate_glow(&mut self.glow, &self.before_epoch, &soup.programs);
+        }
+    }
+
+    /// The current frame: one line per grid row, top row first, or the
+    /// cells in order wrapped at [`ASCII_LIST_WIDTH`].
+    pub fn frame<S: Substrate>(&mut self, soup: &SoupSurface) -> Vec<String> {
+        fill_colors_for_mode::<S>(self.mode, soup, &self.glow, &mut self.colors);
+        let colors = &self.colors;
+        match self.grid {
+            // Rows count up from the bottom of the grid; print the top first.
+            Some((width, height)) => (0..height)
+                .rev()
+                .map(|j| {
+                    (0..width)
+                        .map(|i| ascii_cell(grid_square_rgb(colors, width, i, j)))
+                        .collect()
+                })
+                .collect(),
+            None => colors
+                .chunks(4)
+                .collect::<Vec<_>>()
+                .chunks(ASCII_LIST_WIDTH)
+                .map(|cells| {
+                    cells
+                        .iter()
+                        .map(|rgba| ascii_cell([rgba[0], rgba[1], rgba[2]]))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Draw the current frame with an `epoch` caption, over the previous
+    /// frame if there was one.
+    pub fn draw<S: Substrate>(
+        &mut self,
+        soup: &SoupSurface,
+        epoch: usize,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let lines = self.frame::<S>(soup);
+        if self.drawn_lines > 0 {
+            write!(out, "\r\x1b[{}A", self.drawn_lines)?;
+        }
+        writeln!(out, "\x1b[Kepoch {epoch} ({})", self.mode.name())?;
+        for line in &lines {
+            writeln!(out, "\x1b[K{line}\x1b[0m")?;
+        }
+        self.drawn_lines = lines.len() + 1;
+        out.flush()
+    }
+}
+
+/// One cell in truecolor, as a character as bright as the color.
+fn ascii_cell([r, g, b]: [u8; 3]) -> String {
+    let luma = (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000;
+    let level = luma as usize * ASCII_RAMP.len() / 256;
+    format!("\x1b[38;2;{r};{g};{b}m{}", ASCII_RAMP[level] as char)
+}
+
+/// Mixed into the soup's seed for `sampled_metrics`' draws so they don't
+/// replay the soup's own.
+const METRICS_SAMPLE_STREAM: u64 = 0xD1B5_4A32_D192_ED03;
+
+/// Metrics for `soup` at `epoch`, over `sample` of its programs or over all
+/// of them when `None`.
+///
+/// A sample is an estimate: `sample` distinct cells drawn afresh each call
+/// from the soup's seed and `epoch` (so a rerun draws the same cells), kept
+/// in cell order. Every metric but the parasite fraction, which is cheap
+/// and always counted over the whole population, sees only those programs.
+/// Either way `pop_buf` is left holding the whole population's bytes.
+pub fn sampled_metrics<S: Substrate>(
+    soup: &mut SoupSurface,
+    epoch: usize,
+    sample: Option<usize>,
+    pop_buf: &mut Vec<u8>,
+) -> EpochMetrics {
+    let Some(sample) = sample else {
+        return compute_metrics_surface::<S>(soup, epoch, pop_buf);
+    };
+    let total = soup.programs.len();
+    let mut rng = SmallRng::seed_from_u64(soup.seed() ^ epoch as u64 ^ METRICS_SAMPLE_STREAM);
+    let mut cells = rand::seq::index::sample(&mut rng, total, sample.min(total)).into_vec();
+    cells.sort_unstable();
+    let programs: Vec<Vec<u8>> = cells
+        .iter()
+        .map(|&cell| soup.programs[cell].clone())
+        .collect();
+    let sampled_bytes = programs.concat();
+    soup.population_bytes_into(pop_buf);
+    let copy_events = soup.take_copy_events();
+    let step_limit = soup.config.step_limit;
+    let mut metrics = compute_metrics::<S>(&programs, &sampled_bytes, &[], epoch, step_limit);
+    metrics.parasite_fraction = parasite_fraction(&copy_events, total);
+    metrics
+}
+
+/// Pairings per second over `epochs` epochs of a soup for `S` on `mesh`,
+/// mutation included.
+///
+/// With `dump`, once timing is done the final population is written there
+/// (`num_cells * program_size` bytes) and its HOE and unique program count
+/// go to stderr, so a config whose programs all halt at once can be caught
+/// before its numbers are trusted.
+pub fn run_benchmark<S: Substrate + Sync>(
+    mesh: &SurfaceMesh,
+    config: &SoupSurfaceConfig,
+    seed: u64,
+    epochs: usize,
+    dump: Option<&Path>,
+) -> io::Result<f64> {
+    let mut soup = SoupSurface::new(mesh.clone(), config.clone(), seed);
+    let mut interactions = 0;
+    let start = std::time::Instant::now();
+    for _ in 0..epochs {
+        soup.run_epoch::<S>();
+        soup.mutate();
+        interactions += soup.pair_count();
+    }
+    let rate = interactions as f64 / start.elapsed().as_secs_f64().max(f64::MIN_POSITIVE);
+    if let Some(path) = dump {
+        let mut pop_buf = Vec::new();
+        let metrics = compute_metrics_surface::<S>(&mut soup, epochs, &mut pop_buf);
+        fs::write(path, &pop_buf)?;
+        log::status!(
+            "final population in {}: HOE {:.4}, {} unique programs",
+            path.display(),
+            metrics.hoe,
+            metrics.unique_count
+        );
+    }
+    Ok(rate)
+}
+
+/// [`run_benchmark`] for every registered base substrate, fastest first, as
+/// `(name, interactions per second)`. Each runs at `step_limit`, or its own
+/// recommended limit for the program size when `None`. With `dump`, each
+/// substrate's final population is saved to `dump` with `.NAME` appended.
+pub fn benchmark_all(
+    mesh: &SurfaceMesh,
+    config: &SoupSurfaceConfig,
+    step_limit: Option<usize>,
+    seed: u64,
+    epochs: usize,
+    dump: Option<&Path>,
+) -> Result<Vec<(&'static str, f64)>, String> {
+    let mut rows = Vec::new();
+    for &(name, kind) in SUBSTRATES {
+        if kind == SubstrateKind::Hybrid {
+            continue;
+        }
+        let rate = crate::with_base_substrate!(kind, S => {
+            S::validate_program_size(config.program_size)?;
+            let config = SoupSurfaceConfig {
+                step_limit: step_limit
+                    .unwrap_or_else(|| S::recommended_step_limit(config.program_size)),
+                ..config.clone()
+            };
+            log::status!("Benchmarking {name}...");
+            let dump = dump.map(|path| {
+                let mut path = path.as_os_str().to_owned();
+                path.push(format!(".{name}"));
+                PathBuf::from(path)
+            });
+            run_benchmark::<S>(mesh, &config, seed, epochs, dump.as_deref())
+                .map_err(|e| format!("Failed to write benchmark dump: {e}"))?
+        });
+        rows.push((name, rate));
+    }
+    rows.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Ok(rows)
+}
+
+/// Write `rows` from [`benchmark_all`] as an aligned table.
+pub fn write_benchmark_table(rows: &[(&str, f64)], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{:<10} {:>16}", "substrate", "interactions/s")?;
+    for (name, rate) in rows {
+        writeln!(out, "{name:<10} {rate:>16.0}")?;
+    }
+    Ok(())
+}
+
+/// How one substrate ran a program under [`cross_run`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossRun {
+    pub substrate: &'static str,
+    pub steps: usize,
+    pub halt: Halt,
+    /// Hash of the whole tape after execution.
+    pub tape_hash: u64,
+}
+
+/// Run `program`, zero-padded to `program_size` and followed by an all-zero
+/// partner, under every registered base substrate. Each runs at
+/// `step_limit`, or its own recommended limit for the program size when
+/// `None`.
+pub fn cross_run(
+    program: &[u8],
+    program_size: usize,
+    step_limit: Option<usize>,
+) -> Result<Vec<CrossRun>, String> {
+    if program.len() > program_size {
+        return Err(format!(
+            "Program is {} bytes, longer than the {program_size}-byte program size",
+            program.len()
+        ));
+    }
+    let mut tape = vec![0u8; 2 * program_size];
+    tape[..program.len()].copy_from_slice(program);
+    let mut rows = Vec::new();
+    for &(name, kind) in SUBSTRATES {
+        if kind == SubstrateKind::Hybrid {
+            continue;
+        }
+        let row = crate::with_base_substrate!(kind, S => {
+            S::validate_program_size(program_size)?;
+            let limit = step_limit.unwrap_or_else(|| S::recommended_step_limit(program_size));
+            let mut after = tape.clone();
+            let (steps, halt) = S::execute_status(&mut after, limit);
+            let mut hasher = DefaultHasher::new();
+            after.hash(&mut hasher);
+            CrossRun {
+                substrate: name,
+                steps,
+                halt,
+                tape_hash: hasher.finish(),
+            }
+        });
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Write `rows` from [`cross_run`] as an aligned table.
+pub fn write_cross_run_table(rows: &[CrossRun], out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<10} {:>8} {:<10} tape hash",
+        "substrate", "steps", "status"
+    )?;
+    for row in rows {
+        let status = match row.halt {
+            Halt::Halted => "halted",
+            Halt::OffTape => "off-tape",
+            Halt::StepLimit => "step-limit",
+        };
+        writeln!(
+            out,
+            "{:<10} {:>8} {status:<10} {:016x}",
+            row.substrate, row.steps, row.tape_hash
+        )?;
+    }
+    Ok(())
+}
+
+/// Write `S`'s [`Substrate::opcode_table`] as aligned `OPCODE MNEMONIC
+/// DESCRIPTION` rows, opcodes in hex, or say that `S` has none.
+pub fn write_opcode_table<S: Substrate>(out: &mut impl Write) -> io::Result<()> {
+    let table = S::opcode_table();
+    if table.is_empty() {
+        return writeln!(
+            out,
+            "No opcode table: this substrate's encoding isn't one opcode per byte"
+        );
+    }
+    let width = table
+        .iter()
+        .map(|(_, mnemonic, _)| mnemonic.len())
+        .max()
+        .unwrap_or(0);
+    for (opcode, mnemonic, description) in table {
+        writeln!(out, "{opcode:02X}  {mnemonic:<width$}  {description}")?;
+    }
+    writeln!(out, "Every other byte is a no-op.")
+}
+
+/// Write every distinct program occurring at least `min_count` times in
+/// `programs`, most common first: a `# COUNT copies, HASH` line, where
+/// HASH is a short hash of the bytes, then its `S` disassembly. At most
+/// [`CATALOG_LIMIT`] genotypes are written; a final line counts the rest.
+pub fn write_catalog<S: Substrate>(
+    programs: &[Vec<u8>],
+    min_count: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for p in programs {
+        *counts.entry(p).or_default() += 1;
+    }
+    let mut genotypes: Vec<(&[u8], usize)> = counts
+        .into_iter()
+        .filter(|&(_, count)| count >= min_count)
+        .collect();
+    genotypes.sort_by(|(a, na), (b, nb)| nb.cmp(na).then_with(|| a.cmp(b)));
+    for (i, &(program, count)) in genotypes.iter().take(CATALOG_LIMIT).enumerate() {
+        let mut hasher = DefaultHasher::new();
+        program.hash(&mut hasher);
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "# {count} copies, {:08x}", hasher.finish() as u32)?;
+        write!(out, "{}", S::disassemble(program))?;
+    }
+    if genotypes.len() > CATALOG_LIMIT {
+        writeln!(
+            out,
+            "\n# {} more genotypes with at least {min_count} copies not shown",
+            genotypes.len() - CATALOG_LIMIT
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the byte histogram of `programs` as `format`, with ASCII bars
+/// fitted to `width` columns. Bytes `S` treats as instructions are marked.
+pub fn write_histogram<S: Substrate>(
+    programs: &[Vec<u8>],
+    format: HistogramFormat,
+    width: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let hist = byte_frequency_histogram(programs);
+    match format {
+        HistogramFormat::Csv => {
+            writeln!(out, "byte,count")?;
+            for (byte, count) in hist.iter().enumerate() {
+                writeln!(out, "{byte},{count}")?;
+            }
+        }
+        HistogramFormat::Ascii => {
+            let max = hist.iter().copied().max().unwrap_or(0).max(1);
+            let count_width = max.to_string().len();
+            // "xx c " + count + " " before the bar.
+            let bar_width = width.saturating_sub(6 + count_width).max(1);
+            for (byte, &count) in hist.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                let byte = byte as u8;
+                let glyph = if byte.is_ascii_graphic() {
+                    byte as char
+                } else {
+                    ' '
+                };
+                let fill = if S::is_instruction(byte) { "#" } else { "." };
+                let len = (count * bar_width).div_ceil(max);
+                writeln!(
+                    out,
+                    "{byte:02x} {glyph} {count:>count_width$} {}",
+                    fill.repeat(len)
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write two trajectories from [`run_collecting`], run with the same
+/// schedule, side by side as `epoch,hoe_a,hoe_b` CSV.
+pub fn write_comparison(
+    a: &[(usize, f64)],
+    b: &[(usize, f64)],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "epoch,hoe_a,hoe_b")?;
+    for (&(epoch, hoe_a), &(epoch_b, hoe_b)) in a.iter().zip(b) {
+        debug_assert_eq!(epoch, epoch_b, "trajectories use different schedules");
+        writeln!(out, "{epoch},{hoe_a:.6},{hoe_b:.6}")?;
+    }
+    Ok(())
+}
+
+/// A spatial invasion experiment: every cell starts as the `resident`
+/// program except a geodesic patch seeded with the `invader`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invasion {
+    /// Instruction set name, as accepted by `--substrate`.
+    pub substrate: String,
+    pub resident: Vec<u8>,
+    pub invader: Vec<u8>,
+    /// Geodesic radius of the invader patch, in mesh units.
+    pub patch_radius: f32,
+}
+
+impl Invasion {
+    /// Parse "SUBSTRATE:HEX_A:HEX_B:PATCH_RADIUS", where HEX_A is the
+    /// resident program and HEX_B the invader.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let [substrate, resident, invader, radius] = s.split(':').collect::<Vec<_>>()[..] else {
+            return Err(format!(
+                "Invasion '{s}' is not SUBSTRATE:HEX_A:HEX_B:PATCH_RADIUS"
+            ));
+        };
+        let patch_radius = match radius.parse::<f32>() {
+            Ok(r) if r >= 0.0 => r,
+            _ => {
+                return Err(format!(
+                    "Invalid patch radius '{radius}': expected a non-negative distance"
+                ));
+            }
+        };
+        Ok(Self {
+            substrate: substrate.to_string(),
+            resident: parse_hex(resident)?,
+            invader: parse_hex(invader)?,
+            patch_radius,
+        })
+    }
+
+    /// Fill `soup` with the resident, then overwrite the patch around
+    /// `center` with the invader. Programs shorter than the soup's program
+    /// size are padded with zeros. Returns the number of invaded cells.
+    pub fn seed(&self, soup: &mut SoupSurface, center: usize) -> Result<usize, String> {
+        let cells = soup.mesh.num_cells();
+        if center >= cells {
+            return Err(format!(
+                "Invasion center {center} is not a cell (0..{cells})"
+            ));
+        }
+        let resident = self.padded(&self.resident, soup.config.program_size)?;
+        let invader = self.padded(&self.invader, soup.config.program_size)?;
+        for program in &mut soup.programs {
+            program.copy_from_slice(&resident);
+        }
+        let patch = soup.mesh.geodesic_patch(center, self.patch_radius);
+        for &cell in &patch {
+            soup.programs[cell].copy_from_slice(&invader);
+        }
+        Ok(patch.len())
+    }
+
+    /// Fractions of `programs` that are exactly the resident and exactly
+    /// the invader, both padded to the programs' size.
+    pub fn genotype_fractions(&self, programs: &[Vec<u8>]) -> (f64, f64) {
+        let Some(size) = programs.first().map(Vec::len) else {
+            return (0.0, 0.0);
+        };
+        let (Ok(resident), Ok(invader)) = (
+            self.padded(&self.resident, size),
+            self.padded(&self.invader, size),
+        ) else {
+            return (0.0, 0.0);
+        };
+        let count = |genotype: &[u8]| programs.iter().filter(|p| **p == genotype).count();
+        let total = programs.len() as f64;
+        (
+            count(&resident) as f64 / total,
+            count(&invader) as f64 / total,
+        )
+    }
+
+    fn padded(&self, program: &[u8], size: usize) -> Result<Vec<u8>, String> {
+        if program.len() > size {
+            return Err(format!(
+                "Invasion program is {} bytes, longer than the {size}-byte program size",
+                program.len()
+            ));
+        }
+        let mut padded = program.to_vec();
+        padded.resize(size, 0);
+        Ok(padded)
+    }
+}
+
+/// Decode a program written as pairs of hex digits, e.g. `0c07`.
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("Hex program '{hex}' has an odd number of digits"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("Invalid hex program '{hex}'"))
+        })
+        .collect()
+}
+
+/// Run an `invasion`-seeded `soup` for `epochs` epochs, writing the
+/// resident and invader fractions as `epoch,resident,invader` CSV on the
+/// same schedule as [`run_simulation`].
+pub fn run_invasion<S: Substrate + Sync>(
+    soup: &mut SoupSurface,
+    invasion: &Invasion,
+    epochs: usize,
+    metrics_interval: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "epoch,resident,invader")?;
+    let write_row = |out: &mut dyn Write, soup: &SoupSurface, epoch: usize| {
+        let (resident, invader) = invasion.genotype_fractions(&soup.programs);
+        writeln!(out, "{epoch},{resident:.6},{invader:.6}")
+    };
+    write_row(out, soup, 0)?;
+    for epoch in 1..=epochs {
+        soup.run_epoch::<S>();
+        soup.mutate();
+
+        if epoch.is_multiple_of(metrics_interval) || epoch == epochs {
+            write_row(out, soup, epoch)?;
+            log::progress(&format!("epoch {epoch}/{epochs}"));
+        }
+    }
+    log::end_progress();
+    Ok(())
+}
+
+fn dump_path(dir: &Path, epoch: usize) -> PathBuf {
+    dir.join(format!("epoch-{epoch}.bin"))
+}
+
+/// Save `population` (from `soup` at `epoch`) into the dump directory
+/// `dir`, creating it and (re)writing its manifest.
+fn write_dump(dir: &Path, soup: &SoupSurface, epoch: usize, population: &[u8]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let manifest = format!(
+        "program_size={}\nstep_limit={}\n",
+        soup.config.program_size, soup.config.step_limit
+    );
+    fs::write(dir.join(MANIFEST), manifest)?;
+    fs::write(dump_path(dir, epoch), population)
+}
+
+/// Recompute metrics over the `epoch-*.bin` population dumps in `dir` as
+/// substrate `S`, writing one CSV row per dump (in epoch order) to `out`.
+///
+/// Dumps hold no copy events, so the `parasites` column is always 0.
+pub fn analyze_dumps<S: Substrate>(
+    dir: &Path,
+    level: MetricsLevel,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let manifest_path = dir.join(MANIFEST);
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read {}: {e}", manifest_path.display()))?;
+    let program_size = manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("program_size="))
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("{} has no valid program_size", manifest_path.display()))?;
+    // Dumps written before the step limit was recorded used the default.
+    let step_limit = manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("step_limit="))
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or_else(|| S::recommended_step_limit(program_size));
+
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    let mut dumps: Vec<(usize, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let epoch = name
+                .strip_prefix("epoch-")?
+                .strip_suffix(".bin")?
+                .parse()
+                .ok()?;
+            Some((epoch, path))
+        })
+        .collect();
+    dumps.sort_unstable_by_key(|(epoch, _)| *epoch);
+
+    let write_err = |e: io::Error| format!("Failed to write CSV: {e}");
+    writeln!(out, "{}", csv_header(level)).map_err(write_err)?;
+    for (epoch, path) in dumps {
+        let population =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if population.len() % program_size != 0 {
+            return Err(format!(
+                "{} is {} bytes, not a multiple of program_size {program_size}",
+                path.display(),
+                population.len()
+            ));
+        }
+        let programs: Vec<Vec<u8>> = population
+            .chunks(program_size)
+            .map(<[u8]>::to_vec)
+            .collect();
+        let metrics = compute_metrics::<S>(&programs, &population, &[], epoch, step_limit);
+        writeln!(out, "{}", csv_row(&metrics, level)).map_err(write_err)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bff::Bff;
+    use crate::echo::Echo;
+    use crate::forth::Forth;
+    use crate::surface::{AdaptiveMutation, InteractionMode, RngMode};
+
+    fn small_soup() -> SoupSurface {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 256,
+            mutation_rate: 0.001,
+            interaction_mode: InteractionMode::Normal,
+            ..SoupSurfaceConfig::default()
+        };
+        SoupSurface::new(mesh, config, 42)
+    }
+
+    #[test]
+    fn test_replayed_interactions_reproduce_population() {
+        let soup = || {
+            let mut soup = small_soup();
+            soup.config.rng_mode = RngMode::PerEpoch;
+            soup.config.self_pair_fraction = 0.1;
+            soup
+        };
+        let path = std::env::temp_dir().join("complife_test_interactions.bin");
+        let run = |soup: &mut SoupSurface, epochs: usize, mut trace: InteractionTrace| {
+            let mut out = Vec::new();
+            run_simulation::<Bff>(
+                soup,
+                epochs,
+                RunOptions {
+                    metrics_interval: 2,
+                    level: MetricsLevel::Full,
+                    trace: Some(&mut trace),
+                    ..RunOptions::default()
+                },
+                &mut out,
+            )
+            .map(|()| out)
+        };
+
+        let mut recorded = soup();
+        let trace = InteractionTrace::create(&path).unwrap();
+        let recorded_csv = run(&mut recorded, 6, trace).unwrap();
+        // The pairs come from the log, not from the soup's own pairing.
+        let mut replayed = soup();
+        replayed.config.self_pair_fraction = 0.0;
+        let trace = InteractionTrace::open(&path).unwrap();
+        let replayed_csv = run(&mut replayed, 6, trace).unwrap();
+        assert_eq!(replayed.programs, recorded.programs);
+        assert_eq!(replayed_csv, recorded_csv);
+
+        let trace = InteractionTrace::open(&path).unwrap();
+        let err = run(&mut soup(), 7, trace).unwrap_err();
+        assert!(err.to_string().contains("ends before epoch 7"), "{err}");
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_read_pairs_rejects_oversized_count() {
+        let mut record = 1u64.to_le_bytes().to_vec();
+        record.extend(u32::MAX.to_le_bytes());
+        let err = read_pairs(&mut record.as_slice(), 1, 64).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string().contains("more than the soup's 64 cells"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_full_sample_matches_full_metrics() {
+        let mut full = small_soup();
+        let mut sampled = small_soup();
+        for soup in [&mut full, &mut sampled] {
+            soup.record_copy_events(true);
+            for _ in 0..3 {
+                soup.run_epoch::<Bff>();
+                soup.mutate();
+            }
+        }
+        let total = full.programs.len();
+        let (mut full_buf, mut sampled_buf) = (Vec::new(), Vec::new());
+        let expected = compute_metrics_surface::<Bff>(&mut full, 3, &mut full_buf);
+        let actual = sampled_metrics::<Bff>(&mut sampled, 3, Some(total), &mut sampled_buf);
+        assert_eq!(
+            csv_row(&actual, MetricsLevel::Full),
+            csv_row(&expected, MetricsLevel::Full)
+        );
+        assert_eq!(sampled_buf, full_buf);
+
+        // A smaller sample is an estimate, but the same one on every call.
+        let a = sampled_metrics::<Bff>(&mut sampled, 3, Some(total / 2), &mut sampled_buf);
+        let b = sampled_metrics::<Bff>(&mut sampled, 3, Some(total / 2), &mut sampled_buf);
+        assert_eq!(
+            csv_row(&a, MetricsLevel::Full),
+            csv_row(&b, MetricsLevel::Full)
+        );
+    }
+
+    #[test]
+    fn test_adaptive_mutation_reads_sampled_diversity() {
+        // A fresh random soup is fully diverse, and so is a one-cell sample.
+        let rate_after = |sample| {
+            let mut soup = small_soup();
+            soup.config.adaptive_mutation = Some(AdaptiveMutation::default());
+            run_simulation::<Bff>(
+                &mut soup,
+                1,
+                RunOptions {
+                    metrics_interval: 1,
+                    sample,
+                    ..RunOptions::default()
+                },
+                &mut Vec::new(),
+            )
+            .unwrap();
+            soup.mutation_rate()
+        };
+        assert_eq!(rate_after(None), 0.001);
+        assert_eq!(rate_after(Some(1)), 0.001);
+    }
+
+    #[test]
+    fn test_basic_header() {
+        assert_eq!(csv_header(MetricsLevel::Basic), "epoch,hoe");
+    }
+
+    #[test]
+    fn test_csv_row_formats_metrics() {
+        let mut byte_histogram = [0; 256];
+        byte_histogram[0] = 3;
+        byte_histogram[255] = 1;
+        let metrics = EpochMetrics {
+            epoch: 12,
+            hoe: 1.5,
+            unique_count: 4,
+            zero_count: 3,
+            byte_histogram,
+            chi_square: 2.25,
+            parasite_fraction: 0.125,
+            spectral_flatness: None,
+            active_fraction: None,
+            consensus_distance: 0.5,
+            quines: 1,
+            positional_mi: Vec::new(),
+            epochs_per_second: 0.0,
+        };
+        assert_eq!(csv_row(&metrics, MetricsLevel::Basic), "12,1.500000");
+        let full = csv_row(&metrics, MetricsLevel::Full);
+        let expected = format!(
+            "12,1.500000,4,3,2.250,0.125000,,0.500,1,3{},1",
+            ",0".repeat(254)
+        );
+        assert_eq!(full, expected);
+
+        let mut out = Vec::new();
+        write_metrics_csv(&[metrics.clone(), metrics], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, [csv_header(MetricsLevel::Full), full.clone(), full]);
+    }
+
+    #[test]
+    fn test_csv_histogram_counts_every_byte() {
+        let mut soup = small_soup();
+        run_simulation::<Bff>(
+            &mut soup,
+            4,
+            RunOptions {
+                metrics_interval: 2,
+                ..RunOptions::default()
+            },
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        write_histogram::<Bff>(&soup.programs, HistogramFormat::Csv, 80, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("byte,count"));
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(rows.len(), 256);
+        let total: usize = rows
+            .iter()
+            .map(|row| row.split_once(',').unwrap().1.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total, soup.programs.len() * soup.config.program_size);
+    }
+
+    #[test]
+    fn test_ascii_histogram_fits_width() {
+        let programs = vec![vec![b'[', b'[', b'['], vec![0, b'x']];
+        let mut out = Vec::new();
+        write_histogram::<Bff>(&programs, HistogramFormat::Ascii, 40, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= 40));
+        assert!(lines[1].starts_with("5b [ 3 ###"), "{}", lines[1]);
+        assert!(lines[2].starts_with("78 x 1 ."), "{}", lines[2]);
+    }
+
+    #[test]
+    fn test_catalog_lists_dominant_genotypes() {
+        let mut programs = vec![b"[.]".to_vec(); 5];
+        programs.extend(vec![b"+-".to_vec(); 3]);
+        programs.push(b"<".to_vec());
+        let mut out = Vec::new();
+        write_catalog::<Bff>(&programs, 2, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let headers: Vec<&str> = text.lines().filter(|l| l.starts_with('#')).collect();
+        assert_eq!(headers.len(), 2, "{text}");
+        assert!(headers[0].starts_with("# 5 copies, "), "{text}");
+        assert!(headers[1].starts_with("# 3 copies, "), "{text}");
+        assert!(text.contains("LOOP_START") && text.contains("INC"));
+        assert!(!text.contains("HEAD0--"));
+    }
+
+    #[test]
+    fn test_cross_run_tapes_differ() {
+        let program = parse_hex("5b2e5d").unwrap();
+        let rows = cross_run(&program, 16, Some(256)).unwrap();
+        assert!(rows.iter().any(|row| row.substrate == "bff"));
+        assert!(rows.iter().all(|row| row.steps <= 256));
+        let hashes: std::collections::HashSet<u64> = rows.iter().map(|row| row.tape_hash).collect();
+        assert!(hashes.len() >= 2);
+        assert!(cross_run(&[0; 17], 16, None).is_err());
+
+        let mut out = Vec::new();
+        write_cross_run_table(&rows, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap().lines().count(),
+            rows.len() + 1
+        );
+    }
+
+    #[test]
+    fn test_opcode_table_rows() {
+        let mut out = Vec::new();
+        write_opcode_table::<Bff>(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), Bff::opcode_table().len() + 1);
+        assert!(text.starts_with("2B  INC         Increment"), "{text}");
+
+        let mut out = Vec::new();
+        write_opcode_table::<Forth>(&mut out).unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .starts_with("No opcode table")
+        );
+
+        // Tables list exactly the instruction bytes, in order.
+        fn opcodes<S: Substrate>() -> (Vec<u8>, Vec<u8>) {
+            let listed = S::opcode_table().iter().map(|&(op, _, _)| op).collect();
+            (
+                listed,
+                (0..=255).filter(|&b| S::is_instruction(b)).collect(),
+            )
+        }
+        for (listed, instructions) in [
+            opcodes::<Bff>(),
+            opcodes::<Echo>(),
+            opcodes::<crate::qop::Qop>(),
+            opcodes::<crate::tierra::Tierra>(),
+        ] {
+            assert_eq!(listed, instructions);
+        }
+    }
+
+    #[test]
+    fn test_catalog_truncates() {
+        let programs: Vec<Vec<u8>> = (0..CATALOG_LIMIT as u8 + 3).map(|b| vec![b]).collect();
+        let mut out = Vec::new();
+        write_catalog::<Bff>(&programs, 1, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches(" copies, ").count(), CATALOG_LIMIT);
+        assert!(text.ends_with("# 3 more genotypes with at least 1 copies not shown\n"));
+    }
+
+    #[test]
+    fn test_random_seed_is_printed_and_reproduces() {
+        let _lock = log::test_lock();
+        let printed = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = printed.clone();
+        let (a, b) = log::with_logger(
+            move |line| sink.borrow_mut().push(line.to_string()),
+            || (SeedArg::Random.resolve(), SeedArg::Random.resolve()),
+        );
+        assert_ne!(a, b);
+        let lines = printed.borrow();
+        assert_eq!(lines.len(), 2);
+        let shown = lines[0]
+            .strip_prefix("Seed: ")
+            .and_then(|rest| rest.split(' ').next())
+            .unwrap();
+        let again = SeedArg::parse(shown).unwrap();
+        assert_eq!(again, SeedArg::Fixed(a));
+
+        let run = |seed: u64| {
+            let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+            mesh.compute_neighbors(None, None);
+            let mut soup = SoupSurface::new(mesh, SoupSurfaceConfig::default(), seed);
+            for _ in 0..3 {
+                soup.run_epoch::<Bff>();
+                soup.mutate();
+            }
+            soup.programs
+        };
+        assert_eq!(run(a), run(again.resolve()));
+        assert_ne!(run(a), run(b));
+        assert!(SeedArg::parse("lucky").is_err());
+    }
+
+    #[test]
+    fn test_benchmark_all_has_a_row_per_substrate() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            ..SoupSurfaceConfig::default()
+        };
+        let rows = benchmark_all(&mesh, &config, Some(128), 1, 2, None).unwrap();
+        assert_eq!(rows.len(), SUBSTRATES.len() - 1);
+        assert!(rows.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!(rows.iter().all(|&(_, rate)| rate > 0.0));
+
+        let mut out = Vec::new();
+        write_benchmark_table(&rows, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), rows.len() + 1);
+        assert!(text.lines().any(|line| line.starts_with("bff ")));
+    }
+
+    #[test]
+    fn test_benchmark_dump_round_trips() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 128,
+            ..SoupSurfaceConfig::default()
+        };
+        let path = std::env::temp_dir().join("complife_test_benchmark_dump.bin");
+        let rate = run_benchmark::<Bff>(&mesh, &config, 7, 3, Some(&path)).unwrap();
+        assert!(rate > 0.0);
+        let dumped = fs::read(&path).unwrap();
+        assert_eq!(dumped.len(), mesh.num_cells() * config.program_size);
+
+        // The dump is the population an untimed run of the same soup ends with.
+        let mut soup = SoupSurface::new(mesh, config, 7);
+        for _ in 0..3 {
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+        }
+        let mut expected = Vec::new();
+        soup.population_bytes_into(&mut expected);
+        assert_eq!(dumped, expected);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_full_header_column_count() {
+        let header = csv_header(MetricsLevel::Full);
+        let columns: Vec<&str> = header.split(',').collect();
+        // epoch + (hoe, unique, zeros, chi2, parasites, active, consensus,
+        // quines[, flatness]) + 256 histogram bins.
+        let scalars = if cfg!(feature = "spectral") { 9 } else { 8 };
+        assert_eq!(columns.len(), 1 + scalars + 256);
+        assert_eq!(
+            &columns[..9],
+            &[
+                "epoch",
+                "hoe",
+                "unique",
+                "zeros",
+                "chi2",
+                "parasites",
+                "active",
+                "consensus",
+                "quines"
+            ]
+        );
+        if cfg!(feature = "spectral") {
+            assert_eq!(columns[9], "flatness");
+        }
+        assert_eq!(columns[1 + scalars], "h0");
+        assert_eq!(columns[scalars + 256], "h255");
+    }
+
+    #[test]
+    fn test_rows_match_header_width() {
+        for level in [MetricsLevel::Basic, MetricsLevel::Full] {
+            let mut soup = small_soup();
+            let mut out = Vec::new();
+            run_simulation::<Bff>(
+                &mut soup,
+                4,
+                RunOptions {
+                    metrics_interval: 2,
+                    level,
+                    ..RunOptions::default()
+                },
+                &mut out,
+            )
+            .unwrap();
+            let text = String::from_utf8(out).unwrap();
+            let mut lines = text.lines();
+            let width = lines.next().unwrap().split(',').count();
+            let rows: Vec<&str> = lines.collect();
+            // Epochs 0, 2, 4.
+            assert_eq!(rows.len(), 3);
+            for row in rows {
+                assert_eq!(row.split(',').count(), width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_analyze_synthetic_dumps() {
+        let dir = std::env::temp_dir().join("complife_test_analyze_synthetic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MANIFEST), "program_size=4\n").unwrap();
+        fs::write(dir.join("epoch-10.bin"), [1, 1, 1, 1, 1, 1, 1, 1]).unwrap();
+        fs::write(dir.join("epoch-5.bin"), [0, 0, 0, 0, 1, 2, 3, 4]).unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let mut out = Vec::new();
+        analyze_dumps::<Bff>(&dir, MetricsLevel::Full, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let rows: Vec<Vec<&str>> = text
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').collect())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        // epoch, unique, zeros
+        assert_eq!((rows[0][0], rows[0][2], rows[0][3]), ("5", "2", "4"));
+        assert_eq!((rows[1][0], rows[1][2], rows[1][3]), ("10", "1", "0"));
+    }
+
+    #[test]
+    fn test_analyze_reproduces_simulation_csv() {
+        let dir = std::env::temp_dir().join("complife_test_analyze_roundtrip");
+        let _ = fs::remove_dir_all(&dir);
+        let mut soup = small_soup();
+        let mut simulated = Vec::new();
+        run_simulation::<Bff>(
+            &mut soup,
+            4,
+            RunOptions {
+                metrics_interval: 2,
+                level: MetricsLevel::Full,
+                dump_dir: Some(&dir),
+                ..RunOptions::default()
+            },
+            &mut simulated,
+        )
+        .unwrap();
+
+        let mut analyzed = Vec::new();
+        analyze_dumps::<Bff>(&dir, MetricsLevel::Full, &mut analyzed).unwrap();
+        assert_eq!(analyzed, simulated);
+    }
+
+    #[test]
+    fn test_mixture_parse() {
+        let mixture = Mixture::parse("BFF:0.7,forth:0.3").unwrap();
+        assert_eq!(mixture.0, [("bff".into(), 0.7), ("forth".into(), 0.3)]);
+        assert_eq!(mixture.primary(), "bff");
+        assert!(Mixture::parse("bff").is_err());
+        assert!(Mixture::parse("bff:-1").is_err());
+        assert!(Mixture::parse("bff:0,forth:0").is_err());
+        assert!(Mixture::parse("hybrid:1").is_err());
+    }
+
+    #[test]
+    fn test_manifest_reproduces_run() {
+        let manifest = RunManifest {
+            substrate: "bff".to_string(),
+            seed: 7,
+            epochs: 4,
+            surface: SurfaceSpec::Torus { major: 8, minor: 4 },
+            graph: None,
+            neighbor_radius: None,
+            max_neighbors: Some(6),
+            geodesic_metric: GeodesicMetric::Parametric,
+            cells: CellSite::Face,
+            metrics_interval: 2,
+            metrics_sample: None,
+            config: SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                ..SoupSurfaceConfig::default()
+            },
+            inject: vec![Injection::parse("3:0c").unwrap()],
+            script: ScriptCommand::parse_script("2,extinction,0.25").unwrap(),
+            mixed: Some(Mixture::parse("bff:0.5,forth:0.5").unwrap()),
+        };
+        let path = std::env::temp_dir().join("complife_test_manifest.json");
+        manifest.save(&path).unwrap();
+        let loaded = RunManifest::load(&path).unwrap();
+        assert_eq!(loaded, manifest);
+
+        let run = |manifest: &RunManifest| {
+            let mut soup = manifest.build_soup().unwrap();
+            let mut out = Vec::new();
+            run_simulation::<Bff>(
+                &mut soup,
+                manifest.epochs,
+                RunOptions {
+                    metrics_interval: manifest.metrics_interval,
+                    level: MetricsLevel::Full,
+                    script: &manifest.script,
+                    ..RunOptions::default()
+                },
+                &mut out,
+            )
+            .unwrap();
+            out
+        };
+        assert_eq!(run(&loaded), run(&manifest));
+    }
+
+    #[test]
+    fn test_compare_bff_and_echo() {
+        let mut bff = small_soup();
+        let mut echo = small_soup();
+        let options = || RunOptions {
+            metrics_interval: 2,
+            ..RunOptions::default()
+        };
+        let a = run_collecting::<Bff>(&mut bff, 4, options(), &mut io::sink()).unwrap();
+        let b = run_collecting::<Echo>(&mut echo, 4, options(), &mut io::sink()).unwrap();
+        let mut out = Vec::new();
+        write_comparison(&a, &b, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("epoch,hoe_a,hoe_b"));
+        let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+        assert_eq!(rows.len(), 3);
+        for (row, epoch) in rows.iter().zip(["0", "2", "4"]) {
+            assert_eq!(row.len(), 3);
+            assert_eq!(row[0], epoch);
+            assert!(row[1..].iter().all(|hoe| hoe.parse::<f64>().is_ok()));
+        }
+        // Same seed and surface: the populations start identical.
+        assert_eq!(rows[0][1], rows[0][2]);
+    }
+
+    #[test]
+    fn test_invasion_starts_with_patch_fraction() {
+        let invasion = Invasion::parse("bff:5b2c:0c0d0e:0.8").unwrap();
+        assert_eq!(invasion.resident, [0x5b, 0x2c]);
+        assert_eq!(invasion.invader, [0x0c, 0x0d, 0x0e]);
+
+        let mut soup = small_soup();
+        let patch = invasion.seed(&mut soup, 3).unwrap();
+        assert!(patch > 1 && patch < soup.programs.len());
+
+        let mut out = Vec::new();
+        run_invasion::<Bff>(&mut soup, &invasion, 2, 1, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("epoch,resident,invader"));
+        let first: Vec<f64> = lines
+            .next()
+            .unwrap()
+            .split(',')
+            .map(|v| v.parse().unwrap())
+            .collect();
+        let total = soup.programs.len() as f64;
+        assert_eq!(first[0], 0.0);
+        assert!((first[2] - patch as f64 / total).abs() < 1e-6);
+        assert!((first[1] + first[2] - 1.0).abs() < 1e-6);
+        assert_eq!(lines.count(), 2);
+    }
+
+    #[test]
+    fn test_inject_overwrites_one_cell() {
+        let random = small_soup();
+        let mut soup = small_soup();
+        let injection = Injection::parse("0:0c5b2c").unwrap();
+        injection.apply(&mut soup).unwrap();
+        let mut expected = vec![0u8; 16];
+        expected[..3].copy_from_slice(&[0x0c, 0x5b, 0x2c]);
+        assert_eq!(soup.programs[0], expected);
+        assert_eq!(soup.programs[1..], random.programs[1..]);
+
+        let long = Injection::parse(&format!("1:{}", "ab".repeat(20))).unwrap();
+        long.apply(&mut soup).unwrap();
+        assert_eq!(soup.programs[1], [0xab; 16]);
+
+        assert!(Injection::parse("20:00").unwrap().apply(&mut soup).is_err());
+        assert!(Injection::parse("00").is_err());
+        assert!(Injection::parse("x:00").is_err());
+    }
+
+    #[test]
+    fn test_script_sets_mutation_rate_at_its_epoch() {
+        let script = ScriptCommand::parse_script(
+            "epoch,command,args\n3,set-mutation-rate,0.01\n4,inject,0:0c\n",
+        )
+        .unwrap();
+        assert_eq!(script.len(), 2);
+        let run_to = |epochs: usize| {
+            let mut soup = small_soup();
+            let mut out = Vec::new();
+            run_simulation::<Bff>(
+                &mut soup,
+                epochs,
+                RunOptions {
+                    metrics_interval: 1,
+                    script: &script,
+                    ..RunOptions::default()
+                },
+                &mut out,
+            )
+            .unwrap();
+            soup
+        };
+        assert_eq!(run_to(2).mutation_rate(), 0.001);
+        assert_eq!(run_to(3).mutation_rate(), 0.01);
+
+        assert!(ScriptCommand::parse("x,extinction,0.5").is_err());
+        assert!(ScriptCommand::parse("5,extinction,2").is_err());
+        assert!(ScriptCommand::parse("5,dump").is_err());
+        assert!(ScriptCommand::parse("5,explode,1").is_err());
+    }
+
+    #[test]
+    fn test_ascii_frame_draws_one_character_per_grid_square() {
+        let soup = SoupSurface::new(
+            SurfaceMesh::flat_grid(4, 4).unwrap(),
+            SoupSurfaceConfig::default(),
+            7,
+        );
+        let mut view = AsciiView::new(ColorMode::Hash, Some((4, 4)), 32).unwrap();
+        let frame = view.frame::<Bff>(&soup);
+        assert_eq!(frame.len(), 4);
+        let cells = frame
+            .concat()
+            .bytes()
+            .filter(|b| ASCII_RAMP.contains(b))
+            .count();
+        assert_eq!(cells, 16);
+
+        // Off a grid the 32 faces are listed one character each.
+        let mut view = AsciiView::new(ColorMode::Hash, None, 32).unwrap();
+        let cells = view.frame::<Bff>(&soup).concat();
+        assert_eq!(cells.bytes().filter(|b| ASCII_RAMP.contains(b)).count(), 32);
+        assert!(AsciiView::new(ColorMode::Hash, None, ASCII_MAX_CELLS + 1).is_err());
+    }
+
+    #[test]
+    fn test_invasion_parse_errors() {
+        assert!(Invasion::parse("bff:00:00").is_err());
+        assert!(Invasion::parse("bff:0:00:1").is_err());
+        assert!(Invasion::parse("bff:zz:00:1").is_err());
+        assert!(Invasion::parse("bff:00:00:-1").is_err());
+        let too_long = Invasion::parse(&format!("bff:{}:00:1", "00".repeat(17))).unwrap();
+        assert!(too_long.seed(&mut small_soup(), 0).is_err());
+    }
+
+    #[test]
+    fn test_single_thread_matches_parallel() {
+        let run_on = |threads: usize| -> Vec<u8> {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let mut soup = small_soup();
+                let mut out = Vec::new();
+                run_simulation::<Bff>(
+                    &mut soup,
+                    20,
+                    RunOptions {
+                        metrics_interval: 5,
+                        level: MetricsLevel::Full,
+                        ..RunOptions::default()
+                    },
+                    &mut out,
+                )
+                .unwrap();
+                out
+            })
+        };
+        assert_eq!(run_on(1), run_on(4));
+    }
+}
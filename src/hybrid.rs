@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use crate::substrate::Substrate;
+
+/// Two instruction sets sharing one tape: program A (the first half) is
+/// interpreted by `A`, program B (the second half) by `B`.
+///
+/// `A` runs first from position 0, then `B` runs from the start of the second
+/// half, each with half the step budget. Both see the whole concatenated tape,
+/// so either side can read and overwrite the other — the minimal setup for
+/// pitting instruction sets against each other.
+pub struct Hybrid<A, B>(PhantomData<fn() -> (A, B)>);
+
+impl<A: Substrate, B: Substrate> Substrate for Hybrid<A, B> {
+    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
+        let half = tape.len() / 2;
+        let budget = step_limit / 2;
+        let mut steps = A::execute(tape, budget);
+
+        // Rotate so B's program sits at position 0, run it, and rotate back.
+        tape.rotate_left(half);
+        steps += B::execute(tape, budget);
+        tape.rotate_right(half);
+        steps
+    }
+
+    /// Each half runs on half the budget, so give each at least what its own
+    /// substrate would get alone.
+    fn recommended_step_limit(program_size: usize) -> usize {
+        2 * A::recommended_step_limit(program_size).max(B::recommended_step_limit(program_size))
+    }
+
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        A::validate_program_size(program_size)?;
+        B::validate_program_size(program_size)
+    }
+
+    fn is_instruction(byte: u8) -> bool {
+        A::is_instruction(byte) || B::is_instruction(byte)
+    }
+
+    fn disassemble(tape: &[u8]) -> String {
+        let half = tape.len() / 2;
+        format!(
+            "-- A --\n{}-- B --\n{}",
+            A::disassemble(&tape[..half]),
+            B::disassemble(&tape[half..])
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bff::Bff;
+    use crate::forth::Forth;
+
+    #[test]
+    fn test_forth_half_copies_into_bff_half() {
+        // 0x0C is Forth's one-byte replicator; it is a no-op for BFF.
+        let mut tape = vec![0u8; 128];
+        tape[0] = 0x0C;
+        Hybrid::<Forth, Bff>::execute(&mut tape, 8192);
+        assert_eq!(tape[64], 0x0C);
+    }
+
+    #[test]
+    fn test_each_half_gets_half_the_budget() {
+        // NOP; JMP -1 is an infinite Forth loop, placed at the start of both halves.
+        let mut tape = vec![0u8; 128];
+        tape[..2].copy_from_slice(&[0x0F, 0xC0]);
+        tape[64..66].copy_from_slice(&[0x0F, 0xC0]);
+        let steps = Hybrid::<Forth, Forth>::execute(&mut tape, 100);
+        assert_eq!(steps, 100);
+    }
+
+    #[test]
+    fn test_b_runs_from_second_half() {
+        // COPY in B's half copies tape[64] to tape[64 + 64], i.e. wraps to
+        // the first byte of A's half once rotated back.
+        let mut tape = vec![0u8; 128];
+        tape[64] = 0x0C;
+        Hybrid::<Bff, Forth>::execute(&mut tape, 8192);
+        assert_eq!(tape[0], 0x0C);
+    }
+}
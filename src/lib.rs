@@ -2,14 +2,20 @@ pub mod bff;
 pub mod bits;
 pub mod echo;
 pub mod forth;
+pub mod harvard;
+pub mod headless;
+pub mod hybrid;
+pub mod log;
 pub mod metrics;
 pub mod mos6502;
+pub mod organism;
 pub mod qop;
 pub mod rig;
 pub mod skim;
 pub mod subleq;
 pub mod substrate;
 pub mod surface;
+pub mod tierra;
 pub mod uxn;
 pub mod viz;
 pub mod z80;
@@ -0,0 +1,143 @@
+//! Status output from long-running library calls.
+//!
+//! Mesh generation, neighbor-table construction and headless runs report
+//! what they are doing through this module. By default messages go to
+//! stderr; embedders can silence them with [`set_verbosity`], which holds
+//! for every thread, or capture one thread's with [`with_logger`].
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much status output to produce.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Verbosity {
+    /// No status output at all.
+    Quiet,
+    /// Status lines and progress counters.
+    #[default]
+    Normal,
+}
+
+type Logger = Box<dyn FnMut(&str)>;
+
+/// The process's [`Verbosity`], shared by worker and viewer threads alike.
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+thread_local! {
+    static LOGGER: RefCell<Option<Logger>> = const { RefCell::new(None) };
+}
+
+/// Set the verbosity for status output on every thread.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// The verbosity for status output.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        _ => Verbosity::Normal,
+    }
+}
+
+/// Run `f` with this thread's status output sent to `logger` instead of
+/// stderr. Verbosity still applies: nothing reaches `logger` when quiet.
+pub fn with_logger<R>(logger: impl FnMut(&str) + 'static, f: impl FnOnce() -> R) -> R {
+    let previous = LOGGER.replace(Some(Box::new(logger)));
+    // Restore on unwind too, so a panicking `f` doesn't leak the logger.
+    struct Restore(Option<Logger>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LOGGER.set(self.0.take());
+        }
+    }
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Emit one status line.
+pub fn emit(line: &str) {
+    write(line, |line| eprintln!("{line}"));
+}
+
+/// Emit a progress counter that overwrites the previous one on a terminal.
+/// Finish a run of these with [`end_progress`].
+pub fn progress(line: &str) {
+    write(line, |line| eprint!("\r{line}"));
+}
+
+/// Terminate a run of [`progress`] counters.
+pub fn end_progress() {
+    if verbosity() == Verbosity::Normal && LOGGER.with_borrow(Option::is_none) {
+        eprintln!();
+    }
+}
+
+fn write(line: &str, to_stderr: impl FnOnce(&str)) {
+    if verbosity() == Verbosity::Quiet {
+        return;
+    }
+    LOGGER.with_borrow_mut(|logger| match logger {
+        Some(logger) => logger(line),
+        None => to_stderr(line),
+    });
+}
+
+/// `eprintln!`-style formatting routed through [`emit`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_status {
+    ($($arg:tt)*) => {
+        $crate::log::emit(&format!($($arg)*))
+    };
+}
+pub use crate::__log_status as status;
+
+/// Held by tests that change the verbosity or expect to capture status
+/// lines, since the verbosity is shared by every test thread.
+#[cfg(test)]
+pub(crate) fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn captured(f: impl FnOnce()) -> Vec<String> {
+        let _lock = test_lock();
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&lines);
+        with_logger(move |line| sink.borrow_mut().push(line.to_string()), f);
+        lines.take()
+    }
+
+    #[test]
+    fn test_logger_receives_status_lines() {
+        let lines = captured(|| status!("hello {}", 42));
+        assert_eq!(lines, ["hello 42"]);
+    }
+
+    #[test]
+    fn test_quiet_emits_nothing() {
+        let lines = captured(|| {
+            set_verbosity(Verbosity::Quiet);
+            emit("hidden");
+            progress("hidden");
+            set_verbosity(Verbosity::Normal);
+        });
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_quiet_reaches_other_threads() {
+        let _lock = test_lock();
+        set_verbosity(Verbosity::Quiet);
+        let seen = std::thread::spawn(verbosity).join().unwrap();
+        set_verbosity(Verbosity::Normal);
+        assert_eq!(seen, Verbosity::Quiet);
+    }
+}
@@ -1,3 +1,1082 @@
-fn main() {
-    complife::viz::run_app(complife::viz::MenuConfig::default());
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use complife::bff::Bff;
+use complife::headless::{
+    AsciiView, DEFAULT_METRICS_INTERVAL, HistogramFormat, Injection, InteractionTrace, Invasion,
+    MetricsLevel, Mixture, RunManifest, RunOptions, ScriptCommand, SeedArg, analyze_dumps,
+    benchmark_all, cross_run, parse_hex, run_collecting, run_invasion, run_simulation,
+    write_benchmark_table, write_catalog, write_comparison, write_cross_run_table, write_histogram,
+    write_opcode_table,
+};
+use complife::log::{self, Verbosity};
+use complife::organism::Organism;
+use complife::substrate::Substrate;
+use complife::surface::{
+    AdaptiveMutation, BUILD_MEMORY_LIMIT, CellSite, DEFAULT_NEIGHBORS_ESTIMATE, Drift, Fitness,
+    GeodesicMetric, InteractionGraph, InteractionMode, MutationModel, Mutators, NeighborRadius,
+    RngMode, SelectionMode, SoupSurface, SoupSurfaceConfig, SurfaceMesh, SurfaceSpec, UpdateMode,
+    WriteBackPolicy, estimate_build_bytes, population_for_budget,
+};
+use complife::viz::{ColorMode, MenuConfig, SubstrateKind};
+
+/// Epochs each substrate runs under `--benchmark-all` without `--epochs`.
+const BENCHMARK_EPOCHS: usize = 20;
+
+/// How many times more likely a biased mutation lands on an instruction byte.
+const INSTRUCTION_BIAS: f32 = 8.0;
+
+/// Mutation-spectrum presets selectable from the command line.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum MutationBias {
+    /// Mutations land on the substrate's instruction bytes more often.
+    TowardInstructions,
+}
+
+/// Post-execution selection between paired programs.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Selection {
+    /// Write both halves back unchanged.
+    None,
+    /// The fitter half overwrites the other.
+    Tournament,
+}
+
+/// Fitness functions for `--selection tournament`.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum FitnessArg {
+    /// Bytes left unchanged by execution.
+    Persistence,
+    /// Bytes that decode as instructions.
+    Instructions,
+}
+
+/// Self-replicating programs emerging on triangle-mesh surfaces.
+///
+/// Without `--epochs` the interactive viewer opens, pre-configured with the
+/// given options. With `--epochs` the simulation runs headless and prints
+/// CSV metrics to stdout.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Random seed (required for headless runs), or `random` for one drawn
+    /// from the OS and printed to stderr.
+    #[arg(long = "seed", id = "seed", value_name = "SEED", value_parser = SeedArg::parse)]
+    seed_arg: Option<SeedArg>,
+    /// `--seed` resolved to a number.
+    #[arg(skip)]
+    seed: Option<u64>,
+    /// Run headless for this many epochs instead of opening the viewer.
+    #[arg(long)]
+    epochs: Option<usize>,
+    /// Build the surface and neighbor table, print neighbor statistics, and exit.
+    #[arg(long)]
+    dry_run: bool,
+    /// Build the surface, print a mesh validation report, and exit.
+    #[arg(long)]
+    validate_mesh: bool,
+    /// Build the surface and neighbor table, write the neighbor graph to
+    /// PATH as Graphviz DOT, and exit.
+    #[arg(long, value_name = "PATH")]
+    export_graph: Option<PathBuf>,
+    /// Instruction set: bff, forth, subleq, rsubleq4, qop, skim, rig, bits,
+    /// echo, z80, 8080, uxn, 6502, tierra, harvard, hybrid (viewer only).
+    #[arg(long, default_value = "bff")]
+    substrate: String,
+    /// Surface spec: sphere:N, cubesphere:N, torus:MxN, flat:WxH, hamster:NxS,
+    /// cylinder:SxR, klein:UxV, heightmap:WxH, heightmap:IMAGE[:SCALE],
+    /// trefoil:RxS, obj:PATH. Repeat to run several surfaces side by side
+    /// in the viewer.
+    #[arg(long, default_value = "sphere:4")]
+    surface: Vec<String>,
+    /// Center obj: surfaces on the origin and scale them so their longest
+    /// side spans [-1, 1], matching the procedural shapes.
+    #[arg(long)]
+    obj_normalize: bool,
+    /// Leave zero-area faces of obj: surfaces out of the simulation instead
+    /// of giving each a cell (they are reported either way).
+    #[arg(long)]
+    drop_degenerate: bool,
+    /// Pair cells over a synthetic graph instead of the surface's geodesic
+    /// neighbors: regular:K:N is a random K-regular graph on N cells, wired
+    /// from the seed and drawn on a flat grid (headless runs).
+    #[arg(long, value_name = "SPEC")]
+    graph: Option<String>,
+    /// Geodesic neighbor radius in mesh units, or auto:N for whatever radius
+    /// gives about N neighbors per face [default: 4x the typical spacing of
+    /// adjacent faces].
+    #[arg(long, value_name = "RADIUS", value_parser = NeighborRadius::parse)]
+    neighbor_radius: Option<NeighborRadius>,
+    /// Keep only the K geodesically-nearest neighbors per face.
+    #[arg(long, value_name = "K")]
+    max_neighbors: Option<usize>,
+    /// Build surfaces whose mesh and neighbor table are estimated to need
+    /// more than 1 GiB of memory.
+    #[arg(long)]
+    force: bool,
+    /// Size the soup to a memory budget of GB GiB instead of a --surface:
+    /// run on the largest square flat grid whose mesh, neighbor table, and
+    /// programs are estimated to fit, printing the population chosen.
+    #[arg(long, value_name = "GB", conflicts_with_all = ["surface", "graph"])]
+    memory_budget: Option<f64>,
+    /// How neighbor distances are measured: euclidean between face
+    /// centroids, or parametric in the torus's flat (u, v) domain, which
+    /// gives every face the same neighborhood (headless runs).
+    #[arg(long, value_name = "METRIC", default_value = "euclidean", value_parser = GeodesicMetric::parse)]
+    geodesic_metric: GeodesicMetric,
+    /// Host one program per face, or per vertex with vertices joined by a
+    /// mesh edge adjacent.
+    #[arg(long, value_name = "SITE", default_value = "face", value_parser = CellSite::parse, conflicts_with = "graph")]
+    cells: CellSite,
+    /// Bytes per program.
+    #[arg(long, default_value_t = 64)]
+    program_size: usize,
+    /// Maximum steps per program execution [default: the substrate's
+    /// recommendation for the program size].
+    #[arg(long)]
+    step_limit: Option<usize>,
+    /// Per-byte mutation probability per epoch.
+    #[arg(long, default_value_t = 0.00024)]
+    mutation_rate: f64,
+    /// Raise the mutation rate above `--mutation-rate` while diversity is
+    /// collapsed, relaxing it back as diversity recovers (headless runs).
+    #[arg(long)]
+    adaptive_mutation: bool,
+    /// Unique programs as a fraction of the population below which
+    /// `--adaptive-mutation` ramps the rate up.
+    #[arg(
+        long,
+        requires = "adaptive_mutation",
+        default_value_t = AdaptiveMutation::default().target
+    )]
+    adaptive_target: f64,
+    /// Ceiling for the mutation rate under `--adaptive-mutation`.
+    #[arg(
+        long,
+        requires = "adaptive_mutation",
+        default_value_t = AdaptiveMutation::default().max_rate
+    )]
+    adaptive_max_rate: f64,
+    /// Fraction of cells seeded as mutators, whose heritable mutation rate
+    /// is `--mutator-strength` times `--mutation-rate` (headless runs).
+    #[arg(long, value_name = "F")]
+    mutator_fraction: Option<f64>,
+    /// Mutation-rate multiplier of `--mutator-fraction` cells.
+    #[arg(
+        long,
+        value_name = "X",
+        requires = "mutator_fraction",
+        default_value_t = Mutators::default().strength
+    )]
+    mutator_strength: f64,
+    /// Stop mutating from epoch N on, leaving replication alone to settle
+    /// the competition between existing variants (headless runs).
+    #[arg(long, value_name = "N")]
+    no_mutation_after: Option<usize>,
+    /// Per-program probability each epoch of rotating a random window of
+    /// the program by one byte or reversing it (headless runs).
+    #[arg(long, value_name = "P", default_value_t = 0.0)]
+    rearrange_rate: f64,
+    /// Bias the mutation spectrum (headless runs; default: uniform bit flips).
+    #[arg(long, value_enum)]
+    mutation_bias: Option<MutationBias>,
+    /// Post-execution selection (headless runs).
+    #[arg(long, value_enum, default_value_t = Selection::None)]
+    selection: Selection,
+    /// Fitness function for `--selection tournament`.
+    #[arg(long, value_enum, default_value_t = FitnessArg::Persistence)]
+    fitness: FitnessArg,
+    /// Probability of running a program against an all-zero partner instead
+    /// of a neighbor (headless runs).
+    #[arg(long, default_value_t = 0.0)]
+    self_pair_fraction: f64,
+    /// Play Conway's Game of Life (B3/S23) on the squares of a flat:WxH
+    /// grid instead of running programs, reading byte 0 of each square's
+    /// first face as its state (odd is alive) and never mutating: a check
+    /// on the grid plumbing that needs no substrate (headless runs).
+    #[arg(
+        long,
+        requires = "epochs",
+        conflicts_with_all = ["compare", "graph", "log_interactions", "replay_interactions"]
+    )]
+    life: bool,
+    /// RNG mode: streaming draws from one generator for the whole run;
+    /// per-epoch reseeds it every epoch from the seed and epoch number, so
+    /// partial and resumed runs line up exactly (headless runs).
+    #[arg(long, value_enum, default_value_t = RngMode::Streaming)]
+    rng_mode: RngMode,
+    /// Log how many RNG draws each epoch's pairing, orientation,
+    /// write-back and mutation make, to find where two runs that should
+    /// match part ways (headless runs).
+    #[arg(long)]
+    count_rng: bool,
+    /// Update scheme: sync pairs every cell at most once from the
+    /// population as the epoch began and runs the pairs in parallel; async
+    /// runs random neighbor interactions one at a time on the live
+    /// population (headless runs).
+    #[arg(long = "update", value_enum, default_value_t = UpdateMode::Synchronous)]
+    update_mode: UpdateMode,
+    /// How executed tapes land on their programs: replace overwrites them,
+    /// random:P takes each new byte with probability P (headless runs).
+    #[arg(long, value_name = "POLICY", default_value = "replace", value_parser = WriteBackPolicy::parse)]
+    writeback: WriteBackPolicy,
+    /// Uniform drift vector X,Y,Z: a program upwind of its partner tends to
+    /// execute first, so replication spreads downwind (headless runs).
+    #[arg(long, value_name = "X,Y,Z", default_value = "0,0,0", value_parser = Drift::parse)]
+    drift: Drift,
+    /// Emit metrics every N epochs.
+    #[arg(long, default_value_t = DEFAULT_METRICS_INTERVAL)]
+    metrics_interval: usize,
+    /// Which metric columns to print in headless mode.
+    #[arg(long, value_enum, default_value_t = MetricsLevel::Basic)]
+    metrics: MetricsLevel,
+    /// Estimate metrics from K programs drawn per row, reproducibly from
+    /// the seed, instead of the whole population (headless runs).
+    #[arg(
+        long,
+        value_name = "K",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion"]
+    )]
+    metrics_sample: Option<usize>,
+    /// Record every epoch's pairings to a binary interaction log at PATH
+    /// (headless runs).
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion", "replay_interactions"]
+    )]
+    log_interactions: Option<PathBuf>,
+    /// Take every epoch's pairings from an interaction log written by
+    /// `--log-interactions` instead of drawing them (headless runs).
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion"]
+    )]
+    replay_interactions: Option<PathBuf>,
+    /// After the run, print the final population's byte histogram below
+    /// the metrics: ASCII bars fitted to $COLUMNS (instruction bytes drawn
+    /// with `#`), or 256 `byte,count` CSV rows (headless runs).
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion"]
+    )]
+    final_histogram: Option<HistogramFormat>,
+    /// After the run, print the disassembly of every distinct program
+    /// held by at least MINCOUNT cells, most common first (headless runs).
+    #[arg(
+        long,
+        value_name = "MINCOUNT",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion"]
+    )]
+    catalog: Option<usize>,
+    /// Draw the soup in the terminal at every metrics epoch, colored by
+    /// `--color-mode`: one character per square of a flat grid, or a list
+    /// of cells on other surfaces (headless runs on small soups).
+    #[arg(long, requires = "epochs", conflicts_with_all = ["compare", "invasion"])]
+    ascii: bool,
+    /// Time every substrate on the same surface and soup settings for
+    /// `--epochs` epochs (default 20) and print interactions per second,
+    /// fastest first.
+    #[arg(long, conflicts_with_all = ["compare", "invasion", "mutation_bias", "substrate"])]
+    benchmark_all: bool,
+    /// After `--benchmark-all` times each substrate, save its final
+    /// population to PATH.NAME and print its HOE and unique program count,
+    /// to check the programs did more than halt at once.
+    #[arg(long, value_name = "PATH", requires = "benchmark_all")]
+    benchmark_dump: Option<PathBuf>,
+    /// Save the population at every metrics epoch to DIR (headless runs).
+    #[arg(long, value_name = "DIR")]
+    dump: Option<PathBuf>,
+    /// Run two substrates on the same surface and seed and print their HOE
+    /// side by side as `epoch,hoe_a,hoe_b` (headless runs).
+    #[arg(long, value_name = "SUBA,SUBB", requires = "epochs")]
+    compare: Option<String>,
+    /// Write the run's resolved parameters (surface, soup config, seed,
+    /// substrate, epochs) to PATH as JSON before a headless run starts.
+    #[arg(long, value_name = "PATH", conflicts_with = "compare")]
+    manifest: Option<PathBuf>,
+    /// Rerun the headless run recorded in a `--manifest` file. Its
+    /// parameters replace the simulation options on the command line.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["epochs", "seed", "compare"])]
+    from_manifest: Option<PathBuf>,
+    /// Spatial invasion experiment SUBSTRATE:HEX_A:HEX_B:PATCH_RADIUS: start
+    /// every cell as program A, seed program B in a geodesic patch of that
+    /// radius around `--invasion-center`, and print the fraction of each
+    /// genotype as `epoch,resident,invader` (headless runs).
+    #[arg(
+        long,
+        value_name = "SPEC",
+        value_parser = Invasion::parse,
+        requires = "epochs",
+        conflicts_with_all = ["compare", "manifest", "graph", "life"]
+    )]
+    invasion: Option<Invasion>,
+    /// Assign each cell a substrate drawn from weighted SUBSTRATE:WEIGHT
+    /// entries; each pair runs under its first program's substrate. The
+    /// first entry sets the default step limit (headless runs).
+    #[arg(
+        long,
+        value_name = "SUB:W,...",
+        value_parser = Mixture::parse,
+        requires = "epochs",
+        conflicts_with_all = ["substrate", "compare", "invasion", "benchmark_all"]
+    )]
+    mixed: Option<Mixture>,
+    /// Overwrite cell CELL's program with the HEX bytes at startup,
+    /// zero-padded or truncated to the program size. Repeatable (headless
+    /// runs).
+    #[arg(long, value_name = "CELL:HEX", value_parser = Injection::parse)]
+    inject: Vec<Injection>,
+    /// Inject the program saved in the `.life` file PATH into cell
+    /// `--organism-cell` at startup, as `--inject` would (headless runs).
+    #[arg(long, value_name = "PATH")]
+    load_organism: Option<PathBuf>,
+    /// Cell that `--load-organism` overwrites.
+    #[arg(
+        long,
+        value_name = "CELL",
+        requires = "load_organism",
+        default_value_t = 0
+    )]
+    organism_cell: usize,
+    /// Apply the `epoch,command,args` lines in PATH as their epochs end:
+    /// set-mutation-rate RATE, extinction FRACTION, inject CELL:HEX, or
+    /// dump DIR (headless runs).
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "epochs",
+        conflicts_with_all = ["compare", "invasion"]
+    )]
+    script: Option<PathBuf>,
+    /// Cell at the center of the `--invasion` patch.
+    #[arg(long, value_name = "CELL", requires = "invasion", default_value_t = 0)]
+    invasion_center: usize,
+    /// Recompute metrics over the dumps in DIR instead of simulating. Pass
+    /// the run's `--substrate` for its active-fraction column.
+    #[arg(long, value_name = "DIR", conflicts_with = "epochs")]
+    analyze: Option<PathBuf>,
+    /// Print SUBSTRATE's opcode table (encoding, mnemonic, description)
+    /// and exit.
+    #[arg(long, value_name = "SUBSTRATE")]
+    describe: Option<String>,
+    /// Run the hex-encoded program HEX, zero-padded to --program-size with
+    /// an all-zero partner, under every substrate and print each one's step
+    /// count, how it stopped, and a hash of the tape afterwards; then exit.
+    #[arg(long, value_name = "HEX")]
+    cross_run: Option<String>,
+    /// Run on a dedicated pool of N threads instead of all cores (headless
+    /// runs).
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+    /// Suppress status and progress output on stderr.
+    #[arg(long, short)]
+    quiet: bool,
+    /// Spatial blur strength for the viewer.
+    #[arg(long, default_value_t = 0.0)]
+    blur: f32,
+    /// Color mode the viewer starts in, and the one `--ascii` draws in:
+    /// hash, entropy, zeros, neighbor-similarity, instruction-density,
+    /// unique-bytes, territorial-dominance, opcode-class, temporal-glow,
+    /// termination-cause, or program-age.
+    #[arg(long, value_name = "MODE", default_value = "hash", value_parser = ColorMode::parse)]
+    color_mode: ColorMode,
+    /// Render and pick both sides of each face in the viewer. Use for
+    /// non-orientable surfaces (Klein bottle) and OBJs with inconsistent
+    /// winding; orientable meshes light correctly without it.
+    #[arg(long)]
+    double_sided: bool,
+    /// Shade the viewer's surfaces with averaged vertex normals instead of
+    /// one normal per face. Cell colors stay flat either way.
+    #[arg(long)]
+    smooth_normals: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut cli: Cli) -> Result<(), String> {
+    if cli.quiet {
+        log::set_verbosity(Verbosity::Quiet);
+    }
+    cli.seed = cli.seed_arg.map(SeedArg::resolve);
+    if let Some(mixture) = &cli.mixed {
+        cli.substrate = mixture.primary().to_string();
+    }
+
+    if let Some(dir) = &cli.analyze {
+        let kind = SubstrateKind::parse(&cli.substrate)?;
+        let mut out = std::io::stdout().lock();
+        return dispatch!(kind, analyze_dumps(dir, cli.metrics, &mut out));
+    }
+
+    if let Some(name) = &cli.describe {
+        let kind = SubstrateKind::parse(name)?;
+        let mut out = std::io::stdout().lock();
+        return dispatch!(kind, describe(&mut out));
+    }
+
+    if let Some(hex) = &cli.cross_run {
+        check_run_values(cli.program_size, cli.metrics_interval)?;
+        let rows = cross_run(&parse_hex(hex)?, cli.program_size, cli.step_limit)?;
+        return write_cross_run_table(&rows, &mut std::io::stdout().lock())
+            .map_err(|e| format!("Failed to write cross-run table: {e}"));
+    }
+
+    if let Some(path) = &cli.from_manifest {
+        return run_manifest(&cli, &RunManifest::load(path)?);
+    }
+
+    let kind = SubstrateKind::parse(&cli.substrate)?;
+    check_run_values(cli.program_size, cli.metrics_interval)?;
+    check_program_size(kind, cli.program_size)?;
+    let specs = cli
+        .surface
+        .iter()
+        .map(|s| {
+            let mut spec = SurfaceSpec::parse(s, cli.seed.unwrap_or(42))?;
+            if let SurfaceSpec::ObjFile {
+                normalize,
+                drop_degenerate,
+                ..
+            } = &mut spec
+            {
+                *normalize = cli.obj_normalize;
+                *drop_degenerate = cli.drop_degenerate;
+            }
+            Ok(spec)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let specs = match cli.memory_budget {
+        Some(gb) => vec![budget_surface(&cli, gb)?],
+        None => specs,
+    };
+    let spec = &specs[0];
+    let viewer = cli.epochs.is_none()
+        && !cli.dry_run
+        && !cli.validate_mesh
+        && !cli.benchmark_all
+        && cli.export_graph.is_none();
+    if specs.len() > 1 && !viewer {
+        return Err("Several --surface options are only available in the viewer".into());
+    }
+    let graph = cli
+        .graph
+        .as_deref()
+        .map(|g| InteractionGraph::parse(g, cli.seed.unwrap_or(42)))
+        .transpose()?;
+
+    if cli.validate_mesh {
+        println!("{}", spec.build()?.validate());
+        return Ok(());
+    }
+
+    if let Some(path) = &cli.export_graph {
+        return build_mesh(&cli, spec, graph.as_ref())?
+            .export_graphviz(&path.to_string_lossy())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()));
+    }
+
+    if cli.dry_run {
+        let mesh = build_mesh(&cli, spec, graph.as_ref())?;
+        let label = graph.map_or_else(|| spec.label().to_string(), |g| g.label());
+        println!("{label}: {} cells", mesh.num_cells());
+        println!("neighbors per cell: {}", mesh.neighbor_stats());
+        return Ok(());
+    }
+
+    if cli.benchmark_all {
+        let mesh = build_mesh(&cli, spec, graph.as_ref())?;
+        let epochs = cli.epochs.unwrap_or(BENCHMARK_EPOCHS);
+        let config = soup_config::<Bff>(&cli);
+        let rows = in_thread_pool(cli.threads, || {
+            let seed = cli.seed.unwrap_or(42);
+            let dump = cli.benchmark_dump.as_deref();
+            benchmark_all(&mesh, &config, cli.step_limit, seed, epochs, dump)
+        })??;
+        return write_benchmark_table(&rows, &mut std::io::stdout().lock())
+            .map_err(|e| format!("Failed to write benchmark: {e}"));
+    }
+
+    let Some(epochs) = cli.epochs else {
+        if graph.is_some() {
+            return Err("--graph is only available for headless runs (with --epochs)".into());
+        }
+        for spec in &specs {
+            check_build_memory(&cli, spec, cli.neighbor_radius, cli.max_neighbors)?;
+        }
+        complife::viz::run_app(MenuConfig::new(
+            kind,
+            &specs,
+            cli.seed.unwrap_or(42),
+            cli.neighbor_radius,
+            cli.cells,
+            cli.program_size,
+            cli.step_limit,
+            cli.mutation_rate,
+            100_000,
+            cli.metrics_interval,
+            cli.blur,
+            cli.color_mode,
+            cli.double_sided,
+            cli.smooth_normals,
+        ));
+        return Ok(());
+    };
+
+    let seed = cli
+        .seed
+        .ok_or("--seed is required for headless runs (with --epochs)")?;
+    if let Some(invasion) = &cli.invasion {
+        let kind = SubstrateKind::parse(&invasion.substrate)?;
+        check_program_size(kind, cli.program_size)?;
+        let mesh = build_mesh(&cli, spec, None)?;
+        return in_thread_pool(cli.threads, || {
+            dispatch!(kind, run_invasion_for(&cli, invasion, mesh, seed, epochs))
+        })?;
+    }
+    if let Some(pair) = &cli.compare {
+        return in_thread_pool(cli.threads, || {
+            run_comparison(&cli, pair, spec, graph.as_ref(), seed, epochs)
+        })?;
+    }
+    let manifest = dispatch!(kind, manifest_for(&cli, spec, graph, seed, epochs))?;
+    run_manifest(&cli, &manifest)
+}
+
+/// The mesh and neighbor table the command line describes: the surface with
+/// geodesic neighbors, or `graph` drawn on a flat grid.
+fn build_mesh(
+    cli: &Cli,
+    spec: &SurfaceSpec,
+    graph: Option<&InteractionGraph>,
+) -> Result<SurfaceMesh, String> {
+    if let Some(graph) = graph {
+        return Ok(graph.build()?);
+    }
+    check_build_memory(cli, spec, cli.neighbor_radius, cli.max_neighbors)?;
+    let mut mesh = spec.build()?;
+    mesh.set_geodesic_metric(cli.geodesic_metric)?;
+    if spec.builds_neighbors() {
+        return match cli.cells {
+            CellSite::Face => Ok(mesh),
+            CellSite::Vertex => Err(format!(
+                "--cells vertex isn't available on a {}",
+                spec.label()
+            )),
+        };
+    }
+    mesh.set_cell_site(cli.cells)?;
+    let radius = cli.neighbor_radius.map(|r| r.resolve(&mesh));
+    mesh.compute_neighbors(radius, cli.max_neighbors);
+    Ok(mesh)
+}
+
+/// Refuse, unless `--force`, a surface whose mesh and neighbor table
+/// [`estimate_build_bytes`] puts over [`BUILD_MEMORY_LIMIT`]. Shapes whose
+/// size isn't known before building pass unchecked.
+fn check_build_memory(
+    cli: &Cli,
+    spec: &SurfaceSpec,
+    neighbor_radius: Option<NeighborRadius>,
+    max_neighbors: Option<usize>,
+) -> Result<(), String> {
+    let Some(faces) = spec.face_count() else {
+        return Ok(());
+    };
+    let neighbors = neighbors_estimate(neighbor_radius, max_neighbors);
+    let threads = cli.threads.unwrap_or_else(rayon::current_num_threads);
+    let bytes = estimate_build_bytes(faces, neighbors, threads);
+    // A surface sized to --memory-budget is held to that budget instead.
+    let (limit, source) = match cli.memory_budget {
+        Some(gb) => (gib_to_bytes(gb), "--memory-budget"),
+        None => (BUILD_MEMORY_LIMIT, "default"),
+    };
+    if bytes <= limit {
+        return Ok(());
+    }
+    let gib = |bytes: u64| bytes as f64 / (1u64 << 30) as f64;
+    let estimate = format!(
+        "{} has {faces} faces and needs about {:.1} GiB to build",
+        spec.label(),
+        gib(bytes)
+    );
+    if cli.force {
+        log::status!("{estimate}; building anyway (--force)");
+        return Ok(());
+    }
+    Err(format!(
+        "{estimate}, over the {:.1} GiB {source} limit; pass --force to build it \
+         anyway, or --max-neighbors to shrink the neighbor table",
+        gib(limit)
+    ))
+}
+
+/// Neighbors per cell to budget for: the target of `--neighbor-radius
+/// auto:N` or a typical table, capped at `--max-neighbors`.
+fn neighbors_estimate(
+    neighbor_radius: Option<NeighborRadius>,
+    max_neighbors: Option<usize>,
+) -> usize {
+    let neighbors = match neighbor_radius {
+        Some(NeighborRadius::MeanNeighbors { mean_neighbors }) => mean_neighbors.ceil() as usize,
+        _ => DEFAULT_NEIGHBORS_ESTIMATE,
+    };
+    max_neighbors.map_or(neighbors, |k| k.min(neighbors))
+}
+
+fn gib_to_bytes(gib: f64) -> u64 {
+    (gib * (1u64 << 30) as f64) as u64
+}
+
+/// The largest square flat grid whose soup [`population_for_budget`] fits
+/// in `--memory-budget` `gb` GiB.
+fn budget_surface(cli: &Cli, gb: f64) -> Result<SurfaceSpec, String> {
+    if !gb.is_finite() || gb <= 0.0 {
+        return Err(format!(
+            "--memory-budget must be a positive number, got {gb}"
+        ));
+    }
+    let neighbors = neighbors_estimate(cli.neighbor_radius, cli.max_neighbors);
+    let threads = cli.threads.unwrap_or_else(rayon::current_num_threads);
+    let cells = population_for_budget(gib_to_bytes(gb), cli.program_size, neighbors, threads);
+    // Two triangle cells per grid square.
+    let side = (cells / 2).isqrt();
+    if side == 0 {
+        return Err(format!(
+            "--memory-budget {gb} GiB is too small for any soup of {}-byte programs",
+            cli.program_size
+        ));
+    }
+    log::status!(
+        "Memory budget {gb} GiB: {} cells on flat:{side}x{side}",
+        2 * side * side
+    );
+    Ok(SurfaceSpec::FlatGrid {
+        width: side,
+        height: side,
+    })
+}
+
+/// Run `manifest` headless, first saving it to `--manifest` if given.
+fn run_manifest(cli: &Cli, manifest: &RunManifest) -> Result<(), String> {
+    let kind = SubstrateKind::parse(&manifest.substrate)?;
+    check_run_values(manifest.config.program_size, manifest.metrics_interval)?;
+    check_program_size(kind, manifest.config.program_size)?;
+    if manifest.metrics_sample == Some(0) {
+        return Err("--metrics-sample must be at least 1".into());
+    }
+    if manifest.graph.is_none() {
+        check_build_memory(
+            cli,
+            &manifest.surface,
+            manifest.neighbor_radius,
+            manifest.max_neighbors,
+        )?;
+    }
+    if let Some(path) = &cli.manifest {
+        manifest.save(path)?;
+    }
+    in_thread_pool(cli.threads, || dispatch!(kind, run_headless(cli, manifest)))?
+}
+
+/// Reject sizes no run can use, before they reach the pairing and tape code.
+fn check_run_values(program_size: usize, metrics_interval: usize) -> Result<(), String> {
+    if program_size == 0 {
+        return Err("--program-size must be at least 1".into());
+    }
+    if metrics_interval == 0 {
+        return Err("--metrics-interval must be at least 1".into());
+    }
+    Ok(())
+}
+
+/// Reject a program size that `kind` can't run as intended.
+fn check_program_size(kind: SubstrateKind, program_size: usize) -> Result<(), String> {
+    match kind {
+        // Headless runs reject the hybrid anyway, and the viewer's program
+        // size slider stays within what every substrate accepts.
+        SubstrateKind::Hybrid => Ok(()),
+        kind => dispatch!(kind, validate_program_size(program_size)),
+    }
+}
+
+fn validate_program_size<S: Substrate>(program_size: usize) -> Result<(), String> {
+    S::validate_program_size(program_size)
+}
+
+/// Run `f` on a dedicated pool of `threads` rayon threads, or on the global
+/// pool when `threads` is `None`.
+fn in_thread_pool<R: Send>(
+    threads: Option<usize>,
+    f: impl FnOnce() -> R + Send,
+) -> Result<R, String> {
+    let Some(threads) = threads else {
+        return Ok(f());
+    };
+    if threads == 0 {
+        return Err("--threads must be at least 1".into());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| format!("Failed to build thread pool: {e}"))?;
+    Ok(pool.install(f))
+}
+
+/// Call the generic `$f::<S>(args)` for the substrate type behind a
+/// headless-capable `SubstrateKind`.
+macro_rules! dispatch {
+    ($kind:expr, $f:ident($($arg:expr),*)) => {
+        match $kind {
+            SubstrateKind::Hybrid => {
+                Err("the hybrid substrate is only available in the viewer".to_string())
+            }
+            kind => complife::with_base_substrate!(kind, S => $f::<S>($($arg),*)),
+        }
+    };
+}
+use dispatch;
+
+/// Print `S`'s opcode table for `--describe`.
+fn describe<S: Substrate>(out: &mut impl Write) -> Result<(), String> {
+    write_opcode_table::<S>(out).map_err(|e| format!("Failed to write opcode table: {e}"))
+}
+
+/// The run the command line describes for substrate `S`.
+fn manifest_for<S: Substrate>(
+    cli: &Cli,
+    spec: &SurfaceSpec,
+    graph: Option<InteractionGraph>,
+    seed: u64,
+    epochs: usize,
+) -> Result<RunManifest, String> {
+    let mut config = soup_config::<S>(cli);
+    if cli.life {
+        let (&SurfaceSpec::FlatGrid { width, height }, CellSite::Face) = (spec, cli.cells) else {
+            return Err("--life needs --surface flat:WxH with programs on faces".into());
+        };
+        config.life = Some((width, height));
+    }
+    Ok(RunManifest {
+        substrate: cli.substrate.clone(),
+        seed,
+        epochs,
+        surface: spec.clone(),
+        graph,
+        neighbor_radius: cli.neighbor_radius,
+        max_neighbors: cli.max_neighbors,
+        geodesic_metric: cli.geodesic_metric,
+        cells: cli.cells,
+        metrics_interval: cli.metrics_interval,
+        metrics_sample: cli.metrics_sample,
+        config,
+        inject: injections(cli)?,
+        script: match &cli.script {
+            Some(path) => ScriptCommand::load_script(path)?,
+            None => Vec::new(),
+        },
+        mixed: cli.mixed.clone(),
+    })
+}
+
+/// Build the surface and soup described by `manifest` and run it headless
+/// as substrate `S`.
+fn run_headless<S: Substrate + Sync>(cli: &Cli, manifest: &RunManifest) -> Result<(), String> {
+    let mut soup = manifest.build_soup()?;
+    let mut trace = match (&cli.log_interactions, &cli.replay_interactions) {
+        (Some(path), _) => Some(InteractionTrace::create(path)?),
+        (_, Some(path)) => Some(InteractionTrace::open(path)?),
+        (None, None) => None,
+    };
+    let mut ascii = if cli.ascii {
+        let grid = match (&manifest.surface, &manifest.graph, manifest.cells) {
+            (&SurfaceSpec::FlatGrid { width, height }, None, CellSite::Face) => {
+                Some((width, height))
+            }
+            _ => None,
+        };
+        Some(AsciiView::new(cli.color_mode, grid, soup.mesh.num_cells())?)
+    } else {
+        None
+    };
+    let mut out = std::io::stdout().lock();
+    run_simulation::<S>(
+        &mut soup,
+        manifest.epochs,
+        RunOptions {
+            metrics_interval: manifest.metrics_interval,
+            level: cli.metrics,
+            sample: manifest.metrics_sample,
+            dump_dir: cli.dump.as_deref(),
+            trace: trace.as_mut(),
+            ascii: ascii.as_mut(),
+            script: &manifest.script,
+        },
+        &mut out,
+    )
+    .map_err(|e| format!("Headless run failed: {e}"))?;
+    if let Some(format) = cli.final_histogram {
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(80);
+        writeln!(out)
+            .and_then(|()| write_histogram::<S>(&soup.programs, format, width, &mut out))
+            .map_err(|e| format!("Failed to write histogram: {e}"))?;
+    }
+    if let Some(min_count) = cli.catalog {
+        writeln!(out)
+            .and_then(|()| write_catalog::<S>(&soup.programs, min_count, &mut out))
+            .map_err(|e| format!("Failed to write catalog: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Run the two substrates named in `pair` ("SUBA,SUBB") on copies of the
+/// same surface and seed, in parallel, and print their HOE trajectories
+/// side by side.
+fn run_comparison(
+    cli: &Cli,
+    pair: &str,
+    spec: &SurfaceSpec,
+    graph: Option<&InteractionGraph>,
+    seed: u64,
+    epochs: usize,
+) -> Result<(), String> {
+    let (name_a, name_b) = pair
+        .split_once(',')
+        .ok_or_else(|| format!("--compare expects SUBA,SUBB, got '{pair}'"))?;
+    let kind = |name: &str| SubstrateKind::parse(name.trim());
+    let (kind_a, kind_b) = (kind(name_a)?, kind(name_b)?);
+    check_program_size(kind_a, cli.program_size)?;
+    check_program_size(kind_b, cli.program_size)?;
+
+    let mesh = build_mesh(cli, spec, graph)?;
+    let trajectory = |kind: SubstrateKind, mesh: SurfaceMesh| {
+        dispatch!(kind, trajectory_for(cli, mesh, seed, epochs))
+    };
+    let mesh_a = mesh.clone();
+    // Both runs share the current rayon pool, so `--threads` bounds the pair.
+    let (a, b) = rayon::join(|| trajectory(kind_a, mesh_a), || trajectory(kind_b, mesh));
+    write_comparison(&a?, &b?, &mut std::io::stdout().lock())
+        .map_err(|e| format!("Failed to write CSV: {e}"))
+}
+
+/// HOE trajectory of substrate `S` on `mesh`, for `run_comparison`.
+fn trajectory_for<S: Substrate + Sync>(
+    cli: &Cli,
+    mesh: SurfaceMesh,
+    seed: u64,
+    epochs: usize,
+) -> Result<Vec<(usize, f64)>, String> {
+    let mut soup = build_soup::<S>(cli, mesh, seed);
+    inject(cli, &mut soup)?;
+    // The trajectory is all `write_comparison` needs; each run's own CSV
+    // goes nowhere.
+    run_collecting::<S>(
+        &mut soup,
+        epochs,
+        RunOptions {
+            metrics_interval: cli.metrics_interval,
+            ..RunOptions::default()
+        },
+        &mut std::io::sink(),
+    )
+    .map_err(|e| format!("Failed to run comparison: {e}"))
+}
+
+/// Seed a soup for substrate `S` on `mesh` with `invasion` and run it.
+fn run_invasion_for<S: Substrate + Sync>(
+    cli: &Cli,
+    invasion: &Invasion,
+    mesh: SurfaceMesh,
+    seed: u64,
+    epochs: usize,
+) -> Result<(), String> {
+    let mut soup = build_soup::<S>(cli, mesh, seed);
+    let patch = invasion.seed(&mut soup, cli.invasion_center)?;
+    inject(cli, &mut soup)?;
+    log::status!(
+        "invader patch: {patch} of {} cells around cell {}",
+        soup.programs.len(),
+        cli.invasion_center
+    );
+    run_invasion::<S>(
+        &mut soup,
+        invasion,
+        epochs,
+        cli.metrics_interval,
+        &mut std::io::stdout().lock(),
+    )
+    .map_err(|e| format!("Failed to write CSV: {e}"))
+}
+
+/// Soup for substrate `S` on `mesh` with the command line's settings.
+fn build_soup<S: Substrate>(cli: &Cli, mesh: SurfaceMesh, seed: u64) -> SoupSurface {
+    SoupSurface::new(mesh, soup_config::<S>(cli), seed)
+}
+
+/// The command line's `--inject` programs, followed by the
+/// `--load-organism` program if one was given.
+fn injections(cli: &Cli) -> Result<Vec<Injection>, String> {
+    let mut injections = cli.inject.clone();
+    if let Some(path) = &cli.load_organism {
+        let organism = Organism::load(path)?;
+        if organism.substrate != cli.substrate {
+            log::status!(
+                "warning: {} holds a {} organism; running it as {}",
+                path.display(),
+                organism.substrate,
+                cli.substrate
+            );
+        }
+        injections.push(Injection {
+            cell: cli.organism_cell,
+            program: organism.bytes,
+        });
+    }
+    Ok(injections)
+}
+
+/// Apply the command line's `--inject` and `--load-organism` programs to
+/// `soup`.
+fn inject(cli: &Cli, soup: &mut SoupSurface) -> Result<(), String> {
+    injections(cli)?
+        .iter()
+        .try_for_each(|injection| injection.apply(soup))
+}
+
+/// Soup configuration for substrate `S` from the command line's settings.
+fn soup_config<S: Substrate>(cli: &Cli) -> SoupSurfaceConfig {
+    let mutation_model = match cli.mutation_bias {
+        None => MutationModel::BitFlip,
+        Some(MutationBias::TowardInstructions) => {
+            MutationModel::toward_instructions(S::is_instruction, INSTRUCTION_BIAS)
+        }
+    };
+    SoupSurfaceConfig {
+        program_size: cli.program_size,
+        step_limit: cli
+            .step_limit
+            .unwrap_or_else(|| S::recommended_step_limit(cli.program_size)),
+        mutation_rate: cli.mutation_rate,
+        adaptive_mutation: cli.adaptive_mutation.then_some(AdaptiveMutation {
+            target: cli.adaptive_target,
+            max_rate: cli.adaptive_max_rate,
+        }),
+        mutators: cli.mutator_fraction.map(|fraction| Mutators {
+            fraction,
+            strength: cli.mutator_strength,
+        }),
+        no_mutation_after: cli.no_mutation_after,
+        rearrange_rate: cli.rearrange_rate,
+        interaction_mode: InteractionMode::Normal,
+        mutation_model,
+        selection: match cli.selection {
+            Selection::None => SelectionMode::None,
+            Selection::Tournament => SelectionMode::Tournament(match cli.fitness {
+                FitnessArg::Persistence => Fitness::Persistence,
+                FitnessArg::Instructions => Fitness::InstructionCount,
+            }),
+        },
+        life: None,
+        self_pair_fraction: cli.self_pair_fraction,
+        rng_mode: cli.rng_mode,
+        drift: cli.drift,
+        update_mode: cli.update_mode,
+        write_back: cli.writeback,
+        count_rng: cli.count_rng,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degenerate_run_values_rejected() {
+        assert!(check_run_values(64, 25).is_ok());
+        assert!(
+            check_run_values(0, 25)
+                .unwrap_err()
+                .contains("--program-size")
+        );
+        assert!(
+            check_run_values(64, 0)
+                .unwrap_err()
+                .contains("--metrics-interval")
+        );
+        // An empty population never gets this far: surfaces and graphs
+        // without cells are refused before a soup is made.
+        let spec = SurfaceSpec::parse("flat:0x4", 42).unwrap();
+        assert!(spec.build().is_err());
+        let graph = InteractionGraph::parse("regular:2:0", 42).unwrap();
+        assert!(graph.build().is_err());
+    }
+
+    #[test]
+    fn test_build_memory_error_names_the_applied_limit() {
+        let spec = SurfaceSpec::parse("sphere:8", 42).unwrap();
+        let check = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["complife"], args].concat()).unwrap();
+            check_build_memory(&cli, &spec, None, None).unwrap_err()
+        };
+        let err = check(&[]);
+        assert!(err.contains("1.0 GiB default limit"), "{err}");
+        let err = check(&["--memory-budget", "0.5"]);
+        assert!(err.contains("0.5 GiB --memory-budget limit"), "{err}");
+    }
+
+    #[test]
+    fn test_step_limit_defaults_to_the_substrate() {
+        let config = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["complife"], args].concat()).unwrap();
+            soup_config::<Bff>(&cli).step_limit
+        };
+        assert_eq!(config(&[]), Bff::recommended_step_limit(64));
+        assert_eq!(config(&["--step-limit", "99"]), 99);
+    }
+
+    #[test]
+    fn test_memory_budget_must_be_finite() {
+        for budget in ["inf", "NaN", "0", "-0.5"] {
+            let arg = format!("--memory-budget={budget}");
+            let cli = Cli::try_parse_from(["complife", &arg]).unwrap();
+            let err = budget_surface(&cli, cli.memory_budget.unwrap()).unwrap_err();
+            assert!(err.contains("positive number"), "{err}");
+        }
+        let cli = Cli::try_parse_from(["complife", "--memory-budget", "0.25"]).unwrap();
+        assert!(budget_surface(&cli, 0.25).is_ok());
+    }
+
+    #[test]
+    fn test_saturated_face_count_is_refused() {
+        let cli = Cli::try_parse_from(["complife"]).unwrap();
+        for surface in ["sphere:40", "torus:4000000000x4000000000"] {
+            let spec = SurfaceSpec::parse(surface, 42).unwrap();
+            assert_eq!(spec.face_count(), Some(usize::MAX), "{surface}");
+            let err = check_build_memory(&cli, &spec, None, None).unwrap_err();
+            assert!(err.contains("pass --force"), "{err}");
+        }
+    }
 }
@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::substrate::Substrate;
+
 /// Compute the high-order entropy (HOE) of a byte slice.
 ///
 /// HOE = compressed_size / raw_size, where compression uses brotli at quality 2.
@@ -52,9 +57,374 @@ pub fn byte_frequency_histogram(programs: &[Vec<u8>]) -> [usize; 256] {
     hist
 }
 
+/// Pearson's chi-square statistic of a byte histogram against the uniform
+/// distribution over all 256 values.
+///
+/// For uniformly random bytes this is about 255 (the degrees of freedom);
+/// it grows sharply as a few byte values come to dominate the population.
+pub fn uniformity_chi_square(histogram: &[usize; 256]) -> f64 {
+    let total: usize = histogram.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let expected = total as f64 / 256.0;
+    histogram
+        .iter()
+        .map(|&count| {
+            let diff = count as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// Consecutive samples HOE must stay below the threshold for
+/// [`takeover_epoch`] to call a takeover.
+pub const TAKEOVER_WINDOW: usize = 3;
+
+/// Epoch at which a replicator took over, from `(epoch, hoe)` samples in
+/// epoch order.
+///
+/// This is the epoch of the first sample whose HOE is below `threshold`
+/// and is followed by at least `TAKEOVER_WINDOW - 1` more samples, all
+/// below it too. A dip that recovers within the window, or one too close to
+/// the end of the series to confirm, is not a takeover.
+pub fn takeover_epoch(hoe_series: &[(usize, f64)], threshold: f64) -> Option<usize> {
+    hoe_series
+        .windows(TAKEOVER_WINDOW)
+        .find(|window| window.iter().all(|&(_, hoe)| hoe < threshold))
+        .map(|window| window[0].0)
+}
+
+/// Share of bytes a program half must match for it to count as a copy.
+pub const COPY_SIMILARITY: f64 = 0.75;
+
+/// One replication observed while running a pair: after execution, the
+/// `dest` cell's half held a copy of the `source` cell's program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CopyEvent {
+    pub source: usize,
+    pub dest: usize,
+}
+
+/// Fraction of positions at which `a` and `b` hold the same byte, over the
+/// shorter of the two.
+fn byte_similarity(a: &[u8], b: &[u8]) -> f64 {
+    let len = a.len().min(b.len());
+    if len == 0 {
+        return 0.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / len as f64
+}
+
+/// Whether a half that held `before` and now holds `after` became a copy of
+/// `source` during execution: `after` matches at least [`COPY_SIMILARITY`]
+/// of `source`'s bytes and `before` did not.
+pub fn is_copy(after: &[u8], source: &[u8], before: &[u8]) -> bool {
+    byte_similarity(after, source) >= COPY_SIMILARITY
+        && byte_similarity(before, source) < COPY_SIMILARITY
+}
+
+/// Which of `population` cells behaved as parasites over a window of copy
+/// events.
+///
+/// A cell is flagged when, within the window, it was overwritten by a copy
+/// of its partner at least once (it became a copy of someone else's
+/// program) but was never itself the source of a copy (its own program
+/// did not spread). Cells that neither copied nor were copied are not
+/// flagged. Events naming cells outside `0..population` are ignored.
+pub fn parasite_flags(events: &[CopyEvent], population: usize) -> Vec<bool> {
+    let mut received = vec![false; population];
+    let mut sourced = vec![false; population];
+    for event in events {
+        if event.source < population && event.dest < population {
+            sourced[event.source] = true;
+            received[event.dest] = true;
+        }
+    }
+    received
+        .into_iter()
+        .zip(sourced)
+        .map(|(received, sourced)| received && !sourced)
+        .collect()
+}
+
+/// Fraction of `population` cells flagged by [`parasite_flags`].
+pub fn parasite_fraction(events: &[CopyEvent], population: usize) -> f64 {
+    if population == 0 {
+        return 0.0;
+    }
+    let parasites = parasite_flags(events, population)
+        .into_iter()
+        .filter(|&flag| flag)
+        .count();
+    parasites as f64 / population as f64
+}
+
+/// Largest autocorrelation lag [`spectral_flatness`] transforms; the
+/// spectrum it measures has twice this many bins.
+#[cfg(feature = "spectral")]
+pub const SPECTRAL_MAX_LAG: usize = 256;
+
+/// Spectral flatness of the byte-value autocorrelation of `population`.
+///
+/// The autocorrelation of the mean-centered bytes (computed by FFT) is
+/// truncated at [`SPECTRAL_MAX_LAG`], tapered with a Bartlett window, and
+/// transformed into a power spectrum; the result is that spectrum's
+/// geometric mean over its arithmetic mean. Uncorrelated bytes give a flat
+/// spectrum and a value near 1.0; periodic structure, such as a repeated
+/// genome, concentrates power in a few bins and drives it toward 0.
+/// Empty and constant buffers have no spectrum and return 0.0.
+#[cfg(feature = "spectral")]
+pub fn spectral_flatness(population: &[u8]) -> f64 {
+    use rustfft::FftPlanner;
+    use rustfft::num_complex::Complex;
+
+    let n = population.len();
+    if n == 0 {
+        return 0.0;
+    }
+    let mean = population.iter().map(|&b| b as f64).sum::<f64>() / n as f64;
+
+    // Zero-pad to at least 2n so the circular autocorrelation is linear.
+    let padded = (2 * n).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let mut buf: Vec<Complex<f64>> = population
+        .iter()
+        .map(|&b| Complex::new(b as f64 - mean, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(padded)
+        .collect();
+    planner.plan_fft_forward(padded).process(&mut buf);
+    for c in &mut buf {
+        *c = Complex::new(c.norm_sqr(), 0.0);
+    }
+    planner.plan_fft_inverse(padded).process(&mut buf);
+    let variance = buf[0].re;
+    if variance <= 1e-9 * padded as f64 {
+        return 0.0;
+    }
+
+    // Symmetric, Bartlett-windowed autocorrelation: its transform is a
+    // smoothed, non-negative power spectrum.
+    let max_lag = SPECTRAL_MAX_LAG.min(n - 1);
+    let bins = 2 * max_lag;
+    let mut lags = vec![Complex::new(0.0, 0.0); bins];
+    for k in 0..max_lag {
+        let weight = 1.0 - k as f64 / max_lag as f64;
+        lags[k] = Complex::new(weight * buf[k].re / variance, 0.0);
+        if k > 0 {
+            lags[bins - k] = lags[k];
+        }
+    }
+    planner.plan_fft_forward(bins).process(&mut lags);
+
+    let power: Vec<f64> = lags.iter().map(|c| c.re.max(1e-12)).collect();
+    let log_mean = power.iter().map(|p| p.ln()).sum::<f64>() / bins as f64;
+    let mean_power = power.iter().sum::<f64>() / bins as f64;
+    log_mean.exp() / mean_power
+}
+
+/// Hash of the tape `S` leaves behind when `tape` is run at each of `limits`.
+///
+/// Each limit runs a fresh copy of `tape`. Once a limit passes the step at
+/// which the program halts, the hashes stop changing; a program that is
+/// still running at every limit keeps producing new ones. Useful for
+/// picking a `--step-limit` that lets typical programs finish.
+pub fn step_limit_sensitivity<S: Substrate>(tape: &[u8], limits: &[usize]) -> Vec<u64> {
+    limits
+        .iter()
+        .map(|&limit| {
+            let mut run = tape.to_vec();
+            S::execute(&mut run, limit);
+            let mut hasher = DefaultHasher::new();
+            run.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// The most common program in `programs`, ties going to the smallest.
+pub fn modal_program(programs: &[Vec<u8>]) -> Option<&[u8]> {
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for p in programs {
+        *counts.entry(p).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|(a, na), (b, nb)| na.cmp(nb).then_with(|| b.cmp(a)))
+        .map(|(program, _)| program)
+}
+
+/// The per-offset majority byte over `programs`, `program_size` bytes
+/// long, ties going to the smaller byte. Programs shorter than an offset
+/// don't vote there; an offset nobody reaches reads 0.
+pub fn consensus_program(programs: &[Vec<u8>], program_size: usize) -> Vec<u8> {
+    (0..program_size)
+        .map(|i| {
+            let mut counts = [0usize; 256];
+            for p in programs.iter().filter(|p| p.len() > i) {
+                counts[p[i] as usize] += 1;
+            }
+            (0..=255u8)
+                .max_by(|&a, &b| counts[a as usize].cmp(&counts[b as usize]).then(b.cmp(&a)))
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Mean Hamming distance from each program to `consensus`, counting any
+/// difference in length as that many differing bytes. Falls toward 0 as
+/// the population homogenizes; 0 for an empty population.
+pub fn mean_distance_to_consensus(programs: &[Vec<u8>], consensus: &[u8]) -> f64 {
+    if programs.is_empty() {
+        return 0.0;
+    }
+    let total: usize = programs
+        .iter()
+        .map(|p| {
+            let differing = p.iter().zip(consensus).filter(|(a, b)| a != b).count();
+            differing + p.len().abs_diff(consensus.len())
+        })
+        .sum();
+    total as f64 / programs.len() as f64
+}
+
+/// Mutual information, in bits, between the bytes at offsets `i` and
+/// `i + 1` across the population, for each `i` in `0..program_size - 1`.
+/// High values mark positions that co-evolve, such as an opcode and its
+/// operand. Programs shorter than `program_size` are skipped. The plug-in
+/// estimate reads high when the population is small next to the number
+/// of distinct byte pairs it holds.
+pub fn positional_mutual_information(programs: &[Vec<u8>], program_size: usize) -> Vec<f64> {
+    let programs: Vec<&[u8]> = programs
+        .iter()
+        .filter(|p| p.len() >= program_size)
+        .map(|p| &p[..program_size])
+        .collect();
+    let n = programs.len() as f64;
+    let mut joint = vec![0u32; 256 * 256];
+    (0..program_size.saturating_sub(1))
+        .map(|i| {
+            let mut left = [0u32; 256];
+            let mut right = [0u32; 256];
+            for p in &programs {
+                left[p[i] as usize] += 1;
+                right[p[i + 1] as usize] += 1;
+                joint[(p[i] as usize) << 8 | p[i + 1] as usize] += 1;
+            }
+            // Sum over the pairs present, zeroing each as it's counted.
+            let mut mi = 0.0;
+            for p in &programs {
+                let (x, y) = (p[i] as usize, p[i + 1] as usize);
+                let count = std::mem::take(&mut joint[x << 8 | y]);
+                if count > 0 {
+                    let pxy = count as f64 / n;
+                    let px = left[x] as f64 / n;
+                    let py = right[y] as f64 / n;
+                    mi += pxy * (pxy / (px * py)).log2();
+                }
+            }
+            mi
+        })
+        .collect()
+}
+
+/// Fraction of `program`'s bytes that `S` dispatches an instruction from
+/// when the program runs against an all-zero partner, or `None` if `S`
+/// can't report executed positions. Bytes never reached are dead weight:
+/// compact replicators score near 1, bloated ones low.
+pub fn active_fraction<S: Substrate>(program: &[u8], step_limit: usize) -> Option<f64> {
+    if program.is_empty() {
+        return None;
+    }
+    let mut tape = program.to_vec();
+    tape.resize(2 * program.len(), 0);
+    let mut visited = vec![false; tape.len()];
+    S::execute_profiled(&mut tape, step_limit, &mut visited)?;
+    let active = visited[..program.len()].iter().filter(|&&v| v).count();
+    Some(active as f64 / program.len() as f64)
+}
+
+/// How many of the `sample` most common distinct programs are exact
+/// self-replicators: run against an all-zero partner of the same length,
+/// the partner ends up byte-identical to the program. Ties in frequency go
+/// to the smaller program, so the same population always gives the same
+/// count. Empty programs never count.
+pub fn count_quines<S: Substrate>(programs: &[Vec<u8>], step_limit: usize, sample: usize) -> usize {
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for p in programs.iter().filter(|p| !p.is_empty()) {
+        *counts.entry(p).or_default() += 1;
+    }
+    let mut genotypes: Vec<(&[u8], usize)> = counts.into_iter().collect();
+    genotypes.sort_by(|(a, na), (b, nb)| nb.cmp(na).then_with(|| a.cmp(b)));
+    let mut tape = Vec::new();
+    genotypes
+        .into_iter()
+        .take(sample)
+        .filter(|&(program, _)| {
+            tape.clear();
+            tape.extend_from_slice(program);
+            tape.resize(2 * program.len(), 0);
+            S::execute(&mut tape, step_limit);
+            tape[program.len()..] == *program
+        })
+        .count()
+}
+
+/// Fraction of `program`'s single-bit mutants that still replicate: each
+/// of the `8 * len` one-bit flips runs against a partner holding the
+/// bitwise complement of the mutant, and counts if afterwards the partner
+/// [`is_copy`] of it. The complement can never already resemble the
+/// program, so even mostly-zero programs are judged fairly. Returns 0 for
+/// an empty program.
+pub fn mutational_robustness<S: Substrate>(program: &[u8], step_limit: usize) -> f64 {
+    let len = program.len();
+    if len == 0 {
+        return 0.0;
+    }
+    let mut tape = vec![0u8; 2 * len];
+    let mut replicating = 0;
+    for bit in 0..8 * len {
+        let mut mutant = program.to_vec();
+        mutant[bit / 8] ^= 1 << (bit % 8);
+        let partner: Vec<u8> = mutant.iter().map(|b| !b).collect();
+        tape[..len].copy_from_slice(&mutant);
+        tape[len..].copy_from_slice(&partner);
+        S::execute(&mut tape, step_limit);
+        if is_copy(&tape[len..], &mutant, &partner) {
+            replicating += 1;
+        }
+    }
+    replicating as f64 / (8 * len) as f64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bff::Bff;
+    use crate::forth::Forth;
+    use crate::z80::Z80;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_positional_mutual_information() {
+        // Offset 1 is always offset 0 plus one; offset 2 is independent.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let programs: Vec<Vec<u8>> = (0..4096)
+            .map(|_| {
+                let a = rng.gen_range(0..4u8);
+                vec![a, a + 1, rng.gen_range(0..4u8)]
+            })
+            .collect();
+        let mi = positional_mutual_information(&programs, 3);
+        assert_eq!(mi.len(), 2);
+        // Two bits: offset 0 is uniform over four values.
+        assert!((mi[0] - 2.0).abs() < 0.01, "coupled MI {}", mi[0]);
+        assert!(mi[1] < 0.01, "independent MI {}", mi[1]);
+        assert!(positional_mutual_information(&programs, 1).is_empty());
+        assert_eq!(positional_mutual_information(&[], 3), [0.0, 0.0]);
+    }
 
     #[test]
     fn test_hoe_random_data_near_one() {
@@ -135,4 +505,239 @@ mod tests {
         let hist = byte_frequency_histogram(&programs);
         assert_eq!(hist.iter().sum::<usize>(), 0);
     }
+
+    #[test]
+    fn test_chi_square_uniform_is_zero() {
+        assert_eq!(uniformity_chi_square(&[10; 256]), 0.0);
+    }
+
+    #[test]
+    fn test_chi_square_single_value_is_huge() {
+        let mut hist = [0usize; 256];
+        hist[42] = 2560;
+        // (2560 - 10)²/10 + 255 · 10 = 255 · 2560
+        assert!((uniformity_chi_square(&hist) - 255.0 * 2560.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_chi_square_random_near_dof() {
+        use rand::{Rng, SeedableRng, rngs::SmallRng};
+        let mut rng = SmallRng::seed_from_u64(7);
+        let programs: Vec<Vec<u8>> = (0..1024)
+            .map(|_| (0..64).map(|_| rng.r#gen()).collect())
+            .collect();
+        let chi2 = uniformity_chi_square(&byte_frequency_histogram(&programs));
+        assert!((150.0..400.0).contains(&chi2), "chi2 = {chi2}");
+    }
+
+    #[test]
+    fn test_chi_square_empty() {
+        assert_eq!(uniformity_chi_square(&[0; 256]), 0.0);
+    }
+
+    #[test]
+    fn test_is_copy_requires_change() {
+        let source = [1u8, 2, 3, 4];
+        assert!(is_copy(&[1, 2, 3, 0], &source, &[0; 4]));
+        assert!(!is_copy(&[1, 2, 0, 0], &source, &[0; 4]));
+        // Already a copy before execution: nothing was copied.
+        assert!(!is_copy(&source, &source, &source));
+    }
+
+    #[test]
+    fn test_receiving_lineage_is_parasitic() {
+        // Cell 0 copies itself aggressively into 1 and 2; cells 1 and 2 only
+        // ever receive; cell 3 takes no part.
+        let events = [
+            CopyEvent { source: 0, dest: 1 },
+            CopyEvent { source: 0, dest: 2 },
+            CopyEvent { source: 0, dest: 1 },
+        ];
+        assert_eq!(parasite_flags(&events, 4), [false, true, true, false]);
+        assert_eq!(parasite_fraction(&events, 4), 0.5);
+    }
+
+    #[test]
+    fn test_copying_back_clears_parasite_flag() {
+        let events = [
+            CopyEvent { source: 0, dest: 1 },
+            CopyEvent { source: 1, dest: 2 },
+        ];
+        assert_eq!(parasite_flags(&events, 3), [false, false, true]);
+    }
+
+    #[test]
+    fn test_parasite_fraction_empty() {
+        assert_eq!(parasite_fraction(&[], 0), 0.0);
+        assert_eq!(parasite_fraction(&[], 10), 0.0);
+    }
+
+    #[test]
+    fn test_takeover_monotone_decrease() {
+        let series: Vec<(usize, f64)> = (0..10).map(|i| (i * 100, 1.0 - 0.1 * i as f64)).collect();
+        // HOE = 0.4 at epoch 600 is the first sample below 0.45.
+        assert_eq!(takeover_epoch(&series, 0.45), Some(600));
+        assert_eq!(takeover_epoch(&series, 0.0), None);
+    }
+
+    #[test]
+    fn test_takeover_ignores_transient_dips() {
+        let series = [
+            (0, 1.0),
+            (10, 0.3),
+            (20, 0.9),
+            (30, 0.3),
+            (40, 0.3),
+            (50, 0.8),
+            (60, 0.2),
+            (70, 0.2),
+            (80, 0.2),
+            (90, 0.2),
+        ];
+        assert_eq!(takeover_epoch(&series, 0.5), Some(60));
+    }
+
+    #[test]
+    fn test_takeover_needs_full_window() {
+        let series = [(0, 1.0), (10, 1.0), (20, 0.1), (30, 0.1)];
+        assert_eq!(takeover_epoch(&series, 0.5), None);
+        assert_eq!(takeover_epoch(&[], 0.5), None);
+    }
+
+    #[test]
+    fn test_step_limit_sensitivity_stabilizes_after_halt() {
+        // PUSH 1, PUSH 10, WRITE, then no-ops to the end of a 16-byte tape.
+        let mut tape = vec![0x0Fu8; 16];
+        tape[..3].copy_from_slice(&[0x41, 0x4A, 0x02]);
+        let hashes = step_limit_sensitivity::<Forth>(&tape, &[2, 16, 32, 1000]);
+        // At 2 steps the write has not happened yet.
+        assert_ne!(hashes[0], hashes[1]);
+        assert!(hashes[1..].iter().all(|&h| h == hashes[1]));
+    }
+
+    #[test]
+    fn test_step_limit_sensitivity_of_endless_loop() {
+        // PUSH 20, READ, INC, PUSH 20, WRITE, JMP 0: increments tape[20]
+        // once per six steps, forever.
+        let mut tape = vec![0u8; 32];
+        tape[..6].copy_from_slice(&[0x54, 0x00, 0x08, 0x54, 0x02, 0xC4]);
+        let hashes = step_limit_sensitivity::<Forth>(&tape, &[60, 120, 240]);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[1], hashes[2]);
+        assert_ne!(hashes[0], hashes[2]);
+    }
+
+    #[test]
+    fn test_active_fraction_counts_reached_bytes() {
+        // ++ turns tape[0] into '-', so ] finds no [ behind it and halts:
+        // only the first three bytes ever run.
+        let mut program = vec![0u8; 16];
+        program[..3].copy_from_slice(b"++]");
+        assert_eq!(active_fraction::<Bff>(&program, 1000), Some(3.0 / 16.0));
+
+        // The endless loop from above never leaves its first six bytes.
+        let mut program = vec![0u8; 16];
+        program[..6].copy_from_slice(&[0x54, 0x00, 0x08, 0x54, 0x02, 0xC4]);
+        assert_eq!(active_fraction::<Forth>(&program, 1000), Some(6.0 / 16.0));
+
+        assert_eq!(active_fraction::<Z80>(&program, 1000), None);
+    }
+
+    /// A Forth loop copying bytes `n..1` of a program into its partner:
+    /// PUSH n, then DUP COPY DEC SKIPNZ, leaving by a long forward jump
+    /// once the counter hits zero and looping back otherwise.
+    fn forth_copier(program_size: usize) -> Vec<u8> {
+        let mut program = vec![0x0Fu8; program_size];
+        let n = program_size as u8 - 1;
+        program[..7].copy_from_slice(&[0x40 | n, 0x04, 0x0C, 0x09, 0x07, 0xBF, 0xC4]);
+        program
+    }
+
+    #[test]
+    fn test_count_quines() {
+        // forth_copier's loop, testing the counter before decrementing it,
+        // so byte 0 is copied too before the jump out.
+        let mut quine = vec![0x0Fu8; 64];
+        quine[..7].copy_from_slice(&[0x40 | 63, 0x04, 0x0C, 0x07, 0xBF, 0x09, 0xC4]);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let random: Vec<Vec<u8>> = (0..50)
+            .map(|_| (0..64).map(|_| rng.r#gen()).collect())
+            .collect();
+        assert_eq!(count_quines::<Forth>(&random, 8192, 50), 0);
+
+        let mut programs = random.clone();
+        programs.extend(vec![quine.clone(); 3]);
+        assert_eq!(count_quines::<Forth>(&programs, 8192, 50), 1);
+        // The sample takes the most common genotypes first.
+        assert_eq!(count_quines::<Forth>(&programs, 8192, 1), 1);
+        // Bytes n..1 alone aren't an exact copy.
+        assert_eq!(count_quines::<Forth>(&[forth_copier(64)], 8192, 1), 0);
+        assert_eq!(count_quines::<Forth>(&[vec![]], 8192, 1), 0);
+    }
+
+    #[test]
+    fn test_mutational_robustness() {
+        // Packed, nearly every bit is part of the copy loop.
+        let fragile = mutational_robustness::<Forth>(&forth_copier(7), 1000);
+        // Padded with no-ops that never run, most flips are neutral.
+        let robust = mutational_robustness::<Forth>(&forth_copier(64), 1000);
+        assert!(fragile < 0.5, "packed copier robustness {fragile}");
+        assert!(robust > 0.8, "padded copier robustness {robust}");
+
+        assert_eq!(mutational_robustness::<Forth>(&[0x0F; 8], 1000), 0.0);
+        assert_eq!(mutational_robustness::<Forth>(&[], 1000), 0.0);
+    }
+
+    #[test]
+    fn test_consensus_of_identical_programs() {
+        let programs = vec![vec![4, 0, 9]; 5];
+        let consensus = consensus_program(&programs, 3);
+        assert_eq!(consensus, programs[0]);
+        assert_eq!(mean_distance_to_consensus(&programs, &consensus), 0.0);
+        assert_eq!(mean_distance_to_consensus(&[], &consensus), 0.0);
+    }
+
+    #[test]
+    fn test_consensus_with_half_differing_in_one_byte() {
+        let mut programs = vec![vec![1, 2, 3, 4]; 6];
+        // Three of six differ at offset 2, two of them the same way.
+        for p in &mut programs[..2] {
+            p[2] = 7;
+        }
+        programs[2][2] = 8;
+        let consensus = consensus_program(&programs, 4);
+        assert_eq!(consensus, [1, 2, 3, 4]);
+        assert_eq!(mean_distance_to_consensus(&programs, &consensus), 0.5);
+        // An even split at an offset goes to the smaller byte.
+        assert_eq!(consensus_program(&[vec![9], vec![5]], 2), [5, 0]);
+    }
+
+    #[test]
+    fn test_modal_program() {
+        let programs = vec![vec![2, 2], vec![1, 1], vec![2, 2], vec![1, 1], vec![3, 3]];
+        assert_eq!(modal_program(&programs), Some(&[1u8, 1][..]));
+        assert_eq!(modal_program(&programs[..3]), Some(&[2u8, 2][..]));
+        assert_eq!(modal_program(&[]), None);
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn test_spectral_flatness_white_noise_near_one() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let data: Vec<u8> = (0..1 << 16).map(|_| rng.r#gen()).collect();
+        let flatness = spectral_flatness(&data);
+        assert!(flatness > 0.9, "white noise flatness {flatness}");
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn test_spectral_flatness_periodic_is_low() {
+        let genome: Vec<u8> = (0..16).map(|i| (i * 37 % 256) as u8).collect();
+        let data = genome.repeat(4096);
+        let flatness = spectral_flatness(&data);
+        assert!(flatness < 0.1, "periodic flatness {flatness}");
+        assert_eq!(spectral_flatness(&[]), 0.0);
+        assert_eq!(spectral_flatness(&[5; 100]), 0.0);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::substrate::Substrate;
+use crate::substrate::{Substrate, validate_16bit_program_size};
 
 // The module `crate::mos6502` shadows the external crate name, so we use
 // leading `::` to refer to the external crate throughout this file.
@@ -69,6 +69,10 @@ impl Substrate for Mos6502 {
         steps
     }
 
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        validate_16bit_program_size(program_size)
+    }
+
     fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
         if tape.is_empty() {
             return 0;
@@ -0,0 +1,84 @@
+//! Single evolved programs saved as shareable `.life` files.
+//!
+//! A `.life` file is the [`MAGIC`] header followed by the organism as
+//! JSON. The header lets a loader reject some other file handed to it by
+//! mistake before trying to parse it.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// First bytes of every `.life` file: a tag and a format version.
+pub const MAGIC: &[u8; 5] = b"LIFE\x01";
+
+/// One program lifted out of a soup, with where it came from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Organism {
+    /// Substrate name as accepted by `--substrate`.
+    pub substrate: String,
+    /// The program; its length is the program size it evolved at.
+    pub bytes: Vec<u8>,
+    /// Epoch at which the program was saved.
+    pub epoch: usize,
+    /// Free-form description.
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Organism {
+    /// Encode as the contents of a `.life` file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.extend(serde_json::to_vec(self).expect("organisms always serialize"));
+        out
+    }
+
+    /// Decode the contents of a `.life` file.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let body = data
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or("Not an organism file: missing LIFE header")?;
+        serde_json::from_slice(body).map_err(|e| format!("Invalid organism: {e}"))
+    }
+
+    /// Write the organism to `path`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Read an organism written by [`Organism::save`].
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::from_bytes(&data).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let organism = Organism {
+            substrate: "bff".to_string(),
+            bytes: vec![b'[', b'.', b'{', b'}', 0, 255],
+            epoch: 1234,
+            note: "first replicator".to_string(),
+        };
+        let path = std::env::temp_dir().join("complife_test_organism.life");
+        organism.save(&path).unwrap();
+        assert_eq!(Organism::load(&path).unwrap(), organism);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_rejects_missing_header() {
+        let json = br#"{"substrate":"bff","bytes":[1],"epoch":0}"#;
+        assert!(Organism::from_bytes(json).is_err());
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(json);
+        assert_eq!(Organism::from_bytes(&data).unwrap().note, "");
+    }
+}
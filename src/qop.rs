@@ -208,6 +208,35 @@ impl Substrate for Qop {
         byte <= GET_TAIL
     }
 
+    fn opcode_table() -> &'static [(u8, &'static str, &'static str)] {
+        &[
+            (HALT, "HALT", "Stop"),
+            (
+                PASS,
+                "PASS",
+                "Copy the byte at head to tail; head++, tail++",
+            ),
+            (EAT, "EAT", "acc = byte at head; head++"),
+            (SPIT, "SPIT", "Write acc at tail; tail++"),
+            (SKIP, "SKIP", "head++"),
+            (GAP, "GAP", "Write 0 at tail; tail++"),
+            (INC, "INC", "acc++"),
+            (DEC, "DEC", "acc--"),
+            (XOR, "XOR", "acc ^= byte at head"),
+            (JMP_REL, "JMP_REL n", "Jump by the signed next byte"),
+            (JZ, "JZ n", "JMP_REL if acc is 0, else skip the operand"),
+            (
+                JNZ,
+                "JNZ n",
+                "JMP_REL if acc isn't 0, else skip the operand",
+            ),
+            (SET_HEAD, "SET_HEAD", "head = acc"),
+            (SET_TAIL, "SET_TAIL", "tail = acc"),
+            (GET_HEAD, "GET_HEAD", "acc = head"),
+            (GET_TAIL, "GET_TAIL", "acc = tail"),
+        ]
+    }
+
     fn disassemble(tape: &[u8]) -> String {
         use std::fmt::Write;
         let mut out = String::new();
@@ -69,19 +69,15 @@ fn rig_step(state: &mut RigState, tape: &mut [u8]) -> bool {
             // DEC: r[dst]-- (src ignored)
             state.r[dst] = state.r[dst].wrapping_sub(1);
         }
-        0x8 => {
+        0x8 if state.r[src] == 0 => {
             // JZ: if r[src] == 0, pc = r[dst] as usize
-            if state.r[src] == 0 {
-                state.pc = state.r[dst] as usize;
-                return true;
-            }
+            state.pc = state.r[dst] as usize;
+            return true;
         }
-        0x9 => {
+        0x9 if state.r[src] != 0 => {
             // JNZ: if r[src] != 0, pc = r[dst] as usize
-            if state.r[src] != 0 {
-                state.pc = state.r[dst] as usize;
-                return true;
-            }
+            state.pc = state.r[dst] as usize;
+            return true;
         }
         0xA => {
             // COPY: tape[r[dst]] = tape[r[src]]
@@ -72,19 +72,15 @@ fn skim_step(state: &mut SkimState, tape: &mut [u8]) -> bool {
             // GET_WP: acc = wp
             state.acc = state.wp;
         }
-        0xA => {
+        0xA if state.acc != 0 => {
             // SKZ: if acc == 0, use normal skip; else skip 1
-            if state.acc != 0 {
-                state.pc = (state.pc + 1) % len;
-                return true;
-            }
+            state.pc = (state.pc + 1) % len;
+            return true;
         }
-        0xB => {
+        0xB if state.acc == 0 => {
             // SKNZ: if acc != 0, use normal skip; else skip 1
-            if state.acc == 0 {
-                state.pc = (state.pc + 1) % len;
-                return true;
-            }
+            state.pc = (state.pc + 1) % len;
+            return true;
         }
         0xC => {
             // HALT
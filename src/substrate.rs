@@ -1,3 +1,33 @@
+// Opcode categories returned by `Substrate::instruction_class`.
+
+/// Not an instruction (a no-op byte).
+pub const CLASS_NOP: u8 = 0;
+/// An instruction the substrate doesn't sort into a finer class.
+pub const CLASS_OTHER: u8 = 1;
+/// Arithmetic on values: increment, add, subtract, clear.
+pub const CLASS_ARITHMETIC: u8 = 2;
+/// Moves values between stack slots or registers without touching the tape.
+pub const CLASS_DATA: u8 = 3;
+/// Reads, writes, or copies tape bytes.
+pub const CLASS_MEMORY: u8 = 4;
+/// Moves heads or computes addresses.
+pub const CLASS_POINTER: u8 = 5;
+/// Jumps, loops, and conditional skips.
+pub const CLASS_CONTROL: u8 = 6;
+/// Number of distinct instruction classes.
+pub const NUM_CLASSES: usize = 7;
+
+/// Why an execution stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Halt {
+    /// The program stopped itself before the step limit.
+    Halted,
+    /// The program counter ran off the end of the tape.
+    OffTape,
+    /// The step limit ran out first.
+    StepLimit,
+}
+
 /// A computational substrate that defines how programs are executed on a tape.
 ///
 /// Each instruction set (BFF, Forth, Z80, etc.) implements this trait.
@@ -13,6 +43,22 @@ pub trait Substrate {
     /// Returns the number of steps actually executed.
     fn execute(tape: &mut [u8], step_limit: usize) -> usize;
 
+    /// Execute like [`Substrate::execute`], returning the steps used and
+    /// why execution stopped.
+    ///
+    /// Default: [`Halt::StepLimit`] if every step was used and
+    /// [`Halt::Halted`] otherwise, for substrates that don't tell running
+    /// off the tape apart from other ways of stopping.
+    fn execute_status(tape: &mut [u8], step_limit: usize) -> (usize, Halt) {
+        let steps = Self::execute(tape, step_limit);
+        let halt = if steps >= step_limit {
+            Halt::StepLimit
+        } else {
+            Halt::Halted
+        };
+        (steps, halt)
+    }
+
     /// Execute two programs interleaved on a shared tape.
     ///
     /// `program_size` is the size of each program (`tape.len() / 2`).
@@ -29,6 +75,71 @@ pub trait Substrate {
     /// (as opposed to a no-op). Used for visualization.
     fn is_instruction(byte: u8) -> bool;
 
+    /// Coarse category of `byte` as an opcode, one of the `CLASS_*`
+    /// constants. Used for visualization.
+    ///
+    /// Default: `CLASS_OTHER` for instructions, `CLASS_NOP` otherwise.
+    fn instruction_class(byte: u8) -> u8 {
+        if Self::is_instruction(byte) {
+            CLASS_OTHER
+        } else {
+            CLASS_NOP
+        }
+    }
+
     /// Pretty-print a disassembly of the given tape for human inspection.
     fn disassemble(tape: &[u8]) -> String;
+
+    /// `(opcode, mnemonic, description)` for each instruction, in opcode
+    /// order, as printed by `--describe`. Bytes not listed are no-ops.
+    ///
+    /// Default: empty, for substrates whose encodings don't fit one row
+    /// per opcode byte.
+    fn opcode_table() -> &'static [(u8, &'static str, &'static str)] {
+        &[]
+    }
+
+    /// Step limit to use for `program_size`-byte programs when none is
+    /// given explicitly.
+    ///
+    /// Default: 2^13, the paper's budget for the byte-tape languages.
+    fn recommended_step_limit(program_size: usize) -> usize {
+        let _ = program_size;
+        1 << 13
+    }
+
+    /// Execute like [`Substrate::execute`], also setting `visited[i]` for
+    /// every tape position `i` an instruction is dispatched from. `visited`
+    /// is as long as `tape`.
+    ///
+    /// Default: `None`, for substrates that can't report positions.
+    fn execute_profiled(tape: &mut [u8], step_limit: usize, visited: &mut [bool]) -> Option<usize> {
+        let _ = (tape, step_limit, visited);
+        None
+    }
+
+    /// Check that `program_size`-byte programs run as intended, explaining
+    /// why not otherwise.
+    ///
+    /// Default: every size is fine.
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        let _ = program_size;
+        Ok(())
+    }
+}
+
+/// Largest program size whose two-program tape fits a 16-bit address space.
+pub const MAX_16BIT_PROGRAM_SIZE: usize = 1 << 15;
+
+/// `validate_program_size` for CPUs that address the tape with 16-bit
+/// addresses: past [`MAX_16BIT_PROGRAM_SIZE`] they can't reach the whole
+/// tape, and the second program's start no longer fits in a register.
+pub fn validate_16bit_program_size(program_size: usize) -> Result<(), String> {
+    if program_size > MAX_16BIT_PROGRAM_SIZE {
+        return Err(format!(
+            "{program_size}-byte programs make a tape larger than 16-bit addresses reach; \
+             use a program size of at most {MAX_16BIT_PROGRAM_SIZE}"
+        ));
+    }
+    Ok(())
 }
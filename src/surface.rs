@@ -1,18 +1,22 @@
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 
 use rand::Rng;
+use rand::RngCore;
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::substrate::Substrate;
+use crate::log;
+use crate::metrics::{CopyEvent, is_copy};
+use crate::substrate::{Halt, Substrate};
 
 // ─── Interaction mode ────────────────────────────────────────────────────────
 
 /// How two neighboring programs interact when paired during an epoch.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum InteractionMode {
     /// Current behavior: concatenate [A|B] and execute from position 0.
     #[default]
@@ -33,6 +37,465 @@ impl InteractionMode {
     }
 }
 
+// ─── Mutation model ──────────────────────────────────────────────────────────
+
+/// How a mutation site chooses the byte's new value.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum MutationModel {
+    /// Flip one uniformly chosen bit (the paper's model).
+    #[default]
+    BitFlip,
+    /// Replace byte `b` with a value drawn from row `b` of a 256×256
+    /// substitution matrix. Rows are relative weights and need not sum to 1.
+    Substitution(#[serde(with = "substitution_matrix")] Box<[[f32; 256]; 256]>),
+}
+
+impl MutationModel {
+    /// Substitution matrix in which every byte mutates to an instruction byte
+    /// (per `is_instruction`) `bias` times more often than to a non-instruction
+    /// byte. A byte never "mutates" to itself.
+    pub fn toward_instructions(is_instruction: fn(u8) -> bool, bias: f32) -> Self {
+        let mut matrix: Box<[[f32; 256]; 256]> = vec![[0.0f32; 256]; 256]
+            .into_boxed_slice()
+            .try_into()
+            .expect("vec has exactly 256 rows");
+        for (from, row) in matrix.iter_mut().enumerate() {
+            for (to, weight) in row.iter_mut().enumerate() {
+                *weight = if to == from {
+                    0.0
+                } else if is_instruction(to as u8) {
+                    bias
+                } else {
+                    1.0
+                };
+            }
+        }
+        MutationModel::Substitution(matrix)
+    }
+
+    /// Apply one mutation to `byte`.
+    fn apply(&self, byte: u8, rng: &mut impl Rng) -> u8 {
+        match self {
+            MutationModel::BitFlip => byte ^ (1u8 << rng.gen_range(0..8)),
+            MutationModel::Substitution(matrix) => {
+                let row = &matrix[byte as usize];
+                let total: f32 = row.iter().sum();
+                if total <= 0.0 {
+                    return byte;
+                }
+                let mut r = rng.r#gen::<f32>() * total;
+                for (to, &weight) in row.iter().enumerate() {
+                    if r < weight {
+                        return to as u8;
+                    }
+                    r -= weight;
+                }
+                // Float rounding left a sliver: take the last reachable value.
+                row.iter().rposition(|&w| w > 0.0).unwrap_or(0) as u8
+            }
+        }
+    }
+}
+
+/// Serde for a substitution matrix as 256 rows of 256 weights; serde has no
+/// built-in support for arrays this large.
+mod substitution_matrix {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        matrix: &[[f32; 256]; 256],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let rows: Vec<&[f32]> = matrix.iter().map(|row| &row[..]).collect();
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Box<[[f32; 256]; 256]>, D::Error> {
+        let rows = Vec::<Vec<f32>>::deserialize(deserializer)?;
+        let rows: Vec<[f32; 256]> = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<_, _>>()
+            .map_err(|_| D::Error::custom("substitution matrix rows must have 256 weights"))?;
+        rows.into_boxed_slice()
+            .try_into()
+            .map_err(|_| D::Error::custom("substitution matrix must have 256 rows"))
+    }
+}
+
+/// Controller that raises the mutation rate while diversity is collapsed.
+///
+/// After each metrics interval the soup compares its unique-program fraction
+/// with `target`. Below it, the effective rate is multiplied by
+/// [`AdaptiveMutation::FACTOR`] up to `max_rate`; at or above it, the rate is
+/// divided by the same factor back down to the configured baseline. A zero
+/// baseline therefore never ramps up.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveMutation {
+    /// Unique programs as a fraction of the population below which the
+    /// rate ramps up.
+    pub target: f64,
+    /// Ceiling for the effective mutation rate.
+    pub max_rate: f64,
+}
+
+impl AdaptiveMutation {
+    /// Per-interval multiplier applied while ramping up or relaxing.
+    pub const FACTOR: f64 = 2.0;
+
+    /// Effective rate for the next interval, given the current one, the
+    /// configured baseline, and the observed unique-program fraction.
+    pub fn next_rate(&self, current: f64, baseline: f64, diversity: f64) -> f64 {
+        let next = if diversity < self.target {
+            (current * Self::FACTOR).min(self.max_rate)
+        } else {
+            current / Self::FACTOR
+        };
+        next.max(baseline)
+    }
+}
+
+impl Default for AdaptiveMutation {
+    fn default() -> Self {
+        Self {
+            target: 0.1,
+            max_rate: 0.01,
+        }
+    }
+}
+
+/// Cells whose programs mutate faster than the rest.
+///
+/// When the soup is seeded, each cell becomes a mutator with probability
+/// `fraction`, mutating at `strength` times the soup's rate. The multiplier
+/// is heritable: a program copied over another carries it along, so mutator
+/// lineages spread or die out with the programs they host.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mutators {
+    pub fraction: f64,
+    pub strength: f64,
+}
+
+impl Default for Mutators {
+    fn default() -> Self {
+        Self {
+            fraction: 0.01,
+            strength: 10.0,
+        }
+    }
+}
+
+// ─── Selection ───────────────────────────────────────────────────────────────
+
+/// What happens to a pair after its tape has been executed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelectionMode {
+    /// Both halves are written back as-is; any "winner" is emergent.
+    #[default]
+    None,
+    /// The half with the higher fitness overwrites the other. Ties leave
+    /// both halves untouched.
+    Tournament(Fitness),
+}
+
+/// Where a soup's randomness comes from from one epoch to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum RngMode {
+    /// One generator seeded once and drawn from for the whole run.
+    #[default]
+    Streaming,
+    /// Reseed at the top of every `run_epoch` and `mutate` from the seed
+    /// and epoch number, so an epoch's draws never depend on how many
+    /// earlier epochs made.
+    PerEpoch,
+}
+
+/// [`RngCore`] wrapper that counts the draws made from `inner`: one per
+/// `next_u32`, `next_u64` or fill call, however many bytes it fills.
+#[derive(Clone, Debug)]
+pub struct CountingRng<R> {
+    inner: R,
+    draws: u64,
+}
+
+impl<R> CountingRng<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, draws: 0 }
+    }
+
+    /// Draws made so far.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl<R: RngCore> RngCore for CountingRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 1;
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draws += 1;
+        self.inner.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.draws += 1;
+        self.inner.try_fill_bytes(dest)
+    }
+}
+
+/// A soup's generator: a bare [`SmallRng`], or one wrapped in a
+/// [`CountingRng`] under [`SoupSurfaceConfig::count_rng`].
+#[derive(Clone, Debug)]
+pub enum SoupRng {
+    Bare(SmallRng),
+    Counting(CountingRng<SmallRng>),
+}
+
+impl SoupRng {
+    fn new(seed: u64, counting: bool) -> Self {
+        let rng = SmallRng::seed_from_u64(seed);
+        if counting {
+            SoupRng::Counting(CountingRng::new(rng))
+        } else {
+            SoupRng::Bare(rng)
+        }
+    }
+
+    /// Restart the generator from `seed`, keeping any draw count.
+    fn reseed(&mut self, seed: u64) {
+        match self {
+            SoupRng::Bare(rng) => *rng = SmallRng::seed_from_u64(seed),
+            SoupRng::Counting(counting) => counting.inner = SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Draws made so far; always 0 when not counting.
+    fn draws(&self) -> u64 {
+        match self {
+            SoupRng::Bare(_) => 0,
+            SoupRng::Counting(counting) => counting.draws(),
+        }
+    }
+}
+
+impl RngCore for SoupRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            SoupRng::Bare(rng) => rng.next_u32(),
+            SoupRng::Counting(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            SoupRng::Bare(rng) => rng.next_u64(),
+            SoupRng::Counting(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            SoupRng::Bare(rng) => rng.fill_bytes(dest),
+            SoupRng::Counting(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            SoupRng::Bare(rng) => rng.try_fill_bytes(dest),
+            SoupRng::Counting(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Draws each phase of an epoch made from a counting soup RNG.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RngDraws {
+    /// Shuffling the visiting order, self-pairing, and picking neighbors.
+    pub pairing: u64,
+    /// Coin flips deciding which program of a pair runs first.
+    pub orientation: u64,
+    pub write_back: u64,
+    pub mutation: u64,
+}
+
+impl std::fmt::Display for RngDraws {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "pairing {}, orientation {}, write-back {}, mutation {}",
+            self.pairing, self.orientation, self.write_back, self.mutation
+        )
+    }
+}
+
+/// A uniform flow over the surface that biases which program of a pair
+/// executes first.
+///
+/// For a pair whose centroids are separated by the unit direction `d`
+/// (from the first candidate to the second), the first candidate is made
+/// the aggressor with probability `(1 + tanh(drift · d)) / 2`: programs
+/// upwind of their partner tend to run over it, so replicators spread
+/// downwind. Zero drift keeps the unbiased coin flip.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Drift(pub [f32; 3]);
+
+impl Drift {
+    /// Parse `--drift`: three comma-separated components `X,Y,Z`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = s.split(',').collect();
+        let [x, y, z] = parts[..] else {
+            return Err(format!("Invalid drift '{s}': expected X,Y,Z"));
+        };
+        let component = |c: &str| {
+            c.trim()
+                .parse::<f32>()
+                .map_err(|e| format!("Invalid drift component '{c}': {e}"))
+        };
+        Ok(Drift([component(x)?, component(y)?, component(z)?]))
+    }
+
+    /// Whether this drift leaves pair orientation unbiased.
+    pub fn is_zero(self) -> bool {
+        self.0 == [0.0; 3]
+    }
+
+    /// Probability that a program at `from` is the aggressor against a
+    /// partner at `to`.
+    fn upwind_probability(self, from: [f32; 3], to: [f32; 3]) -> f64 {
+        let d = [to[0] - from[0], to[1] - from[1], to[2] - from[2]];
+        let len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        if len == 0.0 {
+            return 0.5;
+        }
+        let along = (self.0[0] * d[0] + self.0[1] * d[1] + self.0[2] * d[2]) / len;
+        0.5 * (1.0 + (along as f64).tanh())
+    }
+}
+
+/// Whether an epoch's interactions see each other's results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum UpdateMode {
+    /// Pair every cell at most once from the population as the epoch
+    /// began, execute all pairs in parallel, then write them back.
+    #[default]
+    #[value(name = "sync")]
+    Synchronous,
+    /// Run one interaction at a time between a random cell and a random
+    /// neighbor, writing it back before the next runs: a cell may interact
+    /// several times in an epoch, or not at all.
+    #[value(name = "async")]
+    Asynchronous,
+}
+
+/// How an executed tape's bytes land back on the programs that went in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WriteBackPolicy {
+    /// Overwrite each program with its half of the tape.
+    #[default]
+    Replace,
+    /// Take each byte's new value with this probability, else keep the old.
+    RandomReplace(f64),
+}
+
+impl WriteBackPolicy {
+    /// Parse `--writeback`: `replace` or `random:P`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "replace" => Ok(WriteBackPolicy::Replace),
+            _ => {
+                let p = s.strip_prefix("random:").ok_or_else(|| {
+                    format!("Invalid write-back policy '{s}': expected replace or random:P")
+                })?;
+                let p: f64 = p
+                    .parse()
+                    .map_err(|e| format!("Invalid write-back probability '{p}': {e}"))?;
+                if !(0.0..=1.0).contains(&p) {
+                    return Err(format!("Write-back probability {p} is outside [0, 1]"));
+                }
+                Ok(WriteBackPolicy::RandomReplace(p))
+            }
+        }
+    }
+
+    /// Apply the policy to both halves of the tape executed for `(first,
+    /// second)`; a self-paired tape's zero half is discarded anyway.
+    fn apply_pair(
+        self,
+        tape: &mut [u8],
+        programs: &[Vec<u8>],
+        (first, second): (usize, usize),
+        rng: &mut impl Rng,
+    ) {
+        if self == WriteBackPolicy::Replace {
+            return;
+        }
+        let (a, b) = tape.split_at_mut(programs[first].len());
+        self.apply(a, &programs[first], rng);
+        if second != NO_PARTNER {
+            self.apply(b, &programs[second], rng);
+        }
+    }
+
+    /// Rewrite `after`, one half of an executed tape, into what the policy
+    /// leaves of it over `before`, the program that went in.
+    fn apply(self, after: &mut [u8], before: &[u8], rng: &mut impl Rng) {
+        match self {
+            WriteBackPolicy::Replace => {}
+            WriteBackPolicy::RandomReplace(p) => {
+                for (a, &b) in after.iter_mut().zip(before) {
+                    if !rng.gen_bool(p) {
+                        *a = b;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How a tournament scores each half of an executed tape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Fitness {
+    /// Bytes left unchanged by execution (self-similarity before/after).
+    Persistence,
+    /// Bytes the substrate decodes as instructions.
+    InstructionCount,
+}
+
+impl Fitness {
+    /// Score one half: `after` is its post-execution content, `before` the
+    /// program that occupied it going in.
+    fn score<S: Substrate>(self, after: &[u8], before: &[u8]) -> usize {
+        match self {
+            Fitness::Persistence => after.iter().zip(before).filter(|(a, b)| a == b).count(),
+            Fitness::InstructionCount => after.iter().filter(|&&b| S::is_instruction(b)).count(),
+        }
+    }
+
+    /// Score both halves of an executed `tape`, whose programs going in were
+    /// `before_a` and `before_b`, and copy the winner over the loser.
+    fn select<S: Substrate>(self, tape: &mut [u8], ps: usize, before_a: &[u8], before_b: &[u8]) {
+        let (a, b) = tape.split_at_mut(ps);
+        let (score_a, score_b) = (self.score::<S>(a, before_a), self.score::<S>(b, before_b));
+        match score_a.cmp(&score_b) {
+            Ordering::Greater => b.copy_from_slice(a),
+            Ordering::Less => a.copy_from_slice(b),
+            Ordering::Equal => {}
+        }
+    }
+}
+
 // ─── Dijkstra helper ─────────────────────────────────────────────────────────
 
 #[derive(Clone, PartialEq)]
@@ -45,10 +508,14 @@ impl Eq for DijkNode {}
 
 impl Ord for DijkNode {
     fn cmp(&self, other: &Self) -> Ordering {
+        // Break distance ties by face index: adjacency lists come out of a
+        // HashMap in arbitrary order, and a `max_neighbors` cutoff must not
+        // depend on it.
         other
             .dist
             .partial_cmp(&self.dist)
             .unwrap_or(Ordering::Equal)
+            .then_with(|| other.face.cmp(&self.face))
     }
 }
 
@@ -60,12 +527,253 @@ impl PartialOrd for DijkNode {
 
 // ─── SurfaceMesh ─────────────────────────────────────────────────────────────
 
+/// Distribution of geodesic neighbor counts across faces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeighborStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: usize,
+    pub p95: usize,
+}
+
+impl std::fmt::Display for NeighborStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {}, max {}, mean {:.1}, median {}, p95 {}",
+            self.min, self.max, self.mean, self.median, self.p95
+        )
+    }
+}
+
+/// Structural checks on a mesh, from [`SurfaceMesh::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MeshReport {
+    pub faces: usize,
+    /// Faces with (near-)zero area, including ones repeating a vertex.
+    pub degenerate_faces: usize,
+    /// Faces using the same three vertices as an earlier face.
+    pub duplicate_faces: usize,
+    /// Edges used by only one face.
+    pub boundary_edges: usize,
+    /// Groups of faces connected through shared edges.
+    pub components: usize,
+    /// Faces that do not share an edge with exactly three other faces.
+    pub irregular_faces: usize,
+}
+
+impl MeshReport {
+    /// Whether every face shares an edge with exactly three others.
+    pub fn all_faces_have_three_neighbors(&self) -> bool {
+        self.irregular_faces == 0
+    }
+}
+
+impl std::fmt::Display for MeshReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "faces: {}", self.faces)?;
+        writeln!(f, "degenerate faces: {}", self.degenerate_faces)?;
+        writeln!(f, "duplicate faces: {}", self.duplicate_faces)?;
+        writeln!(f, "boundary edges: {}", self.boundary_edges)?;
+        writeln!(f, "connected components: {}", self.components)?;
+        write!(f, "faces without 3 neighbors: {}", self.irregular_faces)
+    }
+}
+
+/// Largest mesh, in faces, that `export_graphviz` will write.
+pub const MAX_GRAPHVIZ_CELLS: usize = 5000;
+
+/// Faces sampled, and bisection steps taken, when searching for a radius
+/// by mean neighbor count.
+const RADIUS_SEARCH_SAMPLES: usize = 256;
+const RADIUS_SEARCH_STEPS: usize = 32;
+
+/// How `compute_neighbors`' geodesic radius is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NeighborRadius {
+    /// A radius in mesh units.
+    Fixed(f32),
+    /// Whatever radius gives faces about this many neighbors on average.
+    MeanNeighbors { mean_neighbors: f64 },
+}
+
+impl NeighborRadius {
+    /// Parse `--neighbor-radius`: a radius such as `0.05`, or `auto:N` for
+    /// about N neighbors per face.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.strip_prefix("auto:") {
+            Some(n) => match n.parse::<f64>() {
+                Ok(mean_neighbors) if mean_neighbors > 0.0 => {
+                    Ok(NeighborRadius::MeanNeighbors { mean_neighbors })
+                }
+                _ => Err(format!(
+                    "Invalid neighbor target '{n}': expected a positive count"
+                )),
+            },
+            None => s
+                .parse::<f32>()
+                .map(NeighborRadius::Fixed)
+                .map_err(|e| format!("Invalid neighbor radius '{s}': {e}")),
+        }
+    }
+
+    /// The radius in mesh units for `mesh`.
+    pub fn resolve(self, mesh: &SurfaceMesh) -> f32 {
+        match self {
+            NeighborRadius::Fixed(radius) => radius,
+            NeighborRadius::MeanNeighbors { mean_neighbors } => {
+                let radius = mesh.radius_for_mean_neighbors(mean_neighbors);
+                log::status!(
+                    "Neighbor radius {radius:.4} targets {mean_neighbors} neighbors per face"
+                );
+                radius
+            }
+        }
+    }
+}
+
+/// How `compute_neighbors` measures the step between adjacent faces.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GeodesicMetric {
+    /// Straight-line distance between face centroids.
+    #[default]
+    Euclidean,
+    /// Distance in the generator's flattened (u, v) parameter domain,
+    /// scaled to mesh units. Only for surfaces that know their
+    /// parametrization (the torus).
+    Parametric,
+}
+
+impl GeodesicMetric {
+    /// Parse `--geodesic-metric`: `euclidean` or `parametric`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "euclidean" => Ok(GeodesicMetric::Euclidean),
+            "parametric" => Ok(GeodesicMetric::Parametric),
+            _ => Err(format!(
+                "Unknown geodesic metric '{s}': expected euclidean or parametric"
+            )),
+        }
+    }
+}
+
+/// Which mesh elements host the simulation's programs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellSite {
+    /// One program per triangular face; faces sharing an edge are adjacent.
+    #[default]
+    Face,
+    /// One program per vertex; vertices joined by an edge are adjacent
+    /// (see [`SurfaceMesh::dual_graph`]).
+    Vertex,
+}
+
+impl CellSite {
+    pub const ALL: [CellSite; 2] = [CellSite::Face, CellSite::Vertex];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CellSite::Face => "Faces",
+            CellSite::Vertex => "Vertices",
+        }
+    }
+
+    /// Parse `--cells`: `face` or `vertex`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "face" => Ok(CellSite::Face),
+            "vertex" => Ok(CellSite::Vertex),
+            _ => Err(format!("Unknown cell site '{s}': expected face or vertex")),
+        }
+    }
+}
+
+/// Each face centroid's coordinates in a surface's flattened parameter
+/// domain, in mesh units, and the period at which each axis wraps.
+#[derive(Clone)]
+struct Parametrization {
+    coords: Vec<[f32; 2]>,
+    periods: [Option<f32>; 2],
+}
+
+impl Parametrization {
+    fn distance(&self, a: usize, b: usize) -> f32 {
+        let mut sq = 0.0;
+        for axis in 0..2 {
+            let mut d = (self.coords[a][axis] - self.coords[b][axis]).abs();
+            if let Some(period) = self.periods[axis] {
+                d = d.min(period - d);
+            }
+            sq += d * d;
+        }
+        sq.sqrt()
+    }
+}
+
+/// Why a surface couldn't be built or loaded.
+#[derive(Debug)]
+pub enum SurfaceError {
+    /// A mesh or image file couldn't be read.
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// More than two faces share the edge between vertices `edge`.
+    NonManifold { edge: (usize, usize), count: usize },
+    /// Parameters or geometry that describe no valid surface.
+    InvalidParams(String),
+    /// Nothing left to host cells: no faces, or only degenerate ones.
+    EmptyMesh,
+    /// An OBJ file's `line` (1-based) couldn't be parsed.
+    ParseError { line: usize, msg: String },
+}
+
+impl std::fmt::Display for SurfaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SurfaceError::Io { path, source } => write!(f, "Failed to read '{path}': {source}"),
+            SurfaceError::NonManifold {
+                edge: (v0, v1),
+                count,
+            } => write!(f, "Non-manifold edge ({v0}, {v1}): shared by {count} faces"),
+            SurfaceError::InvalidParams(msg) => f.write_str(msg),
+            SurfaceError::EmptyMesh => f.write_str("Surface has no usable faces"),
+            SurfaceError::ParseError { line, msg } => write!(f, "Line {line}: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for SurfaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SurfaceError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Lets callers with `String` errors use `?` on surface results.
+impl From<SurfaceError> for String {
+    fn from(err: SurfaceError) -> Self {
+        err.to_string()
+    }
+}
+
 /// A triangle mesh representing a simulation surface.
 ///
-/// Each triangular face hosts one program. The mesh stores geometry for
-/// rendering and a pre-computed geodesic neighbor table for the simulation.
+/// Each triangular face hosts one program, or each vertex under
+/// [`CellSite::Vertex`]. The mesh stores geometry for rendering and a
+/// pre-computed geodesic neighbor table for the simulation.
+///
+/// Centroids and the neighbor table are derived from the vertices. Move
+/// vertices after construction through [`SurfaceMesh::vertices_mut`], which
+/// marks that derived data stale until `compute_neighbors` rebuilds it;
+/// running a soup on a stale mesh panics in debug builds.
+#[derive(Clone)]
 pub struct SurfaceMesh {
-    /// Vertex positions (for rendering).
+    /// Vertex positions (for rendering). Write through `vertices_mut`.
     pub vertices: Vec<[f32; 3]>,
     /// Triangular faces (indices into `vertices`).
     pub faces: Vec<[usize; 3]>,
@@ -75,126 +783,538 @@ pub struct SurfaceMesh {
     pub face_adjacency: Vec<Vec<usize>>,
     /// Flat buffer of geodesic neighbor indices.
     pub neighbor_indices: Vec<usize>,
-    /// Per-face (start, end) ranges into `neighbor_indices`.
+    /// Per-cell (start, end) ranges into `neighbor_indices`.
     pub neighbor_ranges: Vec<(usize, usize)>,
+    /// Distance to each neighbor in `neighbor_indices`, under the geodesic
+    /// metric the table was built with.
+    pub neighbor_distances: Vec<f32>,
+    /// Which elements are cells; see [`SurfaceMesh::set_cell_site`].
+    cell_site: CellSite,
+    /// The dual graph while cells are vertices, otherwise empty.
+    vertex_adjacency: Vec<Vec<usize>>,
+    /// Vertices moved since the centroids or neighbor table were derived.
+    geometry_dirty: bool,
+    /// Parameter-domain coordinates, for generators that know them.
+    parametrization: Option<Parametrization>,
+    geodesic_metric: GeodesicMetric,
 }
 
 impl SurfaceMesh {
-    /// Number of simulation cells (one per face).
+    /// Number of simulation cells (one per face, or per vertex).
     pub fn num_cells(&self) -> usize {
-        self.faces.len()
+        match self.cell_site {
+            CellSite::Face => self.faces.len(),
+            CellSite::Vertex => self.vertices.len(),
+        }
+    }
+
+    /// The geodesic neighbors `cell` interacts with. Empty until
+    /// `compute_neighbors` has run.
+    pub fn neighbors_of(&self, cell: usize) -> &[usize] {
+        let (start, end) = self.neighbor_ranges[cell];
+        &self.neighbor_indices[start..end]
+    }
+
+    /// Distances to `cell`'s neighbors, in the order of `neighbors_of`.
+    pub fn neighbor_distances_of(&self, cell: usize) -> &[f32] {
+        let (start, end) = self.neighbor_ranges[cell];
+        &self.neighbor_distances[start..end]
     }
 
     /// Construct from raw geometry. Builds adjacency and centroids.
     /// Does NOT compute geodesic neighbors — call `compute_neighbors` after.
-    fn from_geometry(vertices: Vec<[f32; 3]>, faces: Vec<[usize; 3]>) -> Result<Self, String> {
+    /// Faces of (near-)zero area are kept, with a warning.
+    fn from_geometry(
+        vertices: Vec<[f32; 3]>,
+        faces: Vec<[usize; 3]>,
+    ) -> Result<Self, SurfaceError> {
+        Self::from_geometry_checked(vertices, faces, false).map(|(mesh, _)| mesh)
+    }
+
+    /// Like `from_geometry`, but faces of (near-)zero area are left out so
+    /// they host no cell. Returns the mesh and the number of faces dropped.
+    /// Dropping happens before adjacency is built, so the remaining faces
+    /// are numbered consecutively and a sliver lying along another face's
+    /// edge can't make it non-manifold.
+    fn from_geometry_without_degenerate(
+        vertices: Vec<[f32; 3]>,
+        faces: Vec<[usize; 3]>,
+    ) -> Result<(Self, usize), SurfaceError> {
+        Self::from_geometry_checked(vertices, faces, true)
+    }
+
+    fn from_geometry_checked(
+        vertices: Vec<[f32; 3]>,
+        mut faces: Vec<[usize; 3]>,
+        drop_degenerate: bool,
+    ) -> Result<(Self, usize), SurfaceError> {
         // Validate vertex indices.
         for (fi, face) in faces.iter().enumerate() {
             for &vi in face {
                 if vi >= vertices.len() {
-                    return Err(format!(
+                    return Err(SurfaceError::InvalidParams(format!(
                         "Face {fi} references vertex {vi}, but only {} vertices exist",
                         vertices.len()
-                    ));
+                    )));
                 }
             }
         }
 
+        let before = faces.len();
+        if drop_degenerate {
+            faces.retain(|&face| !is_degenerate(&vertices, face));
+            if faces.is_empty() {
+                return Err(SurfaceError::EmptyMesh);
+            }
+        } else {
+            let degenerate = faces
+                .iter()
+                .filter(|&&face| is_degenerate(&vertices, face))
+                .count();
+            if degenerate > 0 {
+                log::status!(
+                    "warning: mesh has {degenerate} degenerate faces; \
+                     pass --drop-degenerate to leave them out"
+                );
+            }
+        }
+        let dropped = before - faces.len();
+
         let face_adjacency = build_face_adjacency(&faces)?;
         let face_centroids = compute_face_centroids(&vertices, &faces);
         let n = faces.len();
 
-        Ok(Self {
+        let mesh = Self {
             vertices,
             faces,
             face_centroids,
             face_adjacency,
             neighbor_indices: Vec::new(),
             neighbor_ranges: vec![(0, 0); n],
-        })
+            neighbor_distances: Vec::new(),
+            cell_site: CellSite::Face,
+            vertex_adjacency: Vec::new(),
+            geometry_dirty: false,
+            parametrization: None,
+            geodesic_metric: GeodesicMetric::Euclidean,
+        };
+        Ok((mesh, dropped))
     }
 
-    /// Compute geodesic neighbor table. `radius` of `None` uses 2x average
-    /// centroid-to-centroid distance across adjacent faces.
-    pub fn compute_neighbors(&mut self, radius: Option<f32>) {
-        let radius = radius.unwrap_or_else(|| 4.0 * self.avg_adjacent_centroid_distance());
-        let n = self.faces.len();
-        eprintln!("Computing geodesic neighbors for {n} faces (radius: {radius:.4})...");
-
-        // Borrow shared data immutably so rayon can access it in parallel.
-        let face_adjacency = &self.face_adjacency;
-        let face_centroids = &self.face_centroids;
+    /// Mutable access to the vertex positions. Marks the centroids and
+    /// neighbor table stale: call `recompute_centroids` (before any
+    /// neighbor table exists) or `compute_neighbors` afterwards.
+    pub fn vertices_mut(&mut self) -> &mut [[f32; 3]] {
+        self.geometry_dirty = true;
+        &mut self.vertices
+    }
 
-        // Run Dijkstra from each face in parallel.
-        let per_face_neighbors: Vec<Vec<usize>> = (0..n)
-            .into_par_iter()
-            .map(|source| {
-                let mut dist = vec![f32::INFINITY; n];
-                dist[source] = 0.0;
-                let mut heap = BinaryHeap::new();
-                heap.push(DijkNode {
-                    dist: 0.0,
-                    face: source,
-                });
+    /// Whether vertices moved since the derived data was last rebuilt.
+    pub fn is_geometry_dirty(&self) -> bool {
+        self.geometry_dirty
+    }
 
-                while let Some(node) = heap.pop() {
-                    if node.dist > dist[node.face] {
-                        continue;
-                    }
-                    for &adj in &face_adjacency[node.face] {
-                        let edge_dist =
-                            centroid_distance(&face_centroids[node.face], &face_centroids[adj]);
-                        let new_dist = node.dist + edge_dist;
-                        if new_dist <= radius && new_dist < dist[adj] {
-                            dist[adj] = new_dist;
-                            heap.push(DijkNode {
-                                dist: new_dist,
-                                face: adj,
-                            });
-                        }
-                    }
-                }
+    /// Recompute face centroids from the current vertices. This clears the
+    /// stale mark only if no neighbor table has been built yet; an existing
+    /// table still needs `compute_neighbors`.
+    pub fn recompute_centroids(&mut self) {
+        self.face_centroids = compute_face_centroids(&self.vertices, &self.faces);
+        if self.neighbor_indices.is_empty() {
+            self.geometry_dirty = false;
+        }
+    }
 
-                let mut neighbors = Vec::new();
-                for (i, &d) in dist.iter().enumerate() {
-                    if i != source && d <= radius {
-                        neighbors.push(i);
-                    }
-                }
-                neighbors
+    /// Measure adjacent-face steps with `metric` from the next
+    /// `compute_neighbors` on. Fails for `Parametric` on a surface without
+    /// a known parametrization.
+    pub fn set_geodesic_metric(&mut self, metric: GeodesicMetric) -> Result<(), SurfaceError> {
+        if metric == GeodesicMetric::Parametric && self.parametrization.is_none() {
+            return Err(SurfaceError::InvalidParams(
+                "The parametric geodesic metric needs a torus surface".into(),
+            ));
+        }
+        if metric == GeodesicMetric::Parametric && self.cell_site == CellSite::Vertex {
+            return Err(SurfaceError::InvalidParams(
+                "The parametric geodesic metric needs face cells".into(),
+            ));
+        }
+        self.geodesic_metric = metric;
+        Ok(())
+    }
+
+    /// Host programs on `site` from the next `compute_neighbors` on,
+    /// discarding any existing neighbor table. Fails for vertex cells under
+    /// the parametric metric, whose coordinates belong to faces.
+    pub fn set_cell_site(&mut self, site: CellSite) -> Result<(), SurfaceError> {
+        if site == CellSite::Vertex && self.geodesic_metric == GeodesicMetric::Parametric {
+            return Err(SurfaceError::InvalidParams(
+                "The parametric geodesic metric needs face cells".into(),
+            ));
+        }
+        self.vertex_adjacency = match site {
+            CellSite::Face => Vec::new(),
+            CellSite::Vertex => self.dual_graph(),
+        };
+        self.cell_site = site;
+        self.neighbor_indices = Vec::new();
+        self.neighbor_ranges = vec![(0, 0); self.num_cells()];
+        self.neighbor_distances = Vec::new();
+        Ok(())
+    }
+
+    /// Which elements host programs.
+    pub fn cell_site(&self) -> CellSite {
+        self.cell_site
+    }
+
+    /// For each vertex, the vertices it shares an edge with, in index
+    /// order: the graph vertex cells live on, as `face_adjacency` is for
+    /// face cells.
+    pub fn dual_graph(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); self.vertices.len()];
+        for face in &self.faces {
+            for e in 0..3 {
+                let (v0, v1) = (face[e], face[(e + 1) % 3]);
+                if v0 != v1 {
+                    adjacency[v0].push(v1);
+                    adjacency[v1].push(v0);
+                }
+            }
+        }
+        for list in &mut adjacency {
+            list.sort_unstable();
+            list.dedup();
+        }
+        adjacency
+    }
+
+    /// Which cells are adjacent: `face_adjacency`, or the dual graph under
+    /// vertex cells.
+    pub fn cell_adjacency(&self) -> &[Vec<usize>] {
+        match self.cell_site {
+            CellSite::Face => &self.face_adjacency,
+            CellSite::Vertex => &self.vertex_adjacency,
+        }
+    }
+
+    /// Where `cell` sits: its face centroid, or its vertex.
+    fn cell_position(&self, cell: usize) -> [f32; 3] {
+        match self.cell_site {
+            CellSite::Face => self.face_centroids[cell],
+            CellSite::Vertex => self.vertices[cell],
+        }
+    }
+
+    /// The metric `compute_neighbors` measures with.
+    pub fn geodesic_metric(&self) -> GeodesicMetric {
+        self.geodesic_metric
+    }
+
+    /// Length of the step between adjacent cells `a` and `b` under the
+    /// mesh's geodesic metric.
+    fn edge_length(&self, a: usize, b: usize) -> f32 {
+        match (&self.parametrization, self.geodesic_metric) {
+            (Some(param), GeodesicMetric::Parametric) => param.distance(a, b),
+            _ => centroid_distance(&self.cell_position(a), &self.cell_position(b)),
+        }
+    }
+
+    /// Bring centroids up to date ahead of rebuilding the neighbor table.
+    fn refresh_geometry(&mut self) {
+        if self.geometry_dirty {
+            self.face_centroids = compute_face_centroids(&self.vertices, &self.faces);
+            self.geometry_dirty = false;
+        }
+    }
+
+    /// Compute geodesic neighbor table. `radius` of `None` uses 4x average
+    /// centroid-to-centroid distance across adjacent faces.
+    ///
+    /// `max_neighbors` caps each face's table at its K geodesically-nearest
+    /// faces within the radius, bounding memory on dense meshes.
+    pub fn compute_neighbors(&mut self, radius: Option<f32>, max_neighbors: Option<usize>) {
+        self.refresh_geometry();
+        let radius = radius.unwrap_or_else(|| 4.0 * self.avg_adjacent_centroid_distance());
+        let n = self.num_cells();
+        let cap = max_neighbors.map_or(String::new(), |k| format!(", max: {k}"));
+        log::status!("Computing geodesic neighbors for {n} cells (radius: {radius:.4}{cap})...");
+
+        // Run Dijkstra from each face in parallel.
+        let per_face_neighbors: Vec<Vec<(usize, f32)>> = (0..n)
+            .into_par_iter()
+            .map(|source| {
+                let mut neighbors = self.geodesic_nearest(source, radius, max_neighbors);
+                neighbors.sort_unstable_by_key(|&(face, _)| face);
+                neighbors
             })
             .collect();
 
         // Flatten into the compact buffer format.
         let mut neighbor_indices = Vec::new();
+        let mut neighbor_distances = Vec::new();
         let mut neighbor_ranges = Vec::with_capacity(n);
         for neighbors in &per_face_neighbors {
             let start = neighbor_indices.len();
-            neighbor_indices.extend_from_slice(neighbors);
+            neighbor_indices.extend(neighbors.iter().map(|&(face, _)| face));
+            neighbor_distances.extend(neighbors.iter().map(|&(_, distance)| distance));
             neighbor_ranges.push((start, neighbor_indices.len()));
         }
 
-        let total_neighbors: usize = neighbor_ranges.iter().map(|(s, e)| e - s).sum();
-        let avg = if n > 0 {
-            total_neighbors as f64 / n as f64
-        } else {
-            0.0
-        };
-        eprintln!("  Average neighbors per face: {avg:.1}");
-        eprintln!("  done.");
-
         self.neighbor_indices = neighbor_indices;
         self.neighbor_ranges = neighbor_ranges;
+        self.neighbor_distances = neighbor_distances;
+
+        log::status!("  Neighbors per face: {}", self.neighbor_stats());
+        log::status!("  done.");
+    }
+
+    /// Replace the neighbor table with explicit per-face lists, e.g. from an
+    /// [`InteractionGraph`]. Each neighbor's distance is the straight line
+    /// between the two cells.
+    pub fn set_neighbors(&mut self, neighbors: &[Vec<usize>]) {
+        assert_eq!(neighbors.len(), self.num_cells(), "one list per face");
+        self.refresh_geometry();
+        self.neighbor_indices = neighbors.concat();
+        let mesh = &*self;
+        let distances = neighbors
+            .iter()
+            .enumerate()
+            .flat_map(|(cell, list)| {
+                let from = mesh.cell_position(cell);
+                list.iter()
+                    .map(move |&other| centroid_distance(&from, &mesh.cell_position(other)))
+            })
+            .collect();
+        self.neighbor_distances = distances;
+        let mut start = 0;
+        self.neighbor_ranges = neighbors
+            .iter()
+            .map(|list| {
+                start += list.len();
+                (start - list.len(), start)
+            })
+            .collect();
+    }
+
+    /// Summary of the per-face neighbor counts in the geodesic table.
+    pub fn neighbor_stats(&self) -> NeighborStats {
+        let mut counts: Vec<usize> = self.neighbor_ranges.iter().map(|(s, e)| e - s).collect();
+        if counts.is_empty() {
+            return NeighborStats {
+                min: 0,
+                max: 0,
+                mean: 0.0,
+                median: 0,
+                p95: 0,
+            };
+        }
+        counts.sort_unstable();
+        let n = counts.len();
+        // Nearest-rank percentile.
+        let percentile = |p: usize| counts[(p * n).div_ceil(100).max(1) - 1];
+        NeighborStats {
+            min: counts[0],
+            max: counts[n - 1],
+            mean: counts.iter().sum::<usize>() as f64 / n as f64,
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+
+    /// Write the geodesic neighbor graph as a Graphviz DOT file: one node
+    /// per face and one undirected edge per neighbor pair. Refuses meshes
+    /// over [`MAX_GRAPHVIZ_CELLS`] faces, which `dot` can't lay out usefully.
+    pub fn export_graphviz(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let n = self.num_cells();
+        if n > MAX_GRAPHVIZ_CELLS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{n} faces is too many to export (limit {MAX_GRAPHVIZ_CELLS})"),
+            ));
+        }
+        let mut edges = std::collections::BTreeSet::new();
+        for (face, &(start, end)) in self.neighbor_ranges.iter().enumerate() {
+            for &other in &self.neighbor_indices[start..end] {
+                edges.insert((face.min(other), face.max(other)));
+            }
+        }
+
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(out, "graph neighbors {{")?;
+        for face in 0..n {
+            writeln!(out, "  {face};")?;
+        }
+        for (a, b) in edges {
+            writeln!(out, "  {a} -- {b};")?;
+        }
+        writeln!(out, "}}")?;
+        out.flush()
+    }
+
+    /// Check the mesh for problems that make custom surfaces misbehave:
+    /// degenerate or duplicate faces, open boundaries, disconnected pieces,
+    /// and faces without the three edge-neighbors of a closed triangulation.
+    pub fn validate(&self) -> MeshReport {
+        let degenerate_faces = self.degenerate_face_count();
+
+        let mut seen = std::collections::HashSet::new();
+        let duplicate_faces = self
+            .faces
+            .iter()
+            .filter(|f| {
+                let mut key = **f;
+                key.sort_unstable();
+                !seen.insert(key)
+            })
+            .count();
+
+        let mut edge_uses: HashMap<(usize, usize), usize> = HashMap::new();
+        for face in &self.faces {
+            for e in 0..3 {
+                let (v0, v1) = (face[e], face[(e + 1) % 3]);
+                *edge_uses.entry((v0.min(v1), v0.max(v1))).or_default() += 1;
+            }
+        }
+        let boundary_edges = edge_uses.values().filter(|&&uses| uses == 1).count();
+
+        let mut component = vec![usize::MAX; self.faces.len()];
+        let mut components = 0;
+        for start in 0..self.faces.len() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            component[start] = components;
+            let mut stack = vec![start];
+            while let Some(face) = stack.pop() {
+                for &adj in &self.face_adjacency[face] {
+                    if component[adj] == usize::MAX {
+                        component[adj] = components;
+                        stack.push(adj);
+                    }
+                }
+            }
+            components += 1;
+        }
+
+        MeshReport {
+            faces: self.faces.len(),
+            degenerate_faces,
+            duplicate_faces,
+            boundary_edges,
+            components,
+            irregular_faces: self.face_adjacency.iter().filter(|a| a.len() != 3).count(),
+        }
+    }
+
+    /// Faces within geodesic `radius` of `source` (excluding `source`) with
+    /// their distances, nearest first. Dijkstra settles faces in distance
+    /// order, so stopping after `limit` settled faces yields exactly the
+    /// `limit` nearest.
+    fn geodesic_nearest(
+        &self,
+        source: usize,
+        radius: f32,
+        limit: Option<usize>,
+    ) -> Vec<(usize, f32)> {
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut dist = vec![f32::INFINITY; self.num_cells()];
+        dist[source] = 0.0;
+        let mut heap = BinaryHeap::new();
+        heap.push(DijkNode {
+            dist: 0.0,
+            face: source,
+        });
+
+        let mut settled = Vec::new();
+        while let Some(node) = heap.pop() {
+            if node.dist > dist[node.face] {
+                continue;
+            }
+            if node.face != source {
+                if settled.len() == limit {
+                    break;
+                }
+                settled.push((node.face, node.dist));
+            }
+            for &adj in &self.cell_adjacency()[node.face] {
+                let edge_dist = self.edge_length(node.face, adj);
+                let new_dist = node.dist + edge_dist;
+                if new_dist <= radius && new_dist < dist[adj] {
+                    dist[adj] = new_dist;
+                    heap.push(DijkNode {
+                        dist: new_dist,
+                        face: adj,
+                    });
+                }
+            }
+        }
+        settled
+    }
+
+    /// `center` and every face within geodesic `radius` of it, in index
+    /// order.
+    pub fn geodesic_patch(&self, center: usize, radius: f32) -> Vec<usize> {
+        let nearby = self.geodesic_nearest(center, radius, None);
+        let mut patch: Vec<usize> = std::iter::once(center)
+            .chain(nearby.into_iter().map(|(face, _)| face))
+            .collect();
+        patch.sort_unstable();
+        patch
+    }
+
+    /// Smallest geodesic radius at which faces have, on average, at least
+    /// `target` neighbors, estimated on an evenly spaced sample of faces.
+    ///
+    /// Doubles the radius from the typical adjacent-face spacing until the
+    /// target is bracketed, then bisects. A target beyond what the mesh can
+    /// reach yields the radius at which the sampled counts stop growing.
+    pub fn radius_for_mean_neighbors(&self, target: f64) -> f32 {
+        let n = self.num_cells();
+        let step = self.avg_adjacent_centroid_distance();
+        if n == 0 {
+            return step;
+        }
+        let samples: Vec<usize> = (0..RADIUS_SEARCH_SAMPLES.min(n))
+            .map(|i| i * n / RADIUS_SEARCH_SAMPLES.min(n))
+            .collect();
+        let mean_at = |radius: f32| {
+            let total: usize = samples
+                .par_iter()
+                .map(|&face| self.geodesic_nearest(face, radius, None).len())
+                .sum();
+            total as f64 / samples.len() as f64
+        };
+
+        let (mut lo, mut hi) = (0.0, step);
+        let mut mean = mean_at(hi);
+        while mean < target {
+            let grown = mean_at(hi * 2.0);
+            if grown <= mean {
+                return hi;
+            }
+            (lo, hi, mean) = (hi, hi * 2.0, grown);
+        }
+        for _ in 0..RADIUS_SEARCH_STEPS {
+            let mid = (lo + hi) / 2.0;
+            if mean_at(mid) < target {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        hi
     }
 
-    /// Average centroid-to-centroid distance between adjacent faces.
+    /// Average centroid-to-centroid distance between adjacent cells.
     fn avg_adjacent_centroid_distance(&self) -> f32 {
         let mut total = 0.0f32;
         let mut count = 0usize;
-        for (i, adj_list) in self.face_adjacency.iter().enumerate() {
+        for (i, adj_list) in self.cell_adjacency().iter().enumerate() {
             for &j in adj_list {
                 if j > i {
-                    total += centroid_distance(&self.face_centroids[i], &self.face_centroids[j]);
+                    total += self.edge_length(i, j);
                     count += 1;
                 }
             }
@@ -234,11 +1354,40 @@ impl SurfaceMesh {
         (center, max_dist_sq.sqrt())
     }
 
+    /// Center the bounding box on the origin and scale uniformly so the
+    /// longest side spans [-1, 1], like the procedural shapes. Call before
+    /// `compute_neighbors`.
+    pub fn normalize(&mut self) {
+        if self.vertices.is_empty() {
+            return;
+        }
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for v in &self.vertices {
+            for i in 0..3 {
+                min[i] = min[i].min(v[i]);
+                max[i] = max[i].max(v[i]);
+            }
+        }
+        let longest = (0..3).map(|i| max[i] - min[i]).fold(0.0, f32::max);
+        let scale = if longest > 0.0 { 2.0 / longest } else { 1.0 };
+        for v in self.vertices_mut() {
+            for i in 0..3 {
+                v[i] = (v[i] - (min[i] + max[i]) / 2.0) * scale;
+            }
+        }
+        if let Some(param) = &mut self.parametrization {
+            param.coords.iter_mut().flatten().for_each(|c| *c *= scale);
+            param.periods.iter_mut().flatten().for_each(|p| *p *= scale);
+        }
+        self.recompute_centroids();
+    }
+
     // ─── Procedural generators ───────────────────────────────────────────────
 
     /// Generate an icosphere with the given number of subdivision levels.
     /// Subdivision 0 = icosahedron (20 faces). Each level quadruples face count.
-    pub fn icosphere(subdivisions: usize) -> Result<Self, String> {
+    pub fn icosphere(subdivisions: usize) -> Result<Self, SurfaceError> {
         let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
 
         let mut vertices: Vec<[f32; 3]> = vec![
@@ -308,11 +1457,70 @@ impl SurfaceMesh {
         Self::from_geometry(vertices, faces)
     }
 
+    /// Generate a cube sphere: each face of a cube split into a
+    /// `subdivisions` × `subdivisions` grid of quads, two triangles each,
+    /// and projected onto the unit sphere. Grid lines are spaced evenly in
+    /// angle, so cells vary less in size than on the icosphere.
+    pub fn cube_sphere(subdivisions: usize) -> Result<Self, SurfaceError> {
+        if subdivisions == 0 {
+            return Err(SurfaceError::InvalidParams(
+                "Cube sphere requires at least 1 subdivision".into(),
+            ));
+        }
+        let n = subdivisions;
+
+        // Lattice points on the cube's surface, keyed by integer coordinates
+        // in 0..=n, so the cube faces meeting at an edge share its vertices
+        // and the mesh stays closed across the seams.
+        let mut vertices = Vec::with_capacity(6 * n * n + 2);
+        let mut index: HashMap<[usize; 3], usize> = HashMap::new();
+        let angle = |c: usize| std::f32::consts::FRAC_PI_4 * (2.0 * c as f32 / n as f32 - 1.0);
+        let mut vertex = |p: [usize; 3]| {
+            *index.entry(p).or_insert_with(|| {
+                vertices.push(normalize3(p.map(|c| angle(c).tan())));
+                vertices.len() - 1
+            })
+        };
+
+        let mut faces = Vec::with_capacity(12 * n * n);
+        for axis in 0..3 {
+            // `u` × `v` points along +`axis`, so corners taken in (u, v)
+            // order wind counter-clockwise seen from outside the +`axis`
+            // face and clockwise from outside the opposite one.
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            for side in [0, n] {
+                for i in 0..n {
+                    for j in 0..n {
+                        let mut corner = |di: usize, dj: usize| {
+                            let mut p = [0; 3];
+                            p[axis] = side;
+                            p[u] = i + di;
+                            p[v] = j + dj;
+                            vertex(p)
+                        };
+                        let (a, b, c, d) = (corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1));
+                        if side == n {
+                            faces.push([a, b, c]);
+                            faces.push([a, c, d]);
+                        } else {
+                            faces.push([a, c, b]);
+                            faces.push([a, d, c]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self::from_geometry(vertices, faces)
+    }
+
     /// Generate a torus with `major` segments around the ring and `minor`
     /// segments around the tube cross-section. Major radius = 1.0, minor = 0.4.
-    pub fn torus(major: usize, minor: usize) -> Result<Self, String> {
+    pub fn torus(major: usize, minor: usize) -> Result<Self, SurfaceError> {
         if major < 3 || minor < 3 {
-            return Err("Torus requires at least 3 segments in each dimension".into());
+            return Err(SurfaceError::InvalidParams(
+                "Torus requires at least 3 segments in each dimension".into(),
+            ));
         }
 
         let r_major = 1.0_f32;
@@ -330,7 +1538,12 @@ impl SurfaceMesh {
             }
         }
 
+        // Centroids in the flat (u, v) domain: u runs around the ring's
+        // centerline, v around the tube.
+        let du = 2.0 * std::f32::consts::PI * r_major / major as f32;
+        let dv = 2.0 * std::f32::consts::PI * r_minor / minor as f32;
         let mut faces = Vec::with_capacity(2 * major * minor);
+        let mut coords = Vec::with_capacity(2 * major * minor);
         for i in 0..major {
             let i_next = (i + 1) % major;
             for j in 0..minor {
@@ -341,18 +1554,28 @@ impl SurfaceMesh {
                 let v01 = i * minor + j_next;
                 faces.push([v00, v11, v10]);
                 faces.push([v00, v01, v11]);
+                let (i, j) = (i as f32, j as f32);
+                coords.push([(i + 2.0 / 3.0) * du, (j + 1.0 / 3.0) * dv]);
+                coords.push([(i + 1.0 / 3.0) * du, (j + 2.0 / 3.0) * dv]);
             }
         }
 
-        Self::from_geometry(vertices, faces)
+        let mut mesh = Self::from_geometry(vertices, faces)?;
+        mesh.parametrization = Some(Parametrization {
+            coords,
+            periods: [Some(major as f32 * du), Some(minor as f32 * dv)],
+        });
+        Ok(mesh)
     }
 
     /// Generate a flat grid in the XY plane. `width` x `height` quads,
     /// each split into 2 triangles = 2*width*height faces (programs).
     /// Centered at origin, scaled so longest dimension spans [-1, 1].
-    pub fn flat_grid(width: usize, height: usize) -> Result<Self, String> {
+    pub fn flat_grid(width: usize, height: usize) -> Result<Self, SurfaceError> {
         if width == 0 || height == 0 {
-            return Err("Grid dimensions must be positive".into());
+            return Err(SurfaceError::InvalidParams(
+                "Grid dimensions must be positive".into(),
+            ));
         }
 
         let scale = 2.0 / (width.max(height) as f32);
@@ -384,72 +1607,94 @@ impl SurfaceMesh {
         Self::from_geometry(vertices, faces)
     }
 
-    /// Load a mesh from a Wavefront OBJ file.
-    pub fn from_obj(path: &str) -> Result<Self, String> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read OBJ file '{path}': {e}"))?;
-
-        let mut vertices = Vec::new();
-        let mut faces = Vec::new();
-
-        for (line_num, line) in content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
+    /// A `width` x `height` x `depth` lattice of cells, each interacting
+    /// with the up to 6 cells one step away along an axis (wrapping around
+    /// every axis when `periodic`). The neighbor table is installed
+    /// directly; `compute_neighbors` would replace it. Each cell is drawn
+    /// as a small triangle facing +Z at its lattice point, scaled so the
+    /// longest dimension spans [-1, 1].
+    pub fn lattice_3d(
+        width: usize,
+        height: usize,
+        depth: usize,
+        periodic: bool,
+    ) -> Result<Self, SurfaceError> {
+        if width == 0 || height == 0 || depth == 0 {
+            return Err(SurfaceError::InvalidParams(
+                "Lattice dimensions must be positive".into(),
+            ));
+        }
 
-            let mut parts = line.split_whitespace();
-            match parts.next() {
-                Some("v") => {
-                    let coords: Vec<f32> = parts
-                        .take(3)
-                        .map(|s| s.parse::<f32>())
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|e| {
-                            format!("Line {}: invalid vertex coordinate: {e}", line_num + 1)
-                        })?;
-                    if coords.len() < 3 {
-                        return Err(format!("Line {}: vertex needs 3 coordinates", line_num + 1));
-                    }
-                    vertices.push([coords[0], coords[1], coords[2]]);
-                }
-                Some("f") => {
-                    let indices: Vec<usize> = parts
-                        .map(|s| {
-                            // Handle v, v/vt, v/vt/vn, v//vn formats.
-                            let idx_str = s.split('/').next().unwrap();
-                            idx_str.parse::<usize>().map(|i| i - 1) // OBJ is 1-indexed
-                        })
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|e| format!("Line {}: invalid face index: {e}", line_num + 1))?;
-
-                    if indices.len() < 3 {
-                        return Err(format!(
-                            "Line {}: face needs at least 3 vertices",
-                            line_num + 1
-                        ));
-                    }
-                    // Fan triangulation for quads and n-gons.
-                    for i in 1..indices.len() - 1 {
-                        faces.push([indices[0], indices[i], indices[i + 1]]);
+        let dims = [width, height, depth];
+        let spacing = 2.0 / (width.max(height).max(depth) as f32);
+        let size = 0.3 * spacing;
+        let index = |[x, y, z]: [usize; 3]| x + width * (y + height * z);
+        let mut vertices = Vec::with_capacity(3 * width * height * depth);
+        let mut faces = Vec::with_capacity(width * height * depth);
+        let mut neighbors = Vec::with_capacity(width * height * depth);
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    let cell = [x, y, z];
+                    let center = |a: usize| (cell[a] as f32 + 0.5 - dims[a] as f32 / 2.0) * spacing;
+                    let [cx, cy, cz] = [0, 1, 2].map(center);
+                    let v = vertices.len();
+                    vertices.push([cx - size, cy - size, cz]);
+                    vertices.push([cx + size, cy - size, cz]);
+                    vertices.push([cx, cy + size, cz]);
+                    faces.push([v, v + 1, v + 2]);
+
+                    let mut adjacent = Vec::with_capacity(6);
+                    for axis in 0..3 {
+                        let (i, n) = (cell[axis], dims[axis]);
+                        let steps = match (periodic, i > 0, i + 1 < n) {
+                            (true, _, _) => [Some((i + n - 1) % n), Some((i + 1) % n)],
+                            (false, below, above) => [below.then(|| i - 1), above.then_some(i + 1)],
+                        };
+                        for j in steps.into_iter().flatten() {
+                            let mut other = cell;
+                            other[axis] = j;
+                            adjacent.push(index(other));
+                        }
                     }
+                    // Short periodic axes reach the same cell both ways,
+                    // or the cell itself.
+                    adjacent.sort_unstable();
+                    adjacent.dedup();
+                    adjacent.retain(|&other| other != index(cell));
+                    neighbors.push(adjacent);
                 }
-                _ => {} // Ignore vn, vt, mtllib, usemtl, etc.
             }
         }
 
-        if faces.is_empty() {
-            return Err(format!("OBJ file '{path}' contains no faces"));
-        }
+        let mut mesh = Self::from_geometry(vertices, faces)?;
+        mesh.face_adjacency = neighbors.clone();
+        mesh.set_neighbors(&neighbors);
+        Ok(mesh)
+    }
 
-        eprintln!(
-            "Loaded OBJ: {} vertices, {} faces",
-            vertices.len(),
-            faces.len()
-        );
+    /// Load a mesh from a Wavefront OBJ file.
+    pub fn from_obj(path: &str) -> Result<Self, SurfaceError> {
+        let (vertices, faces) = read_obj(path)?;
         Self::from_geometry(vertices, faces)
     }
 
+    /// Load a mesh from a Wavefront OBJ file, leaving out faces of
+    /// (near-)zero area so they host no cell. Returns the mesh and the
+    /// number of faces dropped.
+    pub fn from_obj_without_degenerate(path: &str) -> Result<(Self, usize), SurfaceError> {
+        let (vertices, faces) = read_obj(path)?;
+        Self::from_geometry_without_degenerate(vertices, faces)
+    }
+
+    /// Faces of (near-)zero area, including ones repeating a vertex.
+    pub fn degenerate_face_count(&self) -> usize {
+        self.faces
+            .iter()
+            .filter(|&&face| is_degenerate(&self.vertices, face))
+            .count()
+    }
+
     /// Generate a "hamster tunnel": a loop of spheres connected by tubes.
     ///
     /// Sphere positions are scattered randomly in 3D, sorted into a short
@@ -459,12 +1704,20 @@ impl SurfaceMesh {
     /// - `num_spheres`: number of sphere nodes (>= 3 for a loop).
     /// - `segments`: vertices per ring cross-section (>= 3).
     /// - `seed`: RNG seed for sphere placement.
-    pub fn hamster_tunnel(num_spheres: usize, segments: usize, seed: u64) -> Result<Self, String> {
+    pub fn hamster_tunnel(
+        num_spheres: usize,
+        segments: usize,
+        seed: u64,
+    ) -> Result<Self, SurfaceError> {
         if num_spheres < 3 {
-            return Err("Hamster tunnel requires at least 3 spheres".into());
+            return Err(SurfaceError::InvalidParams(
+                "Hamster tunnel requires at least 3 spheres".into(),
+            ));
         }
         if segments < 3 {
-            return Err("Hamster tunnel requires at least 3 circumferential segments".into());
+            return Err(SurfaceError::InvalidParams(
+                "Hamster tunnel requires at least 3 circumferential segments".into(),
+            ));
         }
 
         const SPHERE_RADIUS: f32 = 0.4;
@@ -633,7 +1886,7 @@ impl SurfaceMesh {
         // No caps needed — the loop closes on itself.
 
         let face_count = faces.len();
-        eprintln!(
+        log::status!(
             "Surface: hamster tunnel ({num_spheres} spheres, {segments} segments, {face_count} faces)"
         );
         Self::from_geometry(vertices, faces)
@@ -643,12 +1896,16 @@ impl SurfaceMesh {
     ///
     /// - `segments`: vertices per ring cross-section (>= 3).
     /// - `rings`: number of height divisions (>= 1).
-    pub fn cylinder(segments: usize, rings: usize) -> Result<Self, String> {
+    pub fn cylinder(segments: usize, rings: usize) -> Result<Self, SurfaceError> {
         if segments < 3 {
-            return Err("Cylinder requires at least 3 circumferential segments".into());
+            return Err(SurfaceError::InvalidParams(
+                "Cylinder requires at least 3 circumferential segments".into(),
+            ));
         }
         if rings < 1 {
-            return Err("Cylinder requires at least 1 ring division".into());
+            return Err(SurfaceError::InvalidParams(
+                "Cylinder requires at least 1 ring division".into(),
+            ));
         }
 
         let radius = 0.5_f32;
@@ -699,7 +1956,7 @@ impl SurfaceMesh {
         }
 
         let face_count = faces.len();
-        eprintln!("Surface: cylinder ({segments} segments, {rings} rings, {face_count} faces)");
+        log::status!("Surface: cylinder ({segments} segments, {rings} rings, {face_count} faces)");
         Self::from_geometry(vertices, faces)
     }
 
@@ -710,9 +1967,11 @@ impl SurfaceMesh {
     ///
     /// - `u_segments`: segments along the main loop (>= 3).
     /// - `v_segments`: segments around the cross-section (>= 3).
-    pub fn klein_bottle(u_segments: usize, v_segments: usize) -> Result<Self, String> {
+    pub fn klein_bottle(u_segments: usize, v_segments: usize) -> Result<Self, SurfaceError> {
         if u_segments < 3 || v_segments < 3 {
-            return Err("Klein bottle requires at least 3 segments in each dimension".into());
+            return Err(SurfaceError::InvalidParams(
+                "Klein bottle requires at least 3 segments in each dimension".into(),
+            ));
         }
 
         let r = 2.0_f32;
@@ -765,7 +2024,7 @@ impl SurfaceMesh {
         }
 
         let face_count = faces.len();
-        eprintln!(
+        log::status!(
             "Surface: Klein bottle ({u_segments}×{v_segments}, {face_count} faces)"
         );
         Self::from_geometry(vertices, faces)
@@ -778,9 +2037,11 @@ impl SurfaceMesh {
     ///
     /// - `width`, `height`: grid dimensions (>= 1).
     /// - `seed`: RNG seed for the noise function.
-    pub fn heightmap(width: usize, height: usize, seed: u64) -> Result<Self, String> {
+    pub fn heightmap(width: usize, height: usize, seed: u64) -> Result<Self, SurfaceError> {
         if width == 0 || height == 0 {
-            return Err("Heightmap dimensions must be positive".into());
+            return Err(SurfaceError::InvalidParams(
+                "Heightmap dimensions must be positive".into(),
+            ));
         }
 
         let scale = 2.0 / (width.max(height) as f32);
@@ -818,10 +2079,46 @@ impl SurfaceMesh {
         }
 
         let face_count = faces.len();
-        eprintln!("Surface: heightmap ({width}×{height}, {face_count} faces)");
+        log::status!("Surface: heightmap ({width}×{height}, {face_count} faces)");
         Self::from_geometry(vertices, faces)
     }
 
+    /// Build a displaced flat grid from a grayscale image.
+    ///
+    /// Each pixel becomes one grid vertex, so a `W×H` image yields the same
+    /// topology as `flat_grid(W - 1, H - 1)`. Every vertex is lifted along Z
+    /// by its pixel luminance (0–1) times `scale`; image row 0 is the top
+    /// edge of the grid.
+    pub fn from_heightmap(path: &str, scale: f32) -> Result<Self, SurfaceError> {
+        let img = image::open(path)
+            .map_err(|e| SurfaceError::Io {
+                path: path.into(),
+                source: std::io::Error::other(e),
+            })?
+            .to_luma8();
+        let (img_w, img_h) = (img.width() as usize, img.height() as usize);
+        if img_w < 2 || img_h < 2 {
+            return Err(SurfaceError::InvalidParams(format!(
+                "Heightmap image '{path}' is {img_w}×{img_h}; need at least 2×2 pixels"
+            )));
+        }
+
+        let mut mesh = Self::flat_grid(img_w - 1, img_h - 1)?;
+        for (vi, v) in mesh.vertices_mut().iter_mut().enumerate() {
+            let i = vi % img_w;
+            let j = vi / img_w;
+            let luminance = img.get_pixel(i as u32, (img_h - 1 - j) as u32)[0];
+            v[2] = luminance as f32 / 255.0 * scale;
+        }
+        mesh.recompute_centroids();
+
+        log::status!(
+            "Surface: heightmap image ({img_w}×{img_h} px, {} faces)",
+            mesh.faces.len()
+        );
+        Ok(mesh)
+    }
+
     /// Generate a tube extruded along a trefoil knot curve.
     ///
     /// The trefoil knot is the simplest non-trivial knot. The tube follows the
@@ -830,12 +2127,16 @@ impl SurfaceMesh {
     ///
     /// - `rings`: sample points along the knot curve (>= 3).
     /// - `segments`: vertices per ring cross-section (>= 3).
-    pub fn trefoil_knot(rings: usize, segments: usize) -> Result<Self, String> {
+    pub fn trefoil_knot(rings: usize, segments: usize) -> Result<Self, SurfaceError> {
         if rings < 3 {
-            return Err("Trefoil knot requires at least 3 rings".into());
+            return Err(SurfaceError::InvalidParams(
+                "Trefoil knot requires at least 3 rings".into(),
+            ));
         }
         if segments < 3 {
-            return Err("Trefoil knot requires at least 3 circumferential segments".into());
+            return Err(SurfaceError::InvalidParams(
+                "Trefoil knot requires at least 3 circumferential segments".into(),
+            ));
         }
 
         const TUBE_RADIUS: f32 = 0.3;
@@ -944,7 +2245,7 @@ impl SurfaceMesh {
         }
 
         let face_count = faces.len();
-        eprintln!(
+        log::status!(
             "Surface: trefoil knot ({rings} rings, {segments} segments, {face_count} faces)"
         );
         Self::from_geometry(vertices, faces)
@@ -954,11 +2255,15 @@ impl SurfaceMesh {
 // ─── Surface spec ───────────────────────────────────────────────────────────
 
 /// Specification for generating a surface mesh.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SurfaceSpec {
     Sphere {
         subdivisions: usize,
     },
+    /// A projected, subdivided cube; see [`SurfaceMesh::cube_sphere`].
+    CubeSphere {
+        subdivisions: usize,
+    },
     Torus {
         major: usize,
         minor: usize,
@@ -989,16 +2294,134 @@ pub enum SurfaceSpec {
         rings: usize,
         segments: usize,
     },
+    /// A 3D lattice of cells with axis-aligned neighbors; see
+    /// [`SurfaceMesh::lattice_3d`].
+    Grid3d {
+        width: usize,
+        height: usize,
+        depth: usize,
+        /// Wrap around every axis.
+        #[serde(default)]
+        periodic: bool,
+    },
+    HeightmapImage {
+        path: String,
+        scale: f32,
+    },
     ObjFile {
         path: String,
+        /// Recenter and rescale to the procedural shapes' [-1, 1] extent.
+        #[serde(default)]
+        normalize: bool,
+        /// Leave out (near-)zero-area faces instead of giving them cells.
+        #[serde(default)]
+        drop_degenerate: bool,
     },
 }
 
+/// Default Z displacement for a full-white pixel in `heightmap:PATH` specs.
+pub const DEFAULT_HEIGHTMAP_IMAGE_SCALE: f32 = 0.4;
+
+/// Estimated peak memory, in bytes, above which the command line won't
+/// build a surface without `--force`.
+pub const BUILD_MEMORY_LIMIT: u64 = 1 << 30;
+
+/// Neighbors per face that [`estimate_build_bytes`] callers should assume
+/// under the default radius of four face spacings. Regular meshes average
+/// about 25; the slack covers irregular meshes and the spare capacity of
+/// lists grown one neighbor at a time.
+pub const DEFAULT_NEIGHBORS_ESTIMATE: usize = 64;
+
+/// Rough bytes per face of the mesh itself: vertices, faces, centroids,
+/// and edge adjacency.
+const MESH_BYTES_PER_FACE: u64 = 160;
+
+/// Rough peak memory, in bytes, of building a `faces`-face mesh and its
+/// geodesic neighbor table with about `neighbors` neighbors per face on
+/// `threads` threads. Counts the mesh, the per-face neighbor lists and the
+/// flattened table (both alive while [`SurfaceMesh::compute_neighbors`]
+/// assembles it), and each thread's Dijkstra distance array. Saturates at
+/// `u64::MAX` rather than overflowing, so a saturated
+/// [`SurfaceSpec::face_count`] still reads as too big.
+pub fn estimate_build_bytes(faces: usize, neighbors: usize, threads: usize) -> u64 {
+    let faces = faces as u64;
+    let mesh = faces.saturating_mul(MESH_BYTES_PER_FACE);
+    // Both copies of each index, plus each list's Vec and range.
+    let per_face = (neighbors as u64)
+        .saturating_mul(2 * 8)
+        .saturating_add(24 + 16);
+    let table = faces.saturating_mul(per_face);
+    let dijkstra = (threads as u64).saturating_mul(faces).saturating_mul(4);
+    mesh.saturating_add(table).saturating_add(dijkstra)
+}
+
+/// Rough bytes per cell of a running soup beyond its mesh: the program and
+/// its `Vec`, a share of the tape pool (one two-program tape per pair), the
+/// flat population buffer metrics copy into, and the per-cell scratch,
+/// ages, and halt records.
+pub fn soup_bytes_per_cell(program_size: usize) -> u64 {
+    let program_size = program_size as u64;
+    let program = 24 + program_size;
+    let tape = program_size;
+    let metrics = program_size;
+    // order, taken, half a pair, its halt, the cell's halt and age.
+    let scratch = 8 + 1 + 8 + 1 + 2 + 4;
+    program + tape + metrics + scratch
+}
+
+/// Rough peak memory, in bytes, of a `cells`-cell soup of
+/// `program_size`-byte programs: [`estimate_build_bytes`] for its mesh and
+/// neighbor table plus [`soup_bytes_per_cell`] for each cell.
+pub fn estimate_run_bytes(
+    cells: usize,
+    program_size: usize,
+    neighbors: usize,
+    threads: usize,
+) -> u64 {
+    estimate_build_bytes(cells, neighbors, threads)
+        .saturating_add((cells as u64).saturating_mul(soup_bytes_per_cell(program_size)))
+}
+
+/// Most cells [`population_for_budget`] sizes a soup to, however big the
+/// budget.
+pub const MAX_BUDGET_CELLS: usize = u32::MAX as usize;
+
+/// Most cells whose [`estimate_run_bytes`] fits in `budget` bytes, up to
+/// [`MAX_BUDGET_CELLS`].
+pub fn population_for_budget(
+    budget: u64,
+    program_size: usize,
+    neighbors: usize,
+    threads: usize,
+) -> usize {
+    let fits = |cells: usize| estimate_run_bytes(cells, program_size, neighbors, threads) <= budget;
+    // The estimate grows with the cell count, so binary search for the
+    // last cell count that fits.
+    let (mut lo, mut hi) = (0usize, 1usize);
+    while fits(hi) {
+        if hi == MAX_BUDGET_CELLS {
+            return hi;
+        }
+        lo = hi;
+        hi = hi.saturating_mul(2).min(MAX_BUDGET_CELLS);
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if fits(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
 impl SurfaceSpec {
     /// Build a SurfaceMesh from this spec (does NOT compute neighbors).
-    pub fn build(&self) -> Result<SurfaceMesh, String> {
+    pub fn build(&self) -> Result<SurfaceMesh, SurfaceError> {
         match self {
             SurfaceSpec::Sphere { subdivisions } => SurfaceMesh::icosphere(*subdivisions),
+            SurfaceSpec::CubeSphere { subdivisions } => SurfaceMesh::cube_sphere(*subdivisions),
             SurfaceSpec::Torus { major, minor } => SurfaceMesh::torus(*major, *minor),
             SurfaceSpec::FlatGrid { width, height } => SurfaceMesh::flat_grid(*width, *height),
             SurfaceSpec::HamsterTunnel {
@@ -1021,14 +2444,86 @@ impl SurfaceSpec {
             SurfaceSpec::TrefoilKnot { rings, segments } => {
                 SurfaceMesh::trefoil_knot(*rings, *segments)
             }
-            SurfaceSpec::ObjFile { path } => SurfaceMesh::from_obj(path),
+            SurfaceSpec::Grid3d {
+                width,
+                height,
+                depth,
+                periodic,
+            } => SurfaceMesh::lattice_3d(*width, *height, *depth, *periodic),
+            SurfaceSpec::HeightmapImage { path, scale } => {
+                SurfaceMesh::from_heightmap(path, *scale)
+            }
+            SurfaceSpec::ObjFile {
+                path,
+                normalize,
+                drop_degenerate,
+            } => {
+                let mut mesh = if *drop_degenerate {
+                    let (mesh, dropped) = SurfaceMesh::from_obj_without_degenerate(path)?;
+                    if dropped > 0 {
+                        log::status!("Dropped {dropped} degenerate faces from '{path}'");
+                    }
+                    mesh
+                } else {
+                    SurfaceMesh::from_obj(path)?
+                };
+                if *normalize {
+                    mesh.normalize();
+                }
+                Ok(mesh)
+            }
+        }
+    }
+
+    /// Number of faces the built mesh will have, or `None` for shapes read
+    /// from a file or generated at random (hamster tunnels), which aren't
+    /// known until they're built. Saturates instead of overflowing.
+    pub fn face_count(&self) -> Option<usize> {
+        let quads = |a: usize, b: usize| a.saturating_mul(b).saturating_mul(2);
+        match self {
+            SurfaceSpec::Sphere { subdivisions } => Some(
+                u32::try_from(*subdivisions)
+                    .ok()
+                    .and_then(|n| 4usize.checked_pow(n))
+                    .map_or(usize::MAX, |p| p.saturating_mul(20)),
+            ),
+            SurfaceSpec::CubeSphere { subdivisions } => {
+                Some(quads(*subdivisions, *subdivisions).saturating_mul(6))
+            }
+            SurfaceSpec::Torus { major, minor } => Some(quads(*major, *minor)),
+            SurfaceSpec::FlatGrid { width, height } => Some(quads(*width, *height)),
+            SurfaceSpec::Cylinder { segments, rings } => {
+                Some(quads(*segments, rings.saturating_add(1)))
+            }
+            SurfaceSpec::KleinBottle {
+                u_segments,
+                v_segments,
+            } => Some(quads(*u_segments, *v_segments)),
+            SurfaceSpec::Heightmap { width, height, .. } => Some(quads(*width, *height)),
+            SurfaceSpec::TrefoilKnot { rings, segments } => Some(quads(*rings, *segments)),
+            SurfaceSpec::Grid3d {
+                width,
+                height,
+                depth,
+                ..
+            } => Some(width.saturating_mul(*height).saturating_mul(*depth)),
+            SurfaceSpec::HamsterTunnel { .. }
+            | SurfaceSpec::HeightmapImage { .. }
+            | SurfaceSpec::ObjFile { .. } => None,
         }
     }
 
+    /// Whether [`SurfaceSpec::build`] installs the neighbor table itself,
+    /// so callers must skip `compute_neighbors` and keep cells on faces.
+    pub fn builds_neighbors(&self) -> bool {
+        matches!(self, SurfaceSpec::Grid3d { .. })
+    }
+
     /// Human-readable label.
     pub fn label(&self) -> &'static str {
         match self {
             SurfaceSpec::Sphere { .. } => "Sphere",
+            SurfaceSpec::CubeSphere { .. } => "Cube Sphere",
             SurfaceSpec::Torus { .. } => "Torus",
             SurfaceSpec::FlatGrid { .. } => "Flat Grid",
             SurfaceSpec::HamsterTunnel { .. } => "Hamster Tunnel",
@@ -1036,32 +2531,258 @@ impl SurfaceSpec {
             SurfaceSpec::KleinBottle { .. } => "Klein Bottle",
             SurfaceSpec::Heightmap { .. } => "Heightmap",
             SurfaceSpec::TrefoilKnot { .. } => "Trefoil Knot",
+            SurfaceSpec::Grid3d { .. } => "3D Lattice",
+            SurfaceSpec::HeightmapImage { .. } => "Heightmap Image",
             SurfaceSpec::ObjFile { .. } => "OBJ File",
         }
     }
+
+    /// Parse a command-line surface spec such as `sphere:4`, `cubesphere:32`,
+    /// `torus:32x16`, `flat:64x64` or `obj:path/to/mesh.obj`. `seed` is used
+    /// by the procedurally randomized shapes (hamster tunnel, heightmap).
+    ///
+    /// `heightmap:` takes either `WxH` (procedural noise) or an image path,
+    /// optionally followed by `:SCALE` (e.g. `heightmap:terrain.png:0.2`).
+    /// `grid3d:WxHxD` is a 3D lattice; append `:periodic` to wrap it.
+    pub fn parse(s: &str, seed: u64) -> Result<Self, String> {
+        let (kind, args) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid surface spec '{s}': expected KIND:ARGS"))?;
+        match kind {
+            "sphere" => Ok(SurfaceSpec::Sphere {
+                subdivisions: parse_count(args, s)?,
+            }),
+            "cubesphere" => Ok(SurfaceSpec::CubeSphere {
+                subdivisions: parse_count(args, s)?,
+            }),
+            "torus" => {
+                let (major, minor) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::Torus { major, minor })
+            }
+            "flat" => {
+                let (width, height) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::FlatGrid { width, height })
+            }
+            "hamster" => {
+                let (num_spheres, segments) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::HamsterTunnel {
+                    num_spheres,
+                    segments,
+                    seed,
+                })
+            }
+            "cylinder" => {
+                let (segments, rings) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::Cylinder { segments, rings })
+            }
+            "klein" => {
+                let (u_segments, v_segments) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::KleinBottle {
+                    u_segments,
+                    v_segments,
+                })
+            }
+            "heightmap" => {
+                if let Ok((width, height)) = parse_dims(args, s) {
+                    return Ok(SurfaceSpec::Heightmap {
+                        width,
+                        height,
+                        seed,
+                    });
+                }
+                let (path, scale) = match args.rsplit_once(':') {
+                    Some((path, scale)) if scale.parse::<f32>().is_ok() => {
+                        (path, scale.parse::<f32>().unwrap())
+                    }
+                    _ => (args, DEFAULT_HEIGHTMAP_IMAGE_SCALE),
+                };
+                Ok(SurfaceSpec::HeightmapImage {
+                    path: path.to_string(),
+                    scale,
+                })
+            }
+            "trefoil" => {
+                let (rings, segments) = parse_dims(args, s)?;
+                Ok(SurfaceSpec::TrefoilKnot { rings, segments })
+            }
+            "grid3d" => {
+                let (dims, periodic) = match args.strip_suffix(":periodic") {
+                    Some(dims) => (dims, true),
+                    None => (args, false),
+                };
+                let (width, rest) = dims.split_once('x').ok_or_else(|| {
+                    format!("Invalid surface spec '{s}': expected WxHxD dimensions")
+                })?;
+                let (height, depth) = parse_dims(rest, s)?;
+                Ok(SurfaceSpec::Grid3d {
+                    width: parse_count(width, s)?,
+                    height,
+                    depth,
+                    periodic,
+                })
+            }
+            "obj" => Ok(SurfaceSpec::ObjFile {
+                path: args.to_string(),
+                normalize: false,
+                drop_degenerate: false,
+            }),
+            _ => Err(format!(
+                "Unknown surface kind '{kind}' (expected sphere, cubesphere, torus, flat, \
+                 hamster, cylinder, klein, heightmap, trefoil, grid3d, or obj)"
+            )),
+        }
+    }
 }
 
-// ─── Geometry helpers ────────────────────────────────────────────────────────
+/// Parse a single non-negative count from a surface spec argument.
+fn parse_count(args: &str, spec: &str) -> Result<usize, String> {
+    args.parse::<usize>()
+        .map_err(|e| format!("Invalid surface spec '{spec}': {e}"))
+}
 
-/// Build face adjacency table. Returns error if non-manifold edges found.
-fn build_face_adjacency(faces: &[[usize; 3]]) -> Result<Vec<Vec<usize>>, String> {
-    let mut edge_to_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+/// Parse `AxB` dimensions from a surface spec argument.
+fn parse_dims(args: &str, spec: &str) -> Result<(usize, usize), String> {
+    let (a, b) = args
+        .split_once('x')
+        .ok_or_else(|| format!("Invalid surface spec '{spec}': expected AxB dimensions"))?;
+    Ok((parse_count(a, spec)?, parse_count(b, spec)?))
+}
 
-    for (fi, face) in faces.iter().enumerate() {
-        for e in 0..3 {
-            let v0 = face[e];
-            let v1 = face[(e + 1) % 3];
-            let edge = if v0 < v1 { (v0, v1) } else { (v1, v0) };
-            edge_to_faces.entry(edge).or_default().push(fi);
+// ─── Interaction graph ───────────────────────────────────────────────────────
+
+/// Restarts of the random pairing before giving up on a regular graph.
+const REGULAR_GRAPH_ATTEMPTS: usize = 100;
+
+/// A synthetic neighbor table that replaces the geodesic one, decoupling
+/// who interacts with whom from the shape of any surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InteractionGraph {
+    /// Uniformly wired graph in which every cell has exactly `degree`
+    /// neighbors.
+    RandomRegular {
+        degree: usize,
+        cells: usize,
+        seed: u64,
+    },
+}
+
+impl InteractionGraph {
+    /// Build a flat-grid mesh with one face per cell, for rendering and
+    /// bookkeeping, and install this graph as its neighbor table.
+    pub fn build(&self) -> Result<SurfaceMesh, SurfaceError> {
+        match *self {
+            InteractionGraph::RandomRegular {
+                degree,
+                cells,
+                seed,
+            } => {
+                if cells == 0 || !cells.is_multiple_of(2) {
+                    return Err(SurfaceError::InvalidParams(format!(
+                        "Graph cell count must be even and positive (two triangles per \
+                         grid square), got {cells}"
+                    )));
+                }
+                let squares = cells / 2;
+                let width = (1..=squares.isqrt())
+                    .rev()
+                    .find(|w| squares.is_multiple_of(*w))
+                    .unwrap_or(1);
+                let mut mesh = SurfaceMesh::flat_grid(width, squares / width)?;
+                let neighbors = random_regular_graph(cells, degree, seed)
+                    .map_err(SurfaceError::InvalidParams)?;
+                log::status!("Graph: random {degree}-regular ({cells} cells)");
+                mesh.set_neighbors(&neighbors);
+                Ok(mesh)
+            }
+        }
+    }
+
+    /// Human-readable label.
+    pub fn label(&self) -> String {
+        match self {
+            InteractionGraph::RandomRegular { degree, .. } => {
+                format!("Random {degree}-regular graph")
+            }
+        }
+    }
+
+    /// Parse a command-line graph spec: `regular:K:N` is a random
+    /// K-regular graph on N cells, wired from `seed`.
+    pub fn parse(s: &str, seed: u64) -> Result<Self, String> {
+        let invalid = || format!("Invalid graph spec '{s}': expected regular:K:N");
+        let mut parts = s.split(':');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some("regular"), Some(k), Some(n), None) => Ok(InteractionGraph::RandomRegular {
+                degree: k.parse().map_err(|_| invalid())?,
+                cells: n.parse().map_err(|_| invalid())?,
+                seed,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Adjacency lists of a simple random `degree`-regular graph on `n` nodes.
+///
+/// Pairs up `degree` stubs per node at random, rejecting self-loops and
+/// repeated edges, and starts over when the remaining stubs can't be
+/// paired. Works well for the small degrees used as neighborhoods.
+fn random_regular_graph(n: usize, degree: usize, seed: u64) -> Result<Vec<Vec<usize>>, String> {
+    if degree >= n || !(n * degree).is_multiple_of(2) {
+        return Err(format!("No simple {degree}-regular graph on {n} nodes"));
+    }
+    let mut rng = SmallRng::seed_from_u64(seed);
+    'attempt: for _ in 0..REGULAR_GRAPH_ATTEMPTS {
+        let mut stubs: Vec<usize> = (0..n)
+            .flat_map(|v| std::iter::repeat_n(v, degree))
+            .collect();
+        let mut adjacency = vec![Vec::with_capacity(degree); n];
+        while !stubs.is_empty() {
+            let paired = (0..stubs.len()).find_map(|_| {
+                let (i, j) = (rng.gen_range(0..stubs.len()), rng.gen_range(0..stubs.len()));
+                let (u, v) = (stubs[i], stubs[j]);
+                (u != v && !adjacency[u].contains(&v)).then_some((i.max(j), i.min(j)))
+            });
+            let Some((hi, lo)) = paired else {
+                continue 'attempt;
+            };
+            let u = stubs.swap_remove(hi);
+            let v = stubs.swap_remove(lo);
+            adjacency[u].push(v);
+            adjacency[v].push(u);
+        }
+        for list in &mut adjacency {
+            list.sort_unstable();
+        }
+        return Ok(adjacency);
+    }
+    Err(format!(
+        "Failed to wire a {degree}-regular graph on {n} nodes after \
+         {REGULAR_GRAPH_ATTEMPTS} attempts"
+    ))
+}
+
+// ─── Geometry helpers ────────────────────────────────────────────────────────
+
+/// Build face adjacency table. Returns error if non-manifold edges found.
+fn build_face_adjacency(faces: &[[usize; 3]]) -> Result<Vec<Vec<usize>>, SurfaceError> {
+    let mut edge_to_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+    for (fi, face) in faces.iter().enumerate() {
+        for e in 0..3 {
+            let v0 = face[e];
+            let v1 = face[(e + 1) % 3];
+            let edge = if v0 < v1 { (v0, v1) } else { (v1, v0) };
+            edge_to_faces.entry(edge).or_default().push(fi);
         }
     }
 
     for (&(v0, v1), face_list) in &edge_to_faces {
         if face_list.len() > 2 {
-            return Err(format!(
-                "Non-manifold edge ({v0}, {v1}): shared by {} faces",
-                face_list.len()
-            ));
+            return Err(SurfaceError::NonManifold {
+                edge: (v0, v1),
+                count: face_list.len(),
+            });
         }
     }
 
@@ -1076,6 +2797,115 @@ fn build_face_adjacency(faces: &[[usize; 3]]) -> Result<Vec<Vec<usize>>, String>
     Ok(adjacency)
 }
 
+/// Vertices and triangular faces, as read from a file.
+type Geometry = (Vec<[f32; 3]>, Vec<[usize; 3]>);
+
+/// Parse a Wavefront OBJ file into vertices and fan-triangulated faces.
+fn read_obj(path: &str) -> Result<Geometry, SurfaceError> {
+    let content = std::fs::read_to_string(path).map_err(|source| SurfaceError::Io {
+        path: path.into(),
+        source,
+    })?;
+
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => {
+                let coords: Vec<f32> = parts
+                    .take(3)
+                    .map(|s| s.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| SurfaceError::ParseError {
+                        line: line_num + 1,
+                        msg: format!("invalid vertex coordinate: {e}"),
+                    })?;
+                if coords.len() < 3 {
+                    return Err(SurfaceError::ParseError {
+                        line: line_num + 1,
+                        msg: "vertex needs 3 coordinates".into(),
+                    });
+                }
+                vertices.push([coords[0], coords[1], coords[2]]);
+            }
+            Some("f") => {
+                let indices: Vec<usize> = parts
+                    .map(|s| {
+                        // Handle v, v/vt, v/vt/vn, v//vn formats.
+                        let idx_str = s.split('/').next().unwrap();
+                        resolve_obj_index(idx_str, vertices.len())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|msg| SurfaceError::ParseError {
+                        line: line_num + 1,
+                        msg,
+                    })?;
+
+                if indices.len() < 3 {
+                    return Err(SurfaceError::ParseError {
+                        line: line_num + 1,
+                        msg: "face needs at least 3 vertices".into(),
+                    });
+                }
+                // Fan triangulation for quads and n-gons.
+                for i in 1..indices.len() - 1 {
+                    faces.push([indices[0], indices[i], indices[i + 1]]);
+                }
+            }
+            _ => {} // Ignore vn, vt, mtllib, usemtl, etc.
+        }
+    }
+
+    if faces.is_empty() {
+        return Err(SurfaceError::EmptyMesh);
+    }
+
+    log::status!(
+        "Loaded OBJ: {} vertices, {} faces",
+        vertices.len(),
+        faces.len()
+    );
+    Ok((vertices, faces))
+}
+
+/// Zero-based vertex index for an OBJ face index: 1-based when positive,
+/// or counting back from the `vertex_count` vertices read so far when
+/// negative (-1 is the latest). Indices past the end are left for
+/// `from_geometry` to report.
+fn resolve_obj_index(s: &str, vertex_count: usize) -> Result<usize, String> {
+    let index: i64 = s
+        .parse()
+        .map_err(|e| format!("invalid face index '{s}': {e}"))?;
+    match index {
+        0 => Err("face index 0 is invalid (OBJ indices start at 1)".into()),
+        1.. => Ok(index as usize - 1),
+        _ => vertex_count
+            .checked_sub(index.unsigned_abs() as usize)
+            .ok_or_else(|| {
+                format!(
+                    "face index {index} reaches before the first vertex ({vertex_count} so far)"
+                )
+            }),
+    }
+}
+
+/// Whether `face` has (near-)zero area, as with collinear or repeated
+/// vertices.
+fn is_degenerate(vertices: &[[f32; 3]], face: [usize; 3]) -> bool {
+    let [a, b, c] = face.map(|vi| vertices[vi]);
+    let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = cross3(e1, e2);
+    dot3(n, n).sqrt() < 1e-10
+}
+
 /// Compute face centroids.
 fn compute_face_centroids(vertices: &[[f32; 3]], faces: &[[usize; 3]]) -> Vec<[f32; 3]> {
     faces
@@ -1226,7 +3056,7 @@ fn fbm_2d(x: f32, y: f32, seed: u64, octaves: u32) -> f32 {
 /// Sample from geometric distribution via CDF inversion.
 /// Returns the number of bytes to skip before the next mutation.
 /// `inv_log` should be `1.0 / ln(1 - mutation_rate)` (precomputed).
-fn geometric_skip(rng: &mut SmallRng, inv_log: f64) -> usize {
+fn geometric_skip(rng: &mut impl Rng, inv_log: f64) -> usize {
     let u: f64 = rng.r#gen::<f64>();
     if u < 1e-300 {
         return usize::MAX;
@@ -1236,17 +3066,195 @@ fn geometric_skip(rng: &mut SmallRng, inv_log: f64) -> usize {
 
 // ─── SoupSurface ─────────────────────────────────────────────────────────────
 
+/// Second slot of a pair whose partner half is all zeros (self-pairing).
+pub const NO_PARTNER: usize = usize::MAX;
+
+/// Mixed into `mutate`'s per-epoch seed so it doesn't replay the draws
+/// `run_epoch` made from the same epoch's seed.
+const MUTATE_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Mixed into the seed that picks mutator cells, so enabling them leaves
+/// the rest of the run's draws alone.
+const MUTATOR_STREAM: u64 = 0xBF58_476D_1CE4_E5B9;
+
+/// Mixed into the seed that assigns substrates under an ensemble, so
+/// assigning them leaves the rest of the run's draws alone.
+const ENSEMBLE_STREAM: u64 = 0x94D0_49BB_1331_11EB;
+
+/// Runs one tape and reports the steps it took and why it stopped: a
+/// substrate's [`Substrate::execute_status`], so an ensemble can hold
+/// several.
+pub type ExecuteFn = fn(&mut [u8], usize) -> (usize, Halt);
+
+/// Substrates a mixed soup's cells run under; see
+/// [`SoupSurface::set_ensemble`].
+#[derive(Clone)]
+struct Ensemble {
+    executors: Vec<ExecuteFn>,
+    /// Each cell's index into `executors`.
+    substrates: Vec<usize>,
+}
+
+/// Most events a watch log holds; older ones are dropped first.
+pub const WATCH_LOG_CAPACITY: usize = 1024;
+
+/// How the watched cell took part in one epoch's pairing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchRole {
+    /// It sat the epoch out.
+    Unpaired,
+    /// It ran against an all-zero partner half.
+    SelfPaired,
+    /// Its program ran first on the shared tape, against `partner`.
+    Aggressor { partner: usize },
+    /// `partner`'s program ran first, against it.
+    Target { partner: usize },
+}
+
+/// One epoch of the watched cell's history, from
+/// [`SoupSurface::take_watch_log`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub epoch: usize,
+    pub cell: usize,
+    pub role: WatchRole,
+    /// Bytes of the cell's program that differ from the previous event's,
+    /// counting any mutation in between.
+    pub changed_bytes: usize,
+}
+
+/// Copy an executed `tape` back over the programs of `(first, second)`,
+/// recording a [`CopyEvent`] into `copy_events`, if given, for each half
+/// that now holds its partner's program and zeroing the age of each cell
+/// whose program changed.
+fn write_back(
+    programs: &mut [Vec<u8>],
+    copy_events: Option<&mut Vec<CopyEvent>>,
+    multipliers: Option<&mut [f64]>,
+    ages: &mut [u32],
+    (first, second): (usize, usize),
+    tape: &[u8],
+) {
+    let (a, b) = tape.split_at(programs[first].len());
+    if programs[first] != a {
+        ages[first] = 0;
+    }
+    if second != NO_PARTNER {
+        // Copies only matter to a consumer of copy events or to carry a
+        // mutation multiplier; otherwise skip comparing the programs.
+        if copy_events.is_some() || multipliers.is_some() {
+            let into_second = is_copy(b, &programs[first], &programs[second]);
+            let into_first = is_copy(a, &programs[second], &programs[first]);
+            if let Some(copy_events) = copy_events {
+                if into_second {
+                    copy_events.push(CopyEvent {
+                        source: first,
+                        dest: second,
+                    });
+                }
+                if into_first {
+                    copy_events.push(CopyEvent {
+                        source: second,
+                        dest: first,
+                    });
+                }
+            }
+            // A copy carries its source's mutation multiplier along.
+            if let Some(multipliers) = multipliers {
+                let (from_first, from_second) = (multipliers[first], multipliers[second]);
+                if into_second {
+                    multipliers[second] = from_first;
+                }
+                if into_first {
+                    multipliers[first] = from_second;
+                }
+            }
+        }
+        if programs[second] != b {
+            ages[second] = 0;
+        }
+        programs[second].copy_from_slice(b);
+    }
+    programs[first].copy_from_slice(a);
+}
+
 /// Configuration for a surface simulation.
-#[derive(Clone, Copy)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SoupSurfaceConfig {
     /// Bytes per program.
     pub program_size: usize,
     /// Maximum steps per program execution.
     pub step_limit: usize,
-    /// Per-byte mutation probability per epoch.
+    /// Per-byte mutation probability per epoch; the baseline when
+    /// `adaptive_mutation` is set.
     pub mutation_rate: f64,
+    /// Ramp the mutation rate up while diversity is collapsed.
+    pub adaptive_mutation: Option<AdaptiveMutation>,
+    /// Cells seeded with a heritable elevated mutation rate.
+    #[serde(default)]
+    pub mutators: Option<Mutators>,
+    /// Stop mutating from this epoch on, so the population settles without
+    /// new variants.
+    #[serde(default)]
+    pub no_mutation_after: Option<usize>,
+    /// Per-program probability per epoch of a rearrangement: a random
+    /// window of the program rotated by one byte or reversed.
+    #[serde(default)]
+    pub rearrange_rate: f64,
     /// How paired programs interact during execution.
     pub interaction_mode: InteractionMode,
+    /// How each mutation site picks its new value.
+    pub mutation_model: MutationModel,
+    /// Post-execution selection between the two halves of each pair.
+    pub selection: SelectionMode,
+    /// Play Conway's Game of Life on a flat grid of this `(width, height)`
+    /// instead of executing pairs, without mutation.
+    #[serde(default)]
+    pub life: Option<(usize, usize)>,
+    /// Probability that a program is run against an all-zero partner
+    /// instead of a neighbor, isolating self-copying from parasitism.
+    pub self_pair_fraction: f64,
+    /// Whether the RNG streams through the run or restarts every epoch.
+    #[serde(default)]
+    pub rng_mode: RngMode,
+    /// Flow that biases which program of each pair executes first.
+    #[serde(default)]
+    pub drift: Drift,
+    /// Whether interactions within an epoch see each other's results.
+    #[serde(default)]
+    pub update_mode: UpdateMode,
+    /// How executed tapes are written back over their programs.
+    #[serde(default)]
+    pub write_back: WriteBackPolicy,
+    /// Count RNG draws per epoch phase, reported by
+    /// [`SoupSurface::rng_draws`]. Off, the soup draws from a bare
+    /// [`SmallRng`].
+    #[serde(default)]
+    pub count_rng: bool,
+}
+
+impl Default for SoupSurfaceConfig {
+    fn default() -> Self {
+        Self {
+            program_size: 64,
+            step_limit: 1 << 13,
+            mutation_rate: 0.00024,
+            adaptive_mutation: None,
+            mutators: None,
+            no_mutation_after: None,
+            rearrange_rate: 0.0,
+            interaction_mode: InteractionMode::Normal,
+            mutation_model: MutationModel::BitFlip,
+            selection: SelectionMode::None,
+            life: None,
+            self_pair_fraction: 0.0,
+            rng_mode: RngMode::Streaming,
+            drift: Drift::default(),
+            update_mode: UpdateMode::Synchronous,
+            write_back: WriteBackPolicy::Replace,
+            count_rng: false,
+        }
+    }
 }
 
 /// A primordial soup simulation running on a triangle mesh surface.
@@ -1254,7 +3262,7 @@ pub struct SoupSurface {
     pub programs: Vec<Vec<u8>>,
     pub config: SoupSurfaceConfig,
     pub mesh: SurfaceMesh,
-    pub rng: SmallRng,
+    pub rng: SoupRng,
     /// Reusable scratch: shuffled iteration order.
     order: Vec<usize>,
     /// Reusable scratch: taken flags.
@@ -1263,12 +3271,40 @@ pub struct SoupSurface {
     pairs: Vec<(usize, usize)>,
     /// Reusable scratch: flat tape buffer for parallel execution.
     tape_pool: Vec<u8>,
+    /// Reusable scratch: why each pair's execution stopped.
+    pair_halts: Vec<Halt>,
+    /// Why each cell's program last stopped when it ran first in a pair.
+    halts: Vec<Option<Halt>>,
+    /// Epochs since an interaction last changed each cell's program.
+    ages: Vec<u32>,
+    /// Copies observed since the last [`SoupSurface::take_copy_events`].
+    copy_events: Vec<CopyEvent>,
+    /// Whether epochs record `copy_events`; see
+    /// [`SoupSurface::record_copy_events`].
+    record_copies: bool,
+    /// Per-byte mutation probability currently in effect.
+    mutation_rate: f64,
+    /// Each cell's multiple of `mutation_rate`, under `config.mutators`.
+    mutation_multipliers: Option<Vec<f64>>,
+    /// Seed the soup was created with; `RngMode::PerEpoch` derives from it.
+    seed: u64,
+    /// Epochs run so far.
+    epoch: usize,
+    /// Cell whose interactions are logged, and its program as of the last
+    /// event.
+    watch: Option<(usize, Vec<u8>)>,
+    /// Events since the last [`SoupSurface::take_watch_log`].
+    watch_log: VecDeque<WatchEvent>,
+    /// Per-cell substrates, when the soup mixes several.
+    ensemble: Option<Ensemble>,
+    /// Draws made by each phase of the current epoch, when counting.
+    draws: RngDraws,
 }
 
 impl SoupSurface {
     /// Create a new surface soup with randomly initialized programs.
     pub fn new(mesh: SurfaceMesh, config: SoupSurfaceConfig, seed: u64) -> Self {
-        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut rng = SoupRng::new(seed, config.count_rng);
         let total = mesh.num_cells();
         let programs = (0..total)
             .map(|_| {
@@ -1282,9 +3318,23 @@ impl SoupSurface {
         let taken = vec![false; total];
         let pairs = Vec::with_capacity(total / 2);
         let tape_pool = Vec::new();
+        let mutation_multipliers = config.mutators.map(|mutators| {
+            let mut rng = SmallRng::seed_from_u64(seed ^ MUTATOR_STREAM);
+            (0..total)
+                .map(|_| {
+                    if rng.r#gen::<f64>() < mutators.fraction {
+                        mutators.strength.max(0.0)
+                    } else {
+                        1.0
+                    }
+                })
+                .collect()
+        });
 
         Self {
             programs,
+            mutation_rate: config.mutation_rate,
+            mutation_multipliers,
             config,
             mesh,
             rng,
@@ -1292,53 +3342,204 @@ impl SoupSurface {
             taken,
             pairs,
             tape_pool,
+            pair_halts: Vec::new(),
+            halts: vec![None; total],
+            ages: vec![0; total],
+            copy_events: Vec::new(),
+            record_copies: false,
+            seed,
+            epoch: 0,
+            watch: None,
+            watch_log: VecDeque::new(),
+            ensemble: None,
+            draws: RngDraws::default(),
         }
     }
 
-    /// Run one epoch: pair each cell with a random geodesic neighbor, execute
-    /// in parallel.
-    pub fn run_epoch<S: Substrate + Sync>(&mut self) {
-        let total = self.mesh.num_cells();
-        let ps = self.config.program_size;
-        let step_limit = self.config.step_limit;
+    /// Mix substrates: give each cell one of `executors`, the `i`th with
+    /// probability proportional to `weights[i]`, drawn from the soup's seed.
+    ///
+    /// A pair's tape holds two programs but only one interpreter can run
+    /// it, so each pair runs under its first program's substrate: the
+    /// aggressor decides how the shared tape is read. Assignments belong to
+    /// cells and stay put when programs copy over each other. Battling and
+    /// the tournament's instruction count still use the substrate
+    /// `run_epoch` is called with, as does the step limit.
+    pub fn set_ensemble(&mut self, executors: Vec<ExecuteFn>, weights: &[f64]) {
+        assert_eq!(executors.len(), weights.len(), "one weight per substrate");
+        let total: f64 = weights.iter().sum();
+        let mut rng = SmallRng::seed_from_u64(self.seed ^ ENSEMBLE_STREAM);
+        let substrates = (0..self.programs.len())
+            .map(|_| {
+                let mut draw = rng.r#gen::<f64>() * total;
+                weights
+                    .iter()
+                    .position(|&w| {
+                        draw -= w;
+                        draw < 0.0
+                    })
+                    .unwrap_or(weights.len() - 1)
+            })
+            .collect();
+        self.ensemble = Some(Ensemble {
+            executors,
+            substrates,
+        });
+    }
 
-        // --- Phase 1: build pairs (sequential) ---
+    /// Each cell's index into the executors given to
+    /// [`SoupSurface::set_ensemble`], if the soup mixes substrates.
+    pub fn cell_substrates(&self) -> Option<&[usize]> {
+        self.ensemble.as_ref().map(|e| e.substrates.as_slice())
+    }
 
-        for i in 0..total {
-            self.order[i] = i;
+    /// Run `tape` for a pair whose first program is `first`: under its
+    /// substrate in an ensemble, otherwise under `S`.
+    fn execute_first<S: Substrate>(
+        ensemble: Option<&Ensemble>,
+        first: usize,
+        tape: &mut [u8],
+        step_limit: usize,
+    ) -> Halt {
+        let (_, halt) = match ensemble {
+            Some(e) => (e.executors[e.substrates[first]])(tape, step_limit),
+            None => S::execute_status(tape, step_limit),
+        };
+        halt
+    }
+
+    /// A soup created with `seed` that has already run `epoch` epochs and
+    /// holds `programs`. Under `RngMode::PerEpoch` it continues exactly as
+    /// the original run would have.
+    pub fn resume(
+        mesh: SurfaceMesh,
+        config: SoupSurfaceConfig,
+        seed: u64,
+        epoch: usize,
+        programs: Vec<Vec<u8>>,
+    ) -> Result<Self, String> {
+        if programs.len() != mesh.num_cells() {
+            return Err(format!(
+                "Cannot resume {} programs on a surface of {} cells",
+                programs.len(),
+                mesh.num_cells()
+            ));
         }
-        self.order.shuffle(&mut self.rng);
+        if let Some(p) = programs.iter().find(|p| p.len() != config.program_size) {
+            return Err(format!(
+                "Cannot resume a {}-byte program with program size {}",
+                p.len(),
+                config.program_size
+            ));
+        }
+        let mut soup = Self::new(mesh, config, seed);
+        soup.programs = programs;
+        soup.epoch = epoch;
+        Ok(soup)
+    }
 
-        self.taken.fill(false);
-        self.pairs.clear();
+    /// Epochs run so far.
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
 
-        for i in 0..total {
-            let p_idx = self.order[i];
-            if self.taken[p_idx] {
-                continue;
-            }
+    /// Seed the soup was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 
-            let (start, end) = self.mesh.neighbor_ranges[p_idx];
-            let neighbor_count = end - start;
-            if neighbor_count == 0 {
-                continue;
-            }
+    /// RNG draws made by each phase of the current epoch, counting its
+    /// [`SoupSurface::mutate`]; `None` unless `config.count_rng` was set
+    /// when the soup was created.
+    pub fn rng_draws(&self) -> Option<RngDraws> {
+        matches!(self.rng, SoupRng::Counting(_)).then_some(self.draws)
+    }
 
-            let n_idx = self.mesh.neighbor_indices[start + self.rng.gen_range(0..neighbor_count)];
-            if self.taken[n_idx] {
-                continue;
+    /// Run one epoch: pair each cell with a random geodesic neighbor, execute
+    /// in parallel. A Game of Life soup (`config.life`) plays one generation
+    /// instead.
+    pub fn run_epoch<S: Substrate + Sync>(&mut self) {
+        self.begin_epoch();
+        if let Some((width, height)) = self.config.life {
+            self.run_life_epoch(width, height);
+            return;
+        }
+
+        // --- Phase 1: build pairs (sequential) ---
+
+        self.build_pairs();
+        self.execute_pairs::<S>();
+    }
+
+    /// Run one epoch like [`SoupSurface::run_epoch`], but with `pairs` as
+    /// the epoch's `(first, second)` pairs instead of drawing them, as
+    /// [`SoupSurface::pairs`] reported them. Nothing is drawn for the
+    /// pairing, so under [`RngMode::Streaming`] later draws differ from a
+    /// run that drew these pairs; under [`RngMode::PerEpoch`] they don't.
+    /// Only [`UpdateMode::Asynchronous`] accepts a cell in several pairs.
+    pub fn run_epoch_with_pairs<S: Substrate + Sync>(
+        &mut self,
+        pairs: &[(usize, usize)],
+    ) -> Result<(), String> {
+        let total = self.programs.len();
+        let mut seen = vec![false; total];
+        for &(first, second) in pairs {
+            let partner = (second != NO_PARTNER).then_some(second);
+            for cell in std::iter::once(first).chain(partner) {
+                if cell >= total {
+                    return Err(format!("Cell {cell} is past the {total} on this surface"));
+                }
+                if std::mem::replace(&mut seen[cell], true)
+                    && self.config.update_mode == UpdateMode::Synchronous
+                {
+                    return Err(format!("Cell {cell} is paired more than once in an epoch"));
+                }
             }
+        }
+        self.begin_epoch();
+        self.pairs.clear();
+        self.pairs.extend_from_slice(pairs);
+        self.execute_pairs::<S>();
+        Ok(())
+    }
 
-            self.taken[p_idx] = true;
-            self.taken[n_idx] = true;
+    /// The `(first, second)` pairs the most recent epoch executed; a
+    /// self-paired cell's partner is [`NO_PARTNER`].
+    pub fn pairs(&self) -> &[(usize, usize)] {
+        &self.pairs
+    }
 
-            let (first, second) = if self.rng.r#gen::<bool>() {
-                (p_idx, n_idx)
-            } else {
-                (n_idx, p_idx)
-            };
+    /// Advance the epoch counter, reseeding the RNG under
+    /// [`RngMode::PerEpoch`].
+    fn begin_epoch(&mut self) {
+        debug_assert!(
+            !self.mesh.is_geometry_dirty(),
+            "mesh vertices moved after its neighbor table was built; call compute_neighbors"
+        );
+        self.epoch += 1;
+        for age in &mut self.ages {
+            *age = age.saturating_add(1);
+        }
+        if self.config.rng_mode == RngMode::PerEpoch {
+            self.rng.reseed(self.seed ^ self.epoch as u64);
+        }
+        self.draws = RngDraws::default();
+    }
+
+    /// Execute this epoch's `pairs` and write the results back.
+    fn execute_pairs<S: Substrate + Sync>(&mut self) {
+        let drawn = self.rng.draws();
+        self.execute_and_write_back::<S>();
+        self.draws.write_back = self.rng.draws() - drawn;
+    }
 
-            self.pairs.push((first, second));
+    fn execute_and_write_back<S: Substrate + Sync>(&mut self) {
+        let ps = self.config.program_size;
+        let step_limit = self.config.step_limit;
+        if self.config.update_mode == UpdateMode::Asynchronous {
+            self.run_pairs_sequentially::<S>();
+            self.record_watch();
+            return;
         }
 
         // --- Phase 2: execute all pairs in parallel ---
@@ -1351,14 +3552,28 @@ impl SoupSurface {
         for (i, &(first, second)) in self.pairs.iter().enumerate() {
             let base = i * tape_size;
             self.tape_pool[base..base + ps].copy_from_slice(&self.programs[first]);
-            self.tape_pool[base + ps..base + tape_size].copy_from_slice(&self.programs[second]);
+            let partner = &mut self.tape_pool[base + ps..base + tape_size];
+            if second == NO_PARTNER {
+                partner.fill(0);
+            } else {
+                partner.copy_from_slice(&self.programs[second]);
+            }
         }
 
         match self.config.interaction_mode {
             InteractionMode::Normal => {
-                self.tape_pool.par_chunks_mut(tape_size).for_each(|tape| {
-                    S::execute(tape, step_limit);
-                });
+                self.pair_halts.resize(num_pairs, Halt::Halted);
+                let ensemble = self.ensemble.as_ref();
+                self.tape_pool
+                    .par_chunks_mut(tape_size)
+                    .zip(self.pair_halts.par_iter_mut())
+                    .zip(self.pairs.par_iter())
+                    .for_each(|((tape, halt), &(first, _))| {
+                        *halt = Self::execute_first::<S>(ensemble, first, tape, step_limit);
+                    });
+                for (&(first, _), &halt) in self.pairs.iter().zip(&self.pair_halts) {
+                    self.halts[first] = Some(halt);
+                }
             }
             InteractionMode::Battling => {
                 self.tape_pool.par_chunks_mut(tape_size).for_each(|tape| {
@@ -1367,218 +3582,1866 @@ impl SoupSurface {
             }
         }
 
+        if let SelectionMode::Tournament(fitness) = self.config.selection {
+            let programs = &self.programs;
+            self.tape_pool
+                .par_chunks_mut(tape_size)
+                .zip(self.pairs.par_iter())
+                .for_each(|(tape, &(first, second))| {
+                    if second != NO_PARTNER {
+                        fitness.select::<S>(tape, ps, &programs[first], &programs[second]);
+                    }
+                });
+        }
+
         for (i, &(first, second)) in self.pairs.iter().enumerate() {
             let base = i * tape_size;
-            self.programs[first].copy_from_slice(&self.tape_pool[base..base + ps]);
-            self.programs[second].copy_from_slice(&self.tape_pool[base + ps..base + tape_size]);
+            self.config.write_back.apply_pair(
+                &mut self.tape_pool[base..base + tape_size],
+                &self.programs,
+                (first, second),
+                &mut self.rng,
+            );
+            write_back(
+                &mut self.programs,
+                self.record_copies.then_some(&mut self.copy_events),
+                self.mutation_multipliers.as_deref_mut(),
+                &mut self.ages,
+                (first, second),
+                &self.tape_pool[base..base + tape_size],
+            );
         }
+        self.record_watch();
     }
 
-    /// Apply background mutation to all programs.
-    ///
-    /// Uses geometric distribution to skip directly to the next mutation site,
-    /// reducing RNG calls from O(total_bytes) to O(total_bytes * mutation_rate).
-    pub fn mutate(&mut self) {
-        if self.config.mutation_rate <= 0.0 {
+    /// Log what happens to `cell` each epoch from now on, or stop logging
+    /// with `None`. Clears the log either way.
+    pub fn set_watch(&mut self, cell: Option<usize>) {
+        self.watch = cell
+            .filter(|&cell| cell < self.programs.len())
+            .map(|cell| (cell, self.programs[cell].clone()));
+        self.watch_log.clear();
+    }
+
+    /// Events recorded since the last call for the cell given to
+    /// [`SoupSurface::set_watch`], oldest first, leaving the log empty. At
+    /// most [`WATCH_LOG_CAPACITY`] are kept.
+    pub fn take_watch_log(&mut self) -> Vec<WatchEvent> {
+        self.watch_log.drain(..).collect()
+    }
+
+    /// Append the watched cell's role in this epoch's pairs and how far its
+    /// program moved since the previous event.
+    fn record_watch(&mut self) {
+        let Some((cell, previous)) = &mut self.watch else {
             return;
+        };
+        let cell = *cell;
+        let role = self
+            .pairs
+            .iter()
+            .find_map(|&(first, second)| match (first == cell, second) {
+                (true, NO_PARTNER) => Some(WatchRole::SelfPaired),
+                (true, partner) => Some(WatchRole::Aggressor { partner }),
+                (false, _) if second == cell => Some(WatchRole::Target { partner: first }),
+                _ => None,
+            })
+            .unwrap_or(WatchRole::Unpaired);
+        let program = &self.programs[cell];
+        let changed_bytes = program
+            .iter()
+            .zip(&*previous)
+            .filter(|(a, b)| a != b)
+            .count();
+        previous.clone_from(program);
+        if self.watch_log.len() == WATCH_LOG_CAPACITY {
+            self.watch_log.pop_front();
         }
-        let total_bytes = self.programs.len() * self.config.program_size;
-        let ps = self.config.program_size;
-        let inv_log = 1.0 / (1.0 - self.config.mutation_rate).ln();
+        self.watch_log.push_back(WatchEvent {
+            epoch: self.epoch,
+            cell,
+            role,
+            changed_bytes,
+        });
+    }
 
-        let mut pos = geometric_skip(&mut self.rng, inv_log);
-        while pos < total_bytes {
-            let prog_idx = pos / ps;
-            let byte_idx = pos % ps;
-            let bit = 1u8 << self.rng.gen_range(0..8);
-            self.programs[prog_idx][byte_idx] ^= bit;
-            pos = pos
-                .saturating_add(1)
-                .saturating_add(geometric_skip(&mut self.rng, inv_log));
+    /// Fill `pairs` with this epoch's `(first, second)` pairs. A self-paired
+    /// cell's partner is [`NO_PARTNER`].
+    fn build_pairs(&mut self) {
+        let drawn = self.rng.draws();
+        match self.config.update_mode {
+            UpdateMode::Synchronous => self.pair_cells(),
+            UpdateMode::Asynchronous => self.draw_interactions(),
         }
+        self.draws.pairing = self.rng.draws() - drawn - self.draws.orientation;
     }
 
-    /// Fill `buf` with the entire population as a flat byte slice.
-    pub fn population_bytes_into(&self, buf: &mut Vec<u8>) {
-        buf.clear();
-        let total = self.mesh.num_cells() * self.config.program_size;
-        buf.reserve(total);
-        for prog in &self.programs {
-            buf.extend_from_slice(prog);
+    /// The synchronous pairing contract: every cell appears in at most one
+    /// pair, as either member. Cells are visited in shuffled order; a cell
+    /// already taken, or whose drawn neighbor is taken, sits the epoch out
+    /// rather than drawing again.
+    fn pair_cells(&mut self) {
+        let total = self.mesh.num_cells();
+
+        for i in 0..total {
+            self.order[i] = i;
         }
-    }
-}
+        self.order.shuffle(&mut self.rng);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::bff::Bff;
+        self.taken.fill(false);
+        self.pairs.clear();
 
-    #[test]
-    fn test_icosphere_face_counts() {
-        // Sub 0 = 20, sub 1 = 80, sub 2 = 320, sub 3 = 1280
-        for (sub, expected) in [(0, 20), (1, 80), (2, 320), (3, 1280)] {
-            let mesh = SurfaceMesh::icosphere(sub).unwrap();
-            assert_eq!(
-                mesh.faces.len(),
-                expected,
-                "Icosphere sub {sub}: expected {expected} faces, got {}",
-                mesh.faces.len()
-            );
+        let self_pair_fraction = self.config.self_pair_fraction;
+        for i in 0..total {
+            let p_idx = self.order[i];
+            if self.taken[p_idx] {
+                continue;
+            }
+
+            if self_pair_fraction > 0.0 && self.rng.r#gen::<f64>() < self_pair_fraction {
+                self.taken[p_idx] = true;
+                self.pairs.push((p_idx, NO_PARTNER));
+                continue;
+            }
+
+            let (start, end) = self.mesh.neighbor_ranges[p_idx];
+            let neighbor_count = end - start;
+            if neighbor_count == 0 {
+                continue;
+            }
+
+            let n_idx = self.mesh.neighbor_indices[start + self.rng.gen_range(0..neighbor_count)];
+            // A neighbor table may list a cell as its own neighbor.
+            if n_idx == p_idx || self.taken[n_idx] {
+                continue;
+            }
+
+            self.taken[p_idx] = true;
+            self.taken[n_idx] = true;
+
+            let pair = self.orient(p_idx, n_idx);
+            self.pairs.push(pair);
         }
     }
 
-    #[test]
-    fn test_icosphere_vertices_on_unit_sphere() {
-        let mesh = SurfaceMesh::icosphere(2).unwrap();
-        for (i, v) in mesh.vertices.iter().enumerate() {
-            let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
-            assert!(
-                (len - 1.0).abs() < 1e-5,
-                "Vertex {i} has length {len}, expected 1.0"
-            );
+    /// Asynchronous interactions: as many as a synchronous epoch has pairs
+    /// at most, each between a cell drawn with replacement and a random
+    /// neighbor. [`SoupSurface::run_pairs_sequentially`] runs them in
+    /// order on the live population, so a cell drawn twice carries the
+    /// first interaction's result into the second.
+    fn draw_interactions(&mut self) {
+        let total = self.mesh.num_cells();
+        self.pairs.clear();
+        let self_pair_fraction = self.config.self_pair_fraction;
+        for _ in 0..total / 2 {
+            let p_idx = self.rng.gen_range(0..total);
+            if self_pair_fraction > 0.0 && self.rng.r#gen::<f64>() < self_pair_fraction {
+                self.pairs.push((p_idx, NO_PARTNER));
+                continue;
+            }
+            let (start, end) = self.mesh.neighbor_ranges[p_idx];
+            if start == end {
+                continue;
+            }
+            let n_idx = self.mesh.neighbor_indices[self.rng.gen_range(start..end)];
+            if n_idx == p_idx {
+                continue;
+            }
+            let pair = self.orient(p_idx, n_idx);
+            self.pairs.push(pair);
+        }
+    }
+
+    /// The execution phase of [`SoupSurface::run_epoch`] under
+    /// [`UpdateMode::Asynchronous`]: each pair in turn, written back to the
+    /// population before the next runs.
+    fn run_pairs_sequentially<S: Substrate>(&mut self) {
+        let ps = self.config.program_size;
+        let tape_size = ps * 2;
+        let step_limit = self.config.step_limit;
+        self.tape_pool.resize(tape_size, 0);
+
+        for i in 0..self.pairs.len() {
+            let (first, second) = self.pairs[i];
+            let tape = &mut self.tape_pool[..tape_size];
+            tape[..ps].copy_from_slice(&self.programs[first]);
+            if second == NO_PARTNER {
+                tape[ps..].fill(0);
+            } else {
+                tape[ps..].copy_from_slice(&self.programs[second]);
+            }
+            match self.config.interaction_mode {
+                InteractionMode::Normal => {
+                    let ensemble = self.ensemble.as_ref();
+                    let halt = Self::execute_first::<S>(ensemble, first, tape, step_limit);
+                    self.halts[first] = Some(halt);
+                }
+                InteractionMode::Battling => {
+                    S::execute_battle(tape, ps, step_limit);
+                }
+            }
+            if let SelectionMode::Tournament(fitness) = self.config.selection
+                && second != NO_PARTNER
+            {
+                fitness.select::<S>(tape, ps, &self.programs[first], &self.programs[second]);
+            }
+            self.config
+                .write_back
+                .apply_pair(tape, &self.programs, (first, second), &mut self.rng);
+            write_back(
+                &mut self.programs,
+                self.record_copies.then_some(&mut self.copy_events),
+                self.mutation_multipliers.as_deref_mut(),
+                &mut self.ages,
+                (first, second),
+                tape,
+            );
+        }
+    }
+
+    /// Order cell `p` and its neighbor `n` as `(first, second)`: a coin
+    /// flip, biased by the drift when there is one.
+    fn orient(&mut self, p: usize, n: usize) -> (usize, usize) {
+        let drawn = self.rng.draws();
+        let drift = self.config.drift;
+        let p_first = if drift.is_zero() {
+            self.rng.r#gen::<bool>()
+        } else {
+            let (from, to) = (self.mesh.cell_position(p), self.mesh.cell_position(n));
+            let upwind = drift.upwind_probability(from, to);
+            self.rng.r#gen::<f64>() < upwind
+        };
+        self.draws.orientation += self.rng.draws() - drawn;
+        if p_first { (p, n) } else { (n, p) }
+    }
+
+    /// Why each cell's program stopped the last time it ran first in a
+    /// pair; `None` for cells that haven't yet. Only
+    /// [`InteractionMode::Normal`] epochs record this.
+    pub fn halts(&self) -> &[Option<Halt>] {
+        &self.halts
+    }
+
+    /// Epochs since an interaction last changed each cell's program, or
+    /// since the soup was created. Mutation doesn't reset it.
+    pub fn ages(&self) -> &[u32] {
+        &self.ages
+    }
+
+    /// Each cell's multiple of the soup's mutation rate, when the soup has
+    /// mutators.
+    pub fn mutation_multipliers(&self) -> Option<&[f64]> {
+        self.mutation_multipliers.as_deref()
+    }
+
+    /// One Game of Life generation, reading the soup as the
+    /// `width`×`height` squares of a flat grid: square `(i, j)`, rows counted
+    /// from the bottom, is faces `2(j·width + i)` and the one after, and is
+    /// alive when byte 0 of its first face's program is odd. Every square
+    /// takes the B3/S23 rule from its 8 neighbors at once, with squares past
+    /// the grid's edge dead, and its new state, 1 or 0, goes into byte 0 of
+    /// both its faces.
+    fn run_life_epoch(&mut self, width: usize, height: usize) {
+        assert_eq!(
+            self.programs.len(),
+            2 * width * height,
+            "not a {width}x{height} grid"
+        );
+        self.pairs.clear();
+        let alive: Vec<bool> = (0..width * height)
+            .map(|square| self.programs[2 * square][0] & 1 == 1)
+            .collect();
+        for j in 0..height {
+            for i in 0..width {
+                let mut live_neighbors = 0;
+                for nj in j.saturating_sub(1)..(j + 2).min(height) {
+                    for ni in i.saturating_sub(1)..(i + 2).min(width) {
+                        if (ni, nj) != (i, j) && alive[nj * width + ni] {
+                            live_neighbors += 1;
+                        }
+                    }
+                }
+                let square = j * width + i;
+                let next = matches!((alive[square], live_neighbors), (true, 2) | (_, 3));
+                for face in [2 * square, 2 * square + 1] {
+                    if self.programs[face][0] != next as u8 {
+                        self.programs[face][0] = next as u8;
+                        self.ages[face] = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pairs the most recent `run_epoch` executed.
+    pub fn pair_count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Record copy events for [`SoupSurface::take_copy_events`] from the
+    /// next epoch on, or stop and drop any recorded. Off by default: only
+    /// the parasite metric reads them, and a run without metrics would
+    /// otherwise pay for the comparison and keep every event.
+    pub fn record_copy_events(&mut self, record: bool) {
+        self.record_copies = record;
+        if !record {
+            self.copy_events = Vec::new();
+        }
+    }
+
+    /// Copy events recorded by `run_epoch` since the last call, leaving the
+    /// record empty. Empty unless [`SoupSurface::record_copy_events`] is on.
+    pub fn take_copy_events(&mut self) -> Vec<CopyEvent> {
+        std::mem::take(&mut self.copy_events)
+    }
+
+    /// Apply background mutation to all programs.
+    ///
+    /// Uses geometric distribution to skip directly to the next mutation site,
+    /// reducing RNG calls from O(total_bytes) to O(total_bytes * mutation_rate).
+    /// Does nothing from epoch `config.no_mutation_after` on.
+    pub fn mutate(&mut self) {
+        if let Some(cutoff) = self.config.no_mutation_after
+            && self.epoch >= cutoff
+        {
+            return;
+        }
+        if self.config.rng_mode == RngMode::PerEpoch {
+            self.rng
+                .reseed(self.seed ^ self.epoch as u64 ^ MUTATE_STREAM);
+        }
+        let drawn = self.rng.draws();
+        self.apply_mutations();
+        self.rearrange();
+        self.draws.mutation = self.rng.draws() - drawn;
+    }
+
+    fn apply_mutations(&mut self) {
+        // With mutators, sites are drawn at the fastest cell's rate and each
+        // kept in proportion to its own cell's rate.
+        let top_multiplier = self
+            .mutation_multipliers
+            .as_ref()
+            .map_or(1.0, |m| m.iter().copied().fold(0.0, f64::max));
+        let top_rate = (self.mutation_rate * top_multiplier).min(1.0);
+        if top_rate <= 0.0 || self.config.life.is_some() {
+            return;
+        }
+        let total_bytes = self.programs.len() * self.config.program_size;
+        let ps = self.config.program_size;
+        let inv_log = 1.0 / (1.0 - top_rate).ln();
+
+        let mut pos = geometric_skip(&mut self.rng, inv_log);
+        while pos < total_bytes {
+            let prog_idx = pos / ps;
+            let byte_idx = pos % ps;
+            let keep = self.mutation_multipliers.as_ref().is_none_or(|m| {
+                let rate = (self.mutation_rate * m[prog_idx]).min(1.0);
+                rate >= top_rate || self.rng.r#gen::<f64>() < rate / top_rate
+            });
+            if keep {
+                let byte = &mut self.programs[prog_idx][byte_idx];
+                *byte = self.config.mutation_model.apply(*byte, &mut self.rng);
+            }
+            pos = pos
+                .saturating_add(1)
+                .saturating_add(geometric_skip(&mut self.rng, inv_log));
+        }
+    }
+
+    /// Rotate or reverse a random window of each program with probability
+    /// `config.rearrange_rate`, skipping ahead geometrically between the
+    /// programs hit like [`SoupSurface::mutate`] does between bytes.
+    fn rearrange(&mut self) {
+        let rate = self.config.rearrange_rate.min(1.0);
+        if rate <= 0.0 {
+            return;
+        }
+        let inv_log = 1.0 / (1.0 - rate).ln();
+        let total = self.programs.len();
+        let mut cell = geometric_skip(&mut self.rng, inv_log);
+        while cell < total {
+            let program = &mut self.programs[cell];
+            let len = program.len();
+            if len >= 2 {
+                let start = self.rng.gen_range(0..len - 1);
+                let end = self.rng.gen_range(start + 2..=len);
+                let window = &mut program[start..end];
+                if self.rng.r#gen::<bool>() {
+                    window.rotate_left(1);
+                } else {
+                    window.reverse();
+                }
+            }
+            cell = cell
+                .saturating_add(1)
+                .saturating_add(geometric_skip(&mut self.rng, inv_log));
+        }
+    }
+
+    /// Per-byte mutation probability currently in effect: the configured
+    /// rate, or the adaptive controller's output.
+    pub fn mutation_rate(&self) -> f64 {
+        self.mutation_rate
+    }
+
+    /// Replace the mutation rate in effect; an adaptive controller, if
+    /// configured, carries on from here.
+    pub fn set_mutation_rate(&mut self, rate: f64) {
+        self.mutation_rate = rate;
+    }
+
+    /// Refill a random `fraction` of cells with fresh random programs.
+    /// Returns how many cells were wiped.
+    pub fn extinction(&mut self, fraction: f64) -> usize {
+        let total = self.programs.len();
+        let count = ((fraction.clamp(0.0, 1.0) * total as f64).round() as usize).min(total);
+        for cell in rand::seq::index::sample(&mut self.rng, total, count) {
+            self.rng.fill(&mut self.programs[cell][..]);
+        }
+        count
+    }
+
+    /// Feed the latest diversity, unique programs as a fraction of the
+    /// programs counted, to the adaptive mutation controller, if one is
+    /// configured. Call once per metrics interval.
+    pub fn adapt_mutation(&mut self, diversity: f64) {
+        let Some(adaptive) = self.config.adaptive_mutation else {
+            return;
+        };
+        self.mutation_rate =
+            adaptive.next_rate(self.mutation_rate, self.config.mutation_rate, diversity);
+    }
+
+    /// Distribution of geodesic neighbor counts on the underlying mesh.
+    pub fn neighbor_stats(&self) -> NeighborStats {
+        self.mesh.neighbor_stats()
+    }
+
+    /// Write the underlying mesh's neighbor graph as Graphviz DOT.
+    pub fn export_graphviz(&self, path: &str) -> std::io::Result<()> {
+        self.mesh.export_graphviz(path)
+    }
+
+    /// Fill `buf` with the entire population as a flat byte slice.
+    ///
+    /// Every program must be exactly `program_size` bytes long, so that
+    /// program `i` occupies `buf[i * program_size..(i + 1) * program_size]`;
+    /// the metrics that read `buf` rely on that layout. Debug builds assert
+    /// it; use [`SoupSurface::population_bytes_checked`] to get an error
+    /// instead.
+    pub fn population_bytes_into(&self, buf: &mut Vec<u8>) {
+        debug_assert!(
+            self.programs
+                .iter()
+                .all(|p| p.len() == self.config.program_size),
+            "population_bytes_into: program length differs from program size {}",
+            self.config.program_size
+        );
+        buf.clear();
+        let total = self.mesh.num_cells() * self.config.program_size;
+        buf.reserve(total);
+        for prog in &self.programs {
+            buf.extend_from_slice(prog);
+        }
+    }
+
+    /// [`SoupSurface::population_bytes_into`], but returns an error naming
+    /// the first program whose length isn't `program_size` instead of
+    /// producing a misaligned buffer.
+    pub fn population_bytes_checked(&self, buf: &mut Vec<u8>) -> Result<(), String> {
+        let size = self.config.program_size;
+        let mut programs = self.programs.iter().enumerate();
+        if let Some((i, p)) = programs.find(|(_, p)| p.len() != size) {
+            return Err(format!(
+                "Program {i} is {} bytes long, expected program size {size}",
+                p.len()
+            ));
+        }
+        self.population_bytes_into(buf);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bff::Bff;
+    use crate::forth::Forth;
+
+    #[test]
+    fn test_icosphere_face_counts() {
+        // Sub 0 = 20, sub 1 = 80, sub 2 = 320, sub 3 = 1280
+        for (sub, expected) in [(0, 20), (1, 80), (2, 320), (3, 1280)] {
+            let mesh = SurfaceMesh::icosphere(sub).unwrap();
+            assert_eq!(
+                mesh.faces.len(),
+                expected,
+                "Icosphere sub {sub}: expected {expected} faces, got {}",
+                mesh.faces.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_icosphere_vertices_on_unit_sphere() {
+        let mesh = SurfaceMesh::icosphere(2).unwrap();
+        for (i, v) in mesh.vertices.iter().enumerate() {
+            let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            assert!(
+                (len - 1.0).abs() < 1e-5,
+                "Vertex {i} has length {len}, expected 1.0"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cube_sphere_closed_across_seams() {
+        for n in [1, 2, 5] {
+            let mesh = SurfaceMesh::cube_sphere(n).unwrap();
+            assert_eq!(mesh.faces.len(), 12 * n * n);
+            let spec = SurfaceSpec::CubeSphere { subdivisions: n };
+            assert_eq!(spec.face_count(), Some(12 * n * n));
+            // A closed genus-0 mesh: V - E + F = 2 with E = 3F / 2.
+            assert_eq!(mesh.vertices.len(), 6 * n * n + 2);
+            for (i, adj) in mesh.face_adjacency.iter().enumerate() {
+                assert_eq!(adj.len(), 3, "Face {i} has {} adjacent faces", adj.len());
+                for &j in adj {
+                    assert!(mesh.face_adjacency[j].contains(&i));
+                }
+            }
+            for (face, centroid) in mesh.faces.iter().zip(&mesh.face_centroids) {
+                let [a, b, c] = face.map(|v| mesh.vertices[v]);
+                let normal = face_normal(&a, &b, &c);
+                let outward: f32 = (0..3).map(|k| normal[k] * centroid[k]).sum();
+                assert!(outward > 0.0, "face {face:?} winds inward");
+            }
+        }
+        assert!(SurfaceMesh::cube_sphere(0).is_err());
+        assert_eq!(
+            SurfaceSpec::parse("cubesphere:8", 0),
+            Ok(SurfaceSpec::CubeSphere { subdivisions: 8 })
+        );
+    }
+
+    #[test]
+    fn test_torus_face_count() {
+        let mesh = SurfaceMesh::torus(10, 5).unwrap();
+        assert_eq!(mesh.faces.len(), 2 * 10 * 5);
+    }
+
+    #[test]
+    fn test_parametric_metric_evens_out_torus_neighbor_counts() {
+        let spread = |metric| {
+            let mut mesh = SurfaceMesh::torus(24, 12).unwrap();
+            mesh.set_geodesic_metric(metric).unwrap();
+            mesh.compute_neighbors(Some(0.5), None);
+            let counts: Vec<usize> = (0..mesh.num_cells())
+                .map(|i| mesh.neighbors_of(i).len())
+                .collect();
+            counts.iter().max().unwrap() - counts.iter().min().unwrap()
+        };
+        let euclidean = spread(GeodesicMetric::Euclidean);
+        let parametric = spread(GeodesicMetric::Parametric);
+        assert!(
+            parametric < euclidean,
+            "parametric spread {parametric} vs euclidean {euclidean}"
+        );
+    }
+
+    #[test]
+    fn test_parametric_metric_needs_a_parametrization() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        let result = mesh.set_geodesic_metric(GeodesicMetric::Parametric);
+        assert!(result.is_err());
+        assert!(mesh.set_geodesic_metric(GeodesicMetric::Euclidean).is_ok());
+        assert!(GeodesicMetric::parse("taxicab").is_err());
+    }
+
+    #[test]
+    fn test_random_regular_graph_is_regular_and_symmetric() {
+        let graph = InteractionGraph::parse("regular:4:200", 9).unwrap();
+        let mesh = graph.build().unwrap();
+        assert_eq!(mesh.num_cells(), 200);
+        for i in 0..mesh.num_cells() {
+            let neighbors = mesh.neighbors_of(i);
+            assert_eq!(neighbors.len(), 4, "cell {i}");
+            assert!(!neighbors.contains(&i), "cell {i} is its own neighbor");
+            for &j in neighbors {
+                assert!(mesh.neighbors_of(j).contains(&i), "{i} -> {j} is one-way");
+            }
+            let mut unique = neighbors.to_vec();
+            unique.dedup();
+            assert_eq!(unique.len(), 4, "cell {i} has a repeated neighbor");
+        }
+        let rebuilt = graph.build().unwrap();
+        assert_eq!(rebuilt.neighbor_indices, mesh.neighbor_indices);
+    }
+
+    #[test]
+    fn test_interaction_graph_parse_errors() {
+        assert!(InteractionGraph::parse("regular:4", 0).is_err());
+        assert!(InteractionGraph::parse("ring:4:100", 0).is_err());
+        let build = |s: &str| InteractionGraph::parse(s, 0).unwrap().build();
+        assert!(build("regular:4:101").is_err());
+        assert!(build("regular:3:3").is_err());
+    }
+
+    #[test]
+    fn test_flat_grid_face_count() {
+        let mesh = SurfaceMesh::flat_grid(10, 8).unwrap();
+        assert_eq!(mesh.faces.len(), 2 * 10 * 8);
+    }
+
+    #[test]
+    fn test_adjacency_symmetric() {
+        let mesh = SurfaceMesh::icosphere(1).unwrap();
+        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
+            for &j in adj {
+                assert!(
+                    mesh.face_adjacency[j].contains(&i),
+                    "Face {i} adjacent to {j}, but not vice versa"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_icosphere_faces_have_3_adjacent() {
+        // Every face on a closed icosphere should have exactly 3 adjacent faces.
+        let mesh = SurfaceMesh::icosphere(2).unwrap();
+        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
+            assert_eq!(
+                adj.len(),
+                3,
+                "Face {i} has {} adjacent faces, expected 3",
+                adj.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_torus_faces_have_3_adjacent() {
+        let mesh = SurfaceMesh::torus(8, 5).unwrap();
+        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
+            assert_eq!(
+                adj.len(),
+                3,
+                "Face {i} has {} adjacent faces, expected 3",
+                adj.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_geodesic_neighbors_exclude_self() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        for i in 0..mesh.num_cells() {
+            let (start, end) = mesh.neighbor_ranges[i];
+            let neighbors = &mesh.neighbor_indices[start..end];
+            assert!(
+                !neighbors.contains(&i),
+                "Face {i} has itself as a geodesic neighbor"
+            );
+        }
+    }
+
+    #[test]
+    fn test_geodesic_neighbors_nonempty() {
+        let mut mesh = SurfaceMesh::icosphere(2).unwrap();
+        mesh.compute_neighbors(None, None);
+        for i in 0..mesh.num_cells() {
+            let (start, end) = mesh.neighbor_ranges[i];
+            assert!(end > start, "Face {i} has no geodesic neighbors");
+        }
+    }
+
+    #[test]
+    fn test_compute_neighbors_quiet() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        let _lock = log::test_lock();
+        let lines = std::rc::Rc::new(std::cell::RefCell::new(Vec::<String>::new()));
+        let sink = std::rc::Rc::clone(&lines);
+        log::with_logger(
+            move |line| sink.borrow_mut().push(line.to_string()),
+            || {
+                log::set_verbosity(log::Verbosity::Quiet);
+                mesh.compute_neighbors(None, None);
+                log::set_verbosity(log::Verbosity::Normal);
+            },
+        );
+        assert!(lines.borrow().is_empty());
+        assert!(mesh.neighbor_stats().min >= 1);
+    }
+
+    #[test]
+    fn test_neighbor_stats() {
+        let mut mesh = SurfaceSpec::parse("sphere:2", 0).unwrap().build().unwrap();
+        mesh.compute_neighbors(None, None);
+        let stats = mesh.neighbor_stats();
+        assert!(stats.min >= 1);
+        assert!(stats.min <= stats.median && stats.median <= stats.p95 && stats.p95 <= stats.max);
+        let total = mesh.neighbor_indices.len() as f64;
+        assert!((stats.mean - total / mesh.num_cells() as f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_auto_radius_targets_mean_neighbors() {
+        let mut mesh = SurfaceSpec::parse("sphere:3", 0).unwrap().build().unwrap();
+        let radius = NeighborRadius::parse("auto:6").unwrap().resolve(&mesh);
+        mesh.compute_neighbors(Some(radius), None);
+        let mean = mesh.neighbor_stats().mean;
+        assert!((mean - 6.0).abs() < 1.5, "mean neighbors {mean}");
+    }
+
+    #[test]
+    fn test_neighbor_radius_parse() {
+        assert_eq!(
+            NeighborRadius::parse("0.25").unwrap(),
+            NeighborRadius::Fixed(0.25)
+        );
+        assert_eq!(
+            NeighborRadius::parse("auto:8").unwrap(),
+            NeighborRadius::MeanNeighbors {
+                mean_neighbors: 8.0
+            }
+        );
+        assert!(NeighborRadius::parse("auto:0").is_err());
+        assert!(NeighborRadius::parse("auto").is_err());
+    }
+
+    #[test]
+    fn test_max_neighbors_is_deterministic() {
+        // Every torus face has many equidistant candidates at the cutoff.
+        let build = || {
+            let mut mesh = SurfaceMesh::torus(8, 4).unwrap();
+            mesh.compute_neighbors(None, Some(6));
+            mesh.neighbor_indices
+        };
+        let first = build();
+        for _ in 0..8 {
+            assert_eq!(build(), first);
+        }
+    }
+
+    #[test]
+    fn test_compute_neighbors_refreshes_moved_geometry() {
+        let mut mesh = SurfaceMesh::flat_grid(6, 6).unwrap();
+        mesh.compute_neighbors(None, None);
+        for v in mesh.vertices_mut() {
+            v[0] *= 2.0;
+        }
+        assert!(mesh.is_geometry_dirty());
+        mesh.compute_neighbors(None, None);
+        assert!(!mesh.is_geometry_dirty());
+        let fresh = compute_face_centroids(&mesh.vertices, &mesh.faces);
+        assert_eq!(mesh.face_centroids, fresh);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "call compute_neighbors")]
+    fn test_run_epoch_on_moved_geometry_panics() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        let mut soup = SoupSurface::new(mesh, SoupSurfaceConfig::default(), 1);
+        soup.mesh.vertices_mut()[0][0] += 0.5;
+        // Centroids alone don't fix a neighbor table built on the old shape.
+        soup.mesh.recompute_centroids();
+        soup.run_epoch::<crate::bff::Bff>();
+    }
+
+    #[test]
+    fn test_geodesic_patch_grows_with_radius() {
+        let mesh = SurfaceMesh::icosphere(2).unwrap();
+        assert_eq!(mesh.geodesic_patch(7, 0.0), [7]);
+        let small = mesh.geodesic_patch(7, 0.3);
+        let large = mesh.geodesic_patch(7, 0.6);
+        assert!(small.contains(&7) && small.len() > 1);
+        assert!(small.iter().all(|f| large.contains(f)));
+        assert!(large.len() > small.len());
+        assert_eq!(mesh.geodesic_patch(7, 10.0).len(), mesh.num_cells());
+    }
+
+    #[test]
+    fn test_neighbors_of_matches_table() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        assert!(mesh.neighbors_of(0).is_empty());
+        mesh.compute_neighbors(None, None);
+        for i in 0..mesh.num_cells() {
+            let (start, end) = mesh.neighbor_ranges[i];
+            assert_eq!(mesh.neighbors_of(i), &mesh.neighbor_indices[start..end]);
+            assert!(!mesh.neighbors_of(i).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_vertex_cells_follow_mesh_edges() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.set_cell_site(CellSite::Vertex).unwrap();
+        assert_eq!(mesh.num_cells(), mesh.vertices.len());
+        assert_eq!(mesh.num_cells(), 42);
+
+        // The icosahedron's 12 corners keep degree 5; the 30 edge
+        // midpoints added by subdivision have degree 6.
+        let degrees: Vec<usize> = mesh.dual_graph().iter().map(Vec::len).collect();
+        assert_eq!(degrees.iter().filter(|&&d| d == 5).count(), 12);
+        assert_eq!(degrees.iter().filter(|&&d| d == 6).count(), 30);
+
+        // A radius just past the longest edge reaches exactly the
+        // edge-adjacent vertices.
+        let longest = (0..mesh.num_cells())
+            .flat_map(|v| mesh.cell_adjacency()[v].iter().map(move |&w| (v, w)))
+            .map(|(v, w)| centroid_distance(&mesh.vertices[v], &mesh.vertices[w]))
+            .fold(0.0, f32::max);
+        mesh.compute_neighbors(Some(longest * 1.01), None);
+        for v in 0..mesh.num_cells() {
+            assert_eq!(mesh.neighbors_of(v), &mesh.dual_graph()[v][..]);
+        }
+
+        let mut soup = SoupSurface::new(mesh, SoupSurfaceConfig::default(), 1);
+        soup.run_epoch::<Bff>();
+        assert_eq!(soup.programs.len(), 42);
+    }
+
+    #[test]
+    fn test_vertex_cells_reject_parametric_metric() {
+        use GeodesicMetric::{Euclidean, Parametric};
+        let mut mesh = SurfaceMesh::torus(8, 4).unwrap();
+        mesh.set_geodesic_metric(Parametric).unwrap();
+        assert!(mesh.set_cell_site(CellSite::Vertex).is_err());
+        mesh.set_geodesic_metric(Euclidean).unwrap();
+        mesh.set_cell_site(CellSite::Vertex).unwrap();
+        assert!(mesh.set_geodesic_metric(Parametric).is_err());
+        assert_eq!(CellSite::parse("vertex"), Ok(CellSite::Vertex));
+        assert!(CellSite::parse("edge").is_err());
+    }
+
+    #[test]
+    fn test_max_neighbors_keeps_nearest() {
+        let mut mesh = SurfaceMesh::icosphere(2).unwrap();
+        let radius = 1.0;
+        mesh.compute_neighbors(Some(radius), Some(6));
+        for i in 0..mesh.num_cells() {
+            let (start, end) = mesh.neighbor_ranges[i];
+            let kept = &mesh.neighbor_indices[start..end];
+            assert!(kept.len() <= 6, "Face {i} has {} neighbors", kept.len());
+            assert!(!kept.contains(&i), "Face {i} has itself as a neighbor");
+
+            let all = mesh.geodesic_nearest(i, radius, None);
+            assert!(all.len() > 6, "radius too small to exercise the cap");
+            let dist_of = |f: usize| all.iter().find(|(g, _)| *g == f).unwrap().1;
+            let worst_kept = kept.iter().map(|&f| dist_of(f)).fold(0.0, f32::max);
+            for &(f, d) in &all {
+                if !kept.contains(&f) {
+                    assert!(
+                        d >= worst_kept,
+                        "Face {i} dropped {f} (dist {d}) but kept one at {worst_kept}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic_surface_simulation() {
+        let run = |seed: u64| -> Vec<Vec<u8>> {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                mutation_rate: 0.001,
+                interaction_mode: InteractionMode::Normal,
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, seed);
+            for _ in 0..10 {
+                soup.run_epoch::<Bff>();
+                soup.mutate();
+            }
+            soup.programs
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(99));
+    }
+
+    #[test]
+    fn test_obj_loader_cube() {
+        let obj = "\
+v 0.0 0.0 0.0
+v 1.0 0.0 0.0
+v 1.0 1.0 0.0
+v 0.0 1.0 0.0
+v 0.0 0.0 1.0
+v 1.0 0.0 1.0
+v 1.0 1.0 1.0
+v 0.0 1.0 1.0
+f 1 2 3 4
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+        let dir = std::env::temp_dir();
+        let path = dir.join("test_cube.obj");
+        std::fs::write(&path, obj).unwrap();
+        let mesh = SurfaceMesh::from_obj(path.to_str().unwrap()).unwrap();
+        // 6 quads → 12 triangles
+        assert_eq!(mesh.faces.len(), 12);
+        assert_eq!(mesh.vertices.len(), 8);
+    }
+
+    #[test]
+    fn test_obj_negative_indices() {
+        // A tetrahedron whose first face comes before its last vertex.
+        let obj = "\
+v 0.0 0.0 0.0
+v 1.0 0.0 0.0
+v 0.0 1.0 0.0
+f -1 -2 -3
+v 0.0 0.0 1.0
+f 1 2 -1
+f 2 3 -1
+f 3 1 -1
+";
+        let path = std::env::temp_dir().join("complife_test_negative.obj");
+        std::fs::write(&path, obj).unwrap();
+        let mesh = SurfaceMesh::from_obj(path.to_str().unwrap()).unwrap();
+        assert_eq!(mesh.faces, [[2, 1, 0], [0, 1, 3], [1, 2, 3], [2, 0, 3]]);
+
+        for (face, expected) in [("f 0 1 2", "index 0"), ("f -5 -2 -1", "before the first")] {
+            std::fs::write(&path, format!("{obj}{face}\n")).unwrap();
+            let Err(SurfaceError::ParseError { line, msg }) =
+                SurfaceMesh::from_obj(path.to_str().unwrap())
+            else {
+                panic!("{face} should fail to parse");
+            };
+            assert_eq!(line, 9);
+            assert!(msg.contains(expected), "{msg}");
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_obj_normalize_centers_and_scales() {
+        // A 1000x10x10 box far from the origin.
+        let obj = "\
+v 5000 -300 70
+v 6000 -300 70
+v 6000 -290 70
+v 5000 -290 70
+v 5000 -300 80
+v 6000 -300 80
+v 6000 -290 80
+v 5000 -290 80
+f 1 2 3 4
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+        let path = std::env::temp_dir().join("complife_test_far_box.obj");
+        std::fs::write(&path, obj).unwrap();
+        let spec = SurfaceSpec::ObjFile {
+            path: path.to_str().unwrap().to_string(),
+            normalize: true,
+            drop_degenerate: false,
+        };
+        let mut mesh = spec.build().unwrap();
+        let (center, radius) = mesh.bounding_sphere();
+        assert!(center.iter().all(|c| c.abs() < 1e-3), "center {center:?}");
+        assert!((radius - 1.0).abs() < 1e-3, "radius {radius}");
+        assert!(!mesh.is_geometry_dirty());
+        assert!(mesh.face_centroids.iter().all(|c| c[0].abs() <= 1.0));
+        mesh.compute_neighbors(None, None);
+        assert!(mesh.neighbor_stats().mean > 0.0);
+    }
+
+    #[test]
+    fn test_export_graphviz_icosahedron() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let total_neighbors: usize = mesh.neighbor_ranges.iter().map(|(s, e)| e - s).sum();
+        let path = std::env::temp_dir().join("complife_test_icosahedron.dot");
+        let path = path.to_str().unwrap();
+        mesh.export_graphviz(path).unwrap();
+        let dot = std::fs::read_to_string(path).unwrap();
+
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"graph neighbors {"));
+        assert_eq!(lines.last(), Some(&"}"));
+        let is_node = |l: &&&str| l.ends_with(';') && !l.contains("--");
+        assert_eq!(lines.iter().filter(is_node).count(), 20);
+        // Neighbor relations are symmetric, so each edge was listed twice.
+        let edges = lines.iter().filter(|l| l.contains("--")).count();
+        assert_eq!(edges * 2, total_neighbors);
+    }
+
+    #[test]
+    fn test_export_graphviz_size_limit() {
+        let mesh = SurfaceMesh::flat_grid(60, 60).unwrap();
+        assert!(mesh.num_cells() > MAX_GRAPHVIZ_CELLS);
+        let path = std::env::temp_dir().join("complife_test_too_big.dot");
+        let err = mesh.export_graphviz(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_validate_closed_cube() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/obj/cube.obj");
+        let report = SurfaceMesh::from_obj(path).unwrap().validate();
+        assert_eq!(
+            report,
+            MeshReport {
+                faces: 12,
+                degenerate_faces: 0,
+                duplicate_faces: 0,
+                boundary_edges: 0,
+                components: 1,
+                irregular_faces: 0,
+            }
+        );
+        assert!(report.all_faces_have_three_neighbors());
+    }
+
+    #[test]
+    fn test_validate_degenerate_triangle() {
+        // Collinear vertices plus a second copy of the same face elsewhere.
+        let vertices = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [0.0, 5.0, 0.0],
+            [1.0, 5.0, 0.0],
+            [0.0, 6.0, 0.0],
+        ];
+        let faces = vec![[0, 1, 2], [3, 4, 5], [5, 4, 3]];
+        let report = SurfaceMesh::from_geometry(vertices, faces)
+            .unwrap()
+            .validate();
+        assert_eq!(report.degenerate_faces, 1);
+        assert_eq!(report.duplicate_faces, 1);
+        assert_eq!(report.boundary_edges, 3);
+        assert_eq!(report.components, 2);
+        assert!(!report.all_faces_have_three_neighbors());
+    }
+
+    #[test]
+    fn test_drop_degenerate_in_from_geometry() {
+        // A collinear sliver between two good triangles: dropping it must
+        // renumber the survivors so adjacency stays in range.
+        let vertices = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ];
+        let faces = vec![[0, 1, 2], [0, 1, 3], [1, 4, 2]];
+        let (mesh, dropped) =
+            SurfaceMesh::from_geometry_without_degenerate(vertices.clone(), faces.clone()).unwrap();
+        assert_eq!(dropped, 1);
+        assert_eq!(mesh.num_cells(), 2);
+        assert_eq!(mesh.degenerate_face_count(), 0);
+        for adj in &mesh.face_adjacency {
+            assert!(adj.iter().all(|&f| f < mesh.num_cells()));
+        }
+        let kept = SurfaceMesh::from_geometry(vertices, faces).unwrap();
+        assert_eq!(kept.degenerate_face_count(), 1);
+    }
+
+    #[test]
+    fn test_surface_error_kinds() {
+        // Three triangles hinged on the edge between vertices 0 and 1.
+        let vertices = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let faces = vec![[0, 1, 2], [1, 0, 3], [0, 1, 4]];
+        let Err(err) = SurfaceMesh::from_geometry(vertices, faces) else {
+            panic!("a non-manifold mesh should not build");
+        };
+        assert!(matches!(
+            err,
+            SurfaceError::NonManifold {
+                edge: (0, 1),
+                count: 3
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Non-manifold edge (0, 1): shared by 3 faces"
+        );
+
+        assert!(matches!(
+            SurfaceMesh::flat_grid(0, 4),
+            Err(SurfaceError::InvalidParams(_))
+        ));
+        let missing = std::env::temp_dir().join("complife_test_missing.obj");
+        assert!(matches!(
+            SurfaceMesh::from_obj(missing.to_str().unwrap()),
+            Err(SurfaceError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_drop_degenerate_obj_face() {
+        // The cube plus a collinear sliver along its edge 1-2, which also
+        // makes that edge non-manifold while it is kept.
+        let cube = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/obj/cube.obj"
+        ))
+        .unwrap();
+        let path = std::env::temp_dir().join("complife_test_sliver.obj");
+        std::fs::write(&path, format!("{cube}v 1.0 1.0 0.0\nf 1 9 2\n")).unwrap();
+        let path = path.to_str().unwrap();
+
+        assert!(SurfaceMesh::from_obj(path).is_err());
+        let (mesh, dropped) = SurfaceMesh::from_obj_without_degenerate(path).unwrap();
+        assert_eq!(dropped, 1);
+        assert_eq!(mesh.num_cells(), 12);
+        assert_eq!(mesh.face_centroids.len(), 12);
+        let report = mesh.validate();
+        assert_eq!(report.degenerate_faces, 0);
+        assert!(report.all_faces_have_three_neighbors());
+        for (face, adjacent) in mesh.face_adjacency.iter().enumerate() {
+            for &other in adjacent {
+                assert!(other < 12);
+                assert!(mesh.face_adjacency[other].contains(&face));
+            }
+        }
+
+        let spec = SurfaceSpec::ObjFile {
+            path: path.to_string(),
+            normalize: false,
+            drop_degenerate: true,
+        };
+        let mut mesh = spec.build().unwrap();
+        mesh.compute_neighbors(None, None);
+        assert!(mesh.neighbor_indices.iter().all(|&n| n < 12));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_mutation_disabled_surface() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 256,
+            mutation_rate: 0.0,
+            interaction_mode: InteractionMode::Normal,
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 42);
+        let before = soup.programs.clone();
+        soup.mutate();
+        assert_eq!(soup.programs, before);
+    }
+
+    #[test]
+    fn test_full_self_pairing_isolates_programs() {
+        let copies_after = |self_pair_fraction: f64| -> usize {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 64,
+                step_limit: 256,
+                mutation_rate: 0.0,
+                self_pair_fraction,
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            for prog in soup.programs.iter_mut() {
+                prog.fill(0);
+            }
+            // Forth's one-byte replicator copies itself into its partner.
+            soup.programs[0][0] = 0x0C;
+            let mut most = 0;
+            for _ in 0..20 {
+                soup.run_epoch::<Forth>();
+                if self_pair_fraction == 1.0 {
+                    assert!(soup.pairs.iter().all(|&(_, partner)| partner == NO_PARTNER));
+                }
+                let copies = soup.programs.iter().filter(|p| p[0] == 0x0C).count();
+                most = most.max(copies);
+            }
+            most
+        };
+        // A zero program run first can overwrite the replicator, so look at
+        // the peak: it spreads only when it meets real neighbors.
+        assert!(copies_after(0.0) > 1);
+        assert_eq!(copies_after(1.0), 1);
+    }
+
+    #[test]
+    fn test_life_glider_moves_one_square_diagonally_in_four_epochs() {
+        let (width, height) = (8, 8);
+        let mut mesh = SurfaceMesh::flat_grid(width, height).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            life: Some((width, height)),
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 42);
+        for program in &mut soup.programs {
+            program[0] = 0;
+        }
+        // A glider heading right and down, as `(column, row)` from the top.
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let square = |(i, row): (usize, usize)| (height - 1 - row) * width + i;
+        for &cell in &glider {
+            soup.programs[2 * square(cell)][0] = 1;
+        }
+        let before = soup.programs.clone();
+        for _ in 0..4 {
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+        }
+        let live: Vec<usize> = (0..width * height)
+            .filter(|&sq| soup.programs[2 * sq][0] == 1)
+            .collect();
+        let mut moved: Vec<usize> = glider.map(|(i, row)| square((i + 1, row + 1))).to_vec();
+        moved.sort();
+        assert_eq!(live, moved);
+        assert!(
+            (0..width * height).all(|sq| soup.programs[2 * sq][0] == soup.programs[2 * sq + 1][0])
+        );
+        // Nothing mutated the rest of any program.
+        for (program, before) in soup.programs.iter().zip(&before) {
+            assert_eq!(program[1..], before[1..]);
+        }
+        assert_eq!(soup.epoch(), 4);
+    }
+
+    #[test]
+    fn test_tournament_winner_records_copy_event() {
+        let new_soup = |record: bool| {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                mutation_rate: 0.0,
+                selection: SelectionMode::Tournament(Fitness::InstructionCount),
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            soup.record_copy_events(record);
+            for prog in soup.programs.iter_mut() {
+                prog.fill(0);
+            }
+            soup.programs[0] = b"<><><><><><><><>".to_vec();
+            soup
+        };
+        // The winner of a tournament overwrites its partner: a copy event
+        // from the first cell to receive the program.
+        let mut soup = new_soup(true);
+        let mut events = Vec::new();
+        for _ in 0..20 {
+            soup.run_epoch::<Bff>();
+            events = soup.take_copy_events();
+            if !events.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].source, 0);
+        assert_eq!(soup.programs[events[0].dest], soup.programs[0]);
+        assert!(soup.take_copy_events().is_empty());
+
+        // Unless asked for, the same copies go unrecorded.
+        let mut unrecorded = new_soup(false);
+        for _ in 0..20 {
+            unrecorded.run_epoch::<Bff>();
+        }
+        assert!(unrecorded.take_copy_events().is_empty());
+    }
+
+    #[test]
+    fn test_adaptive_mutation_rises_then_relaxes() {
+        let adaptive = AdaptiveMutation {
+            target: 0.5,
+            max_rate: 0.01,
+        };
+        let baseline = 0.001;
+        let mut rate = baseline;
+        let mut rates = Vec::new();
+        for diversity in [0.1, 0.1, 0.1, 0.1, 0.9, 0.9, 0.9, 0.9] {
+            rate = adaptive.next_rate(rate, baseline, diversity);
+            rates.push(rate);
+        }
+        assert_eq!(&rates[..4], &[0.002, 0.004, 0.008, 0.01]);
+        assert!(rates[4..].windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(rates[7], baseline);
+    }
+
+    #[test]
+    fn test_adapt_mutation_uses_population_diversity() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            mutation_rate: 0.001,
+            adaptive_mutation: Some(AdaptiveMutation::default()),
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 42);
+        soup.adapt_mutation(1.0 / soup.programs.len() as f64);
+        assert_eq!(soup.mutation_rate(), 0.002);
+        soup.adapt_mutation(1.0);
+        assert_eq!(soup.mutation_rate(), 0.001);
+    }
+
+    #[test]
+    fn test_drift_orients_aggressors_downwind() {
+        let downwind_fraction = |drift: Drift| {
+            let mut mesh = SurfaceMesh::icosphere(2).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                step_limit: 16,
+                drift,
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            soup.run_epoch::<Bff>();
+            let centroids = &soup.mesh.face_centroids;
+            let downwind = soup
+                .pairs
+                .iter()
+                .filter(|&&(first, second)| centroids[second][0] > centroids[first][0])
+                .count();
+            downwind as f64 / soup.pairs.len() as f64
+        };
+        assert!(downwind_fraction(Drift([50.0, 0.0, 0.0])) > 0.9);
+        assert!(downwind_fraction(Drift([-50.0, 0.0, 0.0])) < 0.1);
+        let unbiased = downwind_fraction(Drift::default());
+        assert!((0.35..0.65).contains(&unbiased), "{unbiased}");
+    }
+
+    #[test]
+    fn test_drift_parse() {
+        assert_eq!(Drift::parse("1,0,-2.5").unwrap(), Drift([1.0, 0.0, -2.5]));
+        assert!(Drift::parse("1,0").is_err());
+        assert!(Drift::parse("1,x,0").is_err());
+        assert!(Drift::parse("0,0,0").unwrap().is_zero());
+    }
+
+    fn soup_with_update(update_mode: UpdateMode) -> SoupSurface {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 256,
+            mutation_rate: 0.01,
+            self_pair_fraction: 0.1,
+            update_mode,
+            ..SoupSurfaceConfig::default()
+        };
+        SoupSurface::new(mesh, config, 42)
+    }
+
+    /// Run epochs of `soup` and check that no pair puts a cell on both
+    /// sides.
+    fn assert_never_paired_with_itself(soup: &mut SoupSurface, epochs: usize) {
+        for _ in 0..epochs {
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+            for &(first, second) in &soup.pairs {
+                assert_ne!(first, second, "cell {first} paired with itself");
+            }
+        }
+    }
+
+    /// Run synchronous epochs of `soup` and check that no cell is in two of
+    /// any epoch's pairs, nor on both sides of any pair.
+    fn assert_sync_cells_paired_at_most_once(soup: &mut SoupSurface, epochs: usize) {
+        assert_eq!(soup.config.update_mode, UpdateMode::Synchronous);
+        for _ in 0..epochs {
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+            let mut seen = vec![false; soup.programs.len()];
+            for &(first, second) in &soup.pairs {
+                assert_ne!(first, second, "cell {first} paired with itself");
+                for cell in [first, second] {
+                    if cell != NO_PARTNER {
+                        assert!(!seen[cell], "cell {cell} paired twice in one epoch");
+                        seen[cell] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sync_cells_paired_at_most_once() {
+        let mut soup = soup_with_update(UpdateMode::Synchronous);
+        assert_sync_cells_paired_at_most_once(&mut soup, 10);
+    }
+
+    #[test]
+    fn test_async_interactions_drawn_with_replacement() {
+        let mut soup = soup_with_update(UpdateMode::Asynchronous);
+        let half = soup.programs.len() / 2;
+        let mut repeated = false;
+        for _ in 0..10 {
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+            assert!(
+                soup.pairs.len() <= half,
+                "more interactions than sync pairs"
+            );
+            let mut seen = vec![false; soup.programs.len()];
+            for &(first, second) in &soup.pairs {
+                assert_ne!(first, second, "cell {first} paired with itself");
+                for cell in [first, second] {
+                    if cell != NO_PARTNER {
+                        repeated |= std::mem::replace(&mut seen[cell], true);
+                    }
+                }
+            }
+        }
+        assert!(repeated, "no cell interacted twice in an async epoch");
+    }
+
+    #[test]
+    fn test_self_loop_neighbor_is_not_paired_with_itself() {
+        for mode in [UpdateMode::Synchronous, UpdateMode::Asynchronous] {
+            let mut soup = soup_with_update(mode);
+            // Every cell lists itself first, and next to only one other cell.
+            let n = soup.programs.len();
+            let neighbors: Vec<Vec<usize>> = (0..n).map(|i| vec![i, i ^ 1]).collect();
+            soup.mesh.set_neighbors(&neighbors);
+            match mode {
+                UpdateMode::Synchronous => assert_sync_cells_paired_at_most_once(&mut soup, 10),
+                UpdateMode::Asynchronous => assert_never_paired_with_itself(&mut soup, 10),
+            }
+        }
+    }
+
+    #[test]
+    fn test_watch_log_records_pairing() {
+        let mut mesh = SurfaceMesh::flat_grid(1, 1).unwrap();
+        mesh.set_neighbors(&[vec![1], vec![0]]);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 256,
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 5);
+        soup.set_watch(Some(0));
+        let mut before = soup.programs[0].clone();
+        for epoch in 1..=3 {
+            soup.run_epoch::<Bff>();
+            let log = soup.take_watch_log();
+            assert_eq!(log.len(), 1);
+            let event = log[0];
+            assert_eq!((event.epoch, event.cell), (epoch, 0));
+            assert!(matches!(
+                event.role,
+                WatchRole::Aggressor { partner: 1 } | WatchRole::Target { partner: 1 }
+            ));
+            let changed = soup.programs[0].iter().zip(&before).filter(|(a, b)| a != b);
+            assert_eq!(event.changed_bytes, changed.count());
+            before.clone_from(&soup.programs[0]);
         }
+
+        soup.set_watch(None);
+        soup.run_epoch::<Bff>();
+        assert!(soup.take_watch_log().is_empty());
     }
 
     #[test]
-    fn test_torus_face_count() {
-        let mesh = SurfaceMesh::torus(10, 5).unwrap();
-        assert_eq!(mesh.faces.len(), 2 * 10 * 5);
+    fn test_async_and_sync_updates_diverge() {
+        let run = |mode| {
+            let mut soup = soup_with_update(mode);
+            for _ in 0..10 {
+                soup.run_epoch::<Bff>();
+                soup.mutate();
+            }
+            soup
+        };
+        let async_ = run(UpdateMode::Asynchronous);
+        assert_eq!(async_.programs, run(UpdateMode::Asynchronous).programs);
+        // Same seed, same initial soup: the update order alone changes the
+        // trajectory, since async draws cells with replacement and each
+        // interaction sees the ones before it.
+        assert_ne!(async_.programs, run(UpdateMode::Synchronous).programs);
+        let mut seen = vec![false; async_.programs.len()];
+        let revisits = async_
+            .pairs
+            .iter()
+            .flat_map(|&(a, b)| [a, b])
+            .filter(|&cell| cell != NO_PARTNER && std::mem::replace(&mut seen[cell], true))
+            .count();
+        assert!(revisits > 0);
     }
 
     #[test]
-    fn test_flat_grid_face_count() {
-        let mesh = SurfaceMesh::flat_grid(10, 8).unwrap();
-        assert_eq!(mesh.faces.len(), 2 * 10 * 8);
+    fn test_ensemble_assignment_matches_weights() {
+        let fresh = || {
+            let mut mesh = SurfaceMesh::icosphere(3).unwrap();
+            mesh.compute_neighbors(None, Some(8));
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                ..SoupSurfaceConfig::default()
+            };
+            SoupSurface::new(mesh, config, 42)
+        };
+        let mut soup = fresh();
+        let executors: Vec<ExecuteFn> = vec![Bff::execute_status, Forth::execute_status];
+        soup.set_ensemble(executors, &[0.7, 0.3]);
+        let substrates = soup.cell_substrates().unwrap();
+        let bff = substrates.iter().filter(|&&s| s == 0).count() as f64;
+        let fraction = bff / substrates.len() as f64;
+        assert!((fraction - 0.7).abs() < 0.05, "{fraction} of cells run BFF");
+
+        // A one-substrate ensemble runs exactly like the plain soup.
+        let mut plain = fresh();
+        let mut single = fresh();
+        single.set_ensemble(vec![Bff::execute_status], &[1.0]);
+        for _ in 0..3 {
+            plain.run_epoch::<Bff>();
+            single.run_epoch::<Bff>();
+        }
+        assert_eq!(single.programs, plain.programs);
     }
 
     #[test]
-    fn test_adjacency_symmetric() {
-        let mesh = SurfaceMesh::icosphere(1).unwrap();
-        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
-            for &j in adj {
-                assert!(
-                    mesh.face_adjacency[j].contains(&i),
-                    "Face {i} adjacent to {j}, but not vice versa"
-                );
-            }
+    fn test_random_replace_zero_keeps_programs() {
+        let fresh = |write_back, update_mode| {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                mutation_rate: 0.0,
+                update_mode,
+                write_back,
+                ..SoupSurfaceConfig::default()
+            };
+            SoupSurface::new(mesh, config, 42)
+        };
+        for mode in [UpdateMode::Synchronous, UpdateMode::Asynchronous] {
+            let mut soup = fresh(WriteBackPolicy::RandomReplace(0.0), mode);
+            let before = soup.programs.clone();
+            soup.run_epoch::<Bff>();
+            assert_eq!(soup.programs, before);
+
+            // The same epoch under the default policy does change something.
+            let mut soup = fresh(WriteBackPolicy::Replace, mode);
+            soup.run_epoch::<Bff>();
+            assert_ne!(soup.programs, before);
+        }
+        assert_eq!(
+            WriteBackPolicy::parse("random:0.25"),
+            Ok(WriteBackPolicy::RandomReplace(0.25))
+        );
+        assert!(WriteBackPolicy::parse("random:2").is_err());
+        assert!(WriteBackPolicy::parse("xor").is_err());
+
+        // A tape execution left alone lands back byte for byte.
+        let program: Vec<u8> = (0..16).collect();
+        for policy in [
+            WriteBackPolicy::RandomReplace(0.5),
+            WriteBackPolicy::Replace,
+        ] {
+            let mut tape = program.clone();
+            policy.apply(&mut tape, &program, &mut SmallRng::seed_from_u64(1));
+            assert_eq!(tape, program);
         }
     }
 
     #[test]
-    fn test_icosphere_faces_have_3_adjacent() {
-        // Every face on a closed icosphere should have exactly 3 adjacent faces.
-        let mesh = SurfaceMesh::icosphere(2).unwrap();
-        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
-            assert_eq!(
-                adj.len(),
-                3,
-                "Face {i} has {} adjacent faces, expected 3",
-                adj.len()
-            );
+    fn test_rearrangement_permutes_bytes() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 32,
+            mutation_rate: 0.0,
+            rearrange_rate: 1.0,
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 9);
+        let before = soup.programs.clone();
+        soup.mutate();
+        let sorted = |program: &[u8]| {
+            let mut bytes = program.to_vec();
+            bytes.sort_unstable();
+            bytes
+        };
+        for (after, before) in soup.programs.iter().zip(&before) {
+            assert_eq!(sorted(after), sorted(before));
         }
+        assert_ne!(soup.programs, before);
     }
 
     #[test]
-    fn test_torus_faces_have_3_adjacent() {
-        let mesh = SurfaceMesh::torus(8, 5).unwrap();
-        for (i, adj) in mesh.face_adjacency.iter().enumerate() {
-            assert_eq!(
-                adj.len(),
-                3,
-                "Face {i} has {} adjacent faces, expected 3",
-                adj.len()
-            );
+    fn test_counting_rng_counts_draws() {
+        let mut counting = CountingRng::new(SmallRng::seed_from_u64(3));
+        let mut bare = SmallRng::seed_from_u64(3);
+        for _ in 0..10 {
+            assert_eq!(counting.next_u64(), bare.next_u64());
         }
+        assert_eq!(counting.draws(), 10);
+
+        // Counting changes nothing about the run, only what it reports.
+        let fresh = |count_rng| {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                mutation_rate: 0.01,
+                count_rng,
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            soup.run_epoch::<Bff>();
+            soup.mutate();
+            soup
+        };
+        let (counted, bare) = (fresh(true), fresh(false));
+        assert_eq!(counted.programs, bare.programs);
+        assert_eq!(bare.rng_draws(), None);
+        let draws = counted.rng_draws().unwrap();
+        assert_eq!(draws.orientation, counted.pairs().len() as u64);
+        assert!(draws.pairing > 0 && draws.mutation > 0);
+        assert_eq!(draws.write_back, 0);
     }
 
     #[test]
-    fn test_geodesic_neighbors_exclude_self() {
-        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
-        mesh.compute_neighbors(None);
-        for i in 0..mesh.num_cells() {
-            let (start, end) = mesh.neighbor_ranges[i];
-            let neighbors = &mesh.neighbor_indices[start..end];
-            assert!(
-                !neighbors.contains(&i),
-                "Face {i} has itself as a geodesic neighbor"
-            );
-        }
+    fn test_per_epoch_rng_resumes_exactly() {
+        let fresh = |mode| {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 16,
+                step_limit: 256,
+                mutation_rate: 0.01,
+                rng_mode: mode,
+                ..SoupSurfaceConfig::default()
+            };
+            SoupSurface::new(mesh, config, 42)
+        };
+        let run = |soup: &mut SoupSurface, epochs: usize| {
+            for _ in 0..epochs {
+                soup.run_epoch::<Bff>();
+                soup.mutate();
+            }
+        };
+        let resumed = |mode| {
+            let mut first = fresh(mode);
+            run(&mut first, 5);
+            let template = fresh(mode);
+            let mut second =
+                SoupSurface::resume(template.mesh, template.config, 42, 5, first.programs).unwrap();
+            run(&mut second, 5);
+            assert_eq!(second.epoch(), 10);
+            second.programs
+        };
+
+        let mut straight = fresh(RngMode::PerEpoch);
+        run(&mut straight, 10);
+        assert_eq!(resumed(RngMode::PerEpoch), straight.programs);
+
+        let mut straight = fresh(RngMode::Streaming);
+        run(&mut straight, 10);
+        assert_ne!(resumed(RngMode::Streaming), straight.programs);
     }
 
     #[test]
-    fn test_geodesic_neighbors_nonempty() {
-        let mut mesh = SurfaceMesh::icosphere(2).unwrap();
-        mesh.compute_neighbors(None);
-        for i in 0..mesh.num_cells() {
-            let (start, end) = mesh.neighbor_ranges[i];
-            assert!(end > start, "Face {i} has no geodesic neighbors");
+    fn test_extinction_wipes_requested_fraction() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 42);
+        for program in &mut soup.programs {
+            program.fill(0);
         }
+        let wiped = soup.extinction(0.5);
+        assert_eq!(wiped, soup.programs.len() / 2);
+        let changed = soup.programs.iter().filter(|p| p.iter().any(|&b| b != 0));
+        assert_eq!(changed.count(), wiped);
+        assert_eq!(soup.extinction(0.0), 0);
     }
 
     #[test]
-    fn test_deterministic_surface_simulation() {
-        let run = |seed: u64| -> Vec<Vec<u8>> {
+    fn test_tournament_spreads_fitter_program() {
+        let copies_after = |selection: SelectionMode| -> usize {
             let mut mesh = SurfaceMesh::icosphere(1).unwrap();
-            mesh.compute_neighbors(None);
+            mesh.compute_neighbors(None, None);
             let config = SoupSurfaceConfig {
                 program_size: 16,
                 step_limit: 256,
-                mutation_rate: 0.001,
-                interaction_mode: InteractionMode::Normal,
+                mutation_rate: 0.0,
+                selection,
+                ..SoupSurfaceConfig::default()
             };
-            let mut soup = SoupSurface::new(mesh, config, seed);
-            for _ in 0..10 {
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            // Head moves only: every byte is a BFF instruction, none write.
+            let fit = b"<><><><><><><><>".to_vec();
+            for prog in soup.programs.iter_mut() {
+                prog.fill(0);
+            }
+            soup.programs[0] = fit.clone();
+            for _ in 0..20 {
                 soup.run_epoch::<Bff>();
-                soup.mutate();
             }
-            soup.programs
+            soup.programs.iter().filter(|p| **p == fit).count()
         };
-        assert_eq!(run(42), run(42));
-        assert_ne!(run(42), run(99));
+        assert_eq!(copies_after(SelectionMode::None), 1);
+        let tournament = SelectionMode::Tournament(Fitness::InstructionCount);
+        assert!(copies_after(tournament) > 10);
     }
 
     #[test]
-    fn test_obj_loader_cube() {
-        let obj = "\
-v 0.0 0.0 0.0
-v 1.0 0.0 0.0
-v 1.0 1.0 0.0
-v 0.0 1.0 0.0
-v 0.0 0.0 1.0
-v 1.0 0.0 1.0
-v 1.0 1.0 1.0
-v 0.0 1.0 1.0
-f 1 2 3 4
-f 5 6 7 8
-f 1 2 6 5
-f 2 3 7 6
-f 3 4 8 7
-f 4 1 5 8
-";
-        let dir = std::env::temp_dir();
-        let path = dir.join("test_cube.obj");
-        std::fs::write(&path, obj).unwrap();
-        let mesh = SurfaceMesh::from_obj(path.to_str().unwrap()).unwrap();
-        // 6 quads → 12 triangles
-        assert_eq!(mesh.faces.len(), 12);
-        assert_eq!(mesh.vertices.len(), 8);
+    fn test_instruction_biased_mutation_raises_density() {
+        let density_after = |model: MutationModel| -> f64 {
+            let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+            mesh.compute_neighbors(None, None);
+            let config = SoupSurfaceConfig {
+                program_size: 64,
+                mutation_rate: 0.05,
+                mutation_model: model,
+                ..SoupSurfaceConfig::default()
+            };
+            let mut soup = SoupSurface::new(mesh, config, 42);
+            for _ in 0..50 {
+                soup.mutate();
+            }
+            let total = soup.programs.len() * 64;
+            let instructions = soup
+                .programs
+                .iter()
+                .flatten()
+                .filter(|&&b| Bff::is_instruction(b))
+                .count();
+            instructions as f64 / total as f64
+        };
+
+        let bit_flip = density_after(MutationModel::BitFlip);
+        let biased = density_after(MutationModel::toward_instructions(Bff::is_instruction, 8.0));
+        assert!(
+            biased > 2.0 * bit_flip,
+            "biased density {biased} should clearly exceed bit-flip density {bit_flip}"
+        );
     }
 
     #[test]
-    fn test_mutation_disabled_surface() {
-        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
-        mesh.compute_neighbors(None);
+    fn test_mutators_mutate_faster_and_pass_it_on() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
         let config = SoupSurfaceConfig {
-            program_size: 16,
-            step_limit: 256,
-            mutation_rate: 0.0,
-            interaction_mode: InteractionMode::Normal,
+            program_size: 64,
+            mutation_rate: 0.002,
+            mutators: Some(Mutators {
+                fraction: 0.25,
+                strength: 10.0,
+            }),
+            ..SoupSurfaceConfig::default()
         };
         let mut soup = SoupSurface::new(mesh, config, 42);
-        let before = soup.programs.clone();
-        soup.mutate();
-        assert_eq!(soup.programs, before);
+        let mut changes = vec![0usize; soup.programs.len()];
+        for _ in 0..20 {
+            let before = soup.programs.clone();
+            soup.mutate();
+            for ((count, before), after) in changes.iter_mut().zip(&before).zip(&soup.programs) {
+                *count += before.iter().zip(after).filter(|(a, b)| a != b).count();
+            }
+        }
+        let multipliers = soup.mutation_multipliers().unwrap().to_vec();
+        let mean_changes = |strength: f64| {
+            let cells: Vec<usize> = (0..changes.len())
+                .filter(|&cell| multipliers[cell] == strength)
+                .collect();
+            assert!(!cells.is_empty());
+            cells.iter().map(|&cell| changes[cell]).sum::<usize>() as f64 / cells.len() as f64
+        };
+        let (mutator, baseline) = (mean_changes(10.0), mean_changes(1.0));
+        assert!(
+            mutator > 5.0 * baseline,
+            "mutators changed {mutator} bytes on average, baseline cells {baseline}"
+        );
+
+        // A copy carries its source's multiplier over the cell it lands on.
+        let mutator_cell = multipliers.iter().position(|&m| m == 10.0).unwrap();
+        let baseline_cell = multipliers.iter().position(|&m| m == 1.0).unwrap();
+        let source = soup.programs[mutator_cell].clone();
+        let tape = [source.clone(), source].concat();
+        let mut multipliers = multipliers;
+        write_back(
+            &mut soup.programs,
+            None,
+            Some(&mut multipliers),
+            &mut soup.ages,
+            (mutator_cell, baseline_cell),
+            &tape,
+        );
+        assert_eq!(multipliers[baseline_cell], 10.0);
+        assert_eq!(multipliers[mutator_cell], 10.0);
+    }
+
+    #[test]
+    fn test_program_age_counts_epochs_without_change() {
+        let config = SoupSurfaceConfig {
+            program_size: 64,
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(SurfaceMesh::flat_grid(2, 2).unwrap(), config, 3);
+        // Cell 0 increments the last byte of its partner, cell 1, each time
+        // it runs; cell 2 runs against nothing and never changes.
+        for cell in 0..3 {
+            soup.programs[cell].fill(0);
+        }
+        soup.programs[0][..2].copy_from_slice(b"<+");
+
+        for epoch in 1..=10 {
+            soup.run_epoch_with_pairs::<Bff>(&[(0, 1), (2, NO_PARTNER)])
+                .unwrap();
+            assert_eq!(soup.ages()[1], 0);
+            assert_eq!(soup.ages()[0], epoch);
+            assert_eq!(soup.ages()[2], epoch);
+        }
+        assert_eq!(soup.programs[1][63], 10);
+    }
+
+    #[test]
+    fn test_no_mutation_after_cutoff() {
+        let config = SoupSurfaceConfig {
+            mutation_rate: 0.05,
+            no_mutation_after: Some(3),
+            ..SoupSurfaceConfig::default()
+        };
+        let mut soup = SoupSurface::new(SurfaceMesh::icosphere(0).unwrap(), config, 9);
+        for epoch in 1..=6 {
+            // No pairs, so only mutation can touch the programs.
+            soup.run_epoch_with_pairs::<Bff>(&[]).unwrap();
+            let before = soup.programs.clone();
+            soup.mutate();
+            assert_eq!(soup.programs == before, epoch >= 3, "epoch {epoch}");
+        }
+    }
+
+    #[test]
+    fn test_substitution_never_maps_byte_to_itself() {
+        let model = MutationModel::toward_instructions(Bff::is_instruction, 8.0);
+        let mut rng = SmallRng::seed_from_u64(7);
+        for b in 0..=255u8 {
+            assert_ne!(model.apply(b, &mut rng), b);
+        }
     }
 
     #[test]
@@ -1586,12 +5449,13 @@ f 4 1 5 8
         use crate::metrics::high_order_entropy;
 
         let mut mesh = SurfaceMesh::icosphere(1).unwrap();
-        mesh.compute_neighbors(None);
+        mesh.compute_neighbors(None, None);
         let config = SoupSurfaceConfig {
             program_size: 64,
             step_limit: 8192,
             mutation_rate: 0.00024,
             interaction_mode: InteractionMode::Normal,
+            ..SoupSurfaceConfig::default()
         };
         let mut soup = SoupSurface::new(mesh, config, 42);
 
@@ -1613,6 +5477,22 @@ f 4 1 5 8
         assert!(final_hoe > 0.0, "Final HOE should be positive");
     }
 
+    #[test]
+    fn test_population_bytes_checked_rejects_mismatched_length() {
+        let mut mesh = SurfaceMesh::icosphere(0).unwrap();
+        mesh.compute_neighbors(None, None);
+        let mut soup = SoupSurface::new(mesh, SoupSurfaceConfig::default(), 42);
+        let mut buf = Vec::new();
+        soup.population_bytes_checked(&mut buf).unwrap();
+        assert_eq!(buf.len(), 20 * 64);
+
+        soup.programs[3].truncate(10);
+        let err = soup.population_bytes_checked(&mut buf).unwrap_err();
+        assert!(err.contains("Program 3 is 10 bytes"), "{err}");
+        soup.programs[3].clear();
+        assert!(soup.population_bytes_checked(&mut buf).is_err());
+    }
+
     #[test]
     fn test_hamster_tunnel_basic() {
         let mesh = SurfaceMesh::hamster_tunnel(5, 16, 42).unwrap();
@@ -1687,12 +5567,167 @@ f 4 1 5 8
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/obj/icosahedron.obj");
         let spec = SurfaceSpec::ObjFile {
             path: path.to_string(),
+            normalize: false,
+            drop_degenerate: false,
         };
         assert_eq!(spec.label(), "OBJ File");
         let mesh = spec.build().unwrap();
         assert_eq!(mesh.faces.len(), 20);
     }
 
+    #[test]
+    fn test_surface_spec_parse() {
+        assert_eq!(
+            SurfaceSpec::parse("sphere:3", 0).unwrap(),
+            SurfaceSpec::Sphere { subdivisions: 3 }
+        );
+        assert_eq!(
+            SurfaceSpec::parse("torus:32x16", 0).unwrap(),
+            SurfaceSpec::Torus {
+                major: 32,
+                minor: 16
+            }
+        );
+        assert_eq!(
+            SurfaceSpec::parse("hamster:10x24", 7).unwrap(),
+            SurfaceSpec::HamsterTunnel {
+                num_spheres: 10,
+                segments: 24,
+                seed: 7
+            }
+        );
+        assert_eq!(
+            SurfaceSpec::parse("obj:a/b.obj", 0).unwrap(),
+            SurfaceSpec::ObjFile {
+                path: "a/b.obj".to_string(),
+                normalize: false,
+                drop_degenerate: false,
+            }
+        );
+        assert!(SurfaceSpec::parse("sphere", 0).is_err());
+        assert!(SurfaceSpec::parse("torus:32", 0).is_err());
+        assert!(SurfaceSpec::parse("cube:4", 0).is_err());
+    }
+
+    #[test]
+    fn test_face_count_matches_built_mesh() {
+        for spec in [
+            "sphere:2",
+            "torus:8x6",
+            "flat:5x3",
+            "cylinder:6x4",
+            "klein:8x6",
+            "heightmap:5x4",
+            "trefoil:12x5",
+            "grid3d:4x3x2",
+        ] {
+            let spec = SurfaceSpec::parse(spec, 1).unwrap();
+            let faces = spec.build().unwrap().faces.len();
+            assert_eq!(spec.face_count(), Some(faces), "{spec:?}");
+        }
+        let obj = SurfaceSpec::parse("obj:mesh.obj", 1).unwrap();
+        assert_eq!(obj.face_count(), None);
+        let huge = SurfaceSpec::Sphere { subdivisions: 64 };
+        assert_eq!(huge.face_count(), Some(usize::MAX));
+    }
+
+    #[test]
+    fn test_lattice_3d_neighbors() {
+        let spec = SurfaceSpec::parse("grid3d:4x3x5", 1).unwrap();
+        assert!(spec.builds_neighbors());
+        let mesh = spec.build().unwrap();
+        assert_eq!(mesh.num_cells(), 4 * 3 * 5);
+        let cell = |x: usize, y: usize, z: usize| x + 4 * (y + 3 * z);
+        let interior = cell(1, 1, 2);
+        assert_eq!(
+            mesh.neighbors_of(interior),
+            [
+                cell(1, 1, 1),
+                cell(1, 0, 2),
+                cell(0, 1, 2),
+                cell(2, 1, 2),
+                cell(1, 2, 2),
+                cell(1, 1, 3),
+            ]
+        );
+        assert_eq!(mesh.neighbors_of(cell(0, 0, 0)).len(), 3);
+        assert_eq!(mesh.neighbors_of(cell(3, 1, 0)).len(), 4);
+
+        // Wrapped, every cell is interior; a 2-wide axis reaches one cell
+        // both ways, and a 1-wide axis only the cell itself.
+        let periodic = SurfaceSpec::parse("grid3d:4x2x1:periodic", 1).unwrap();
+        let mesh = periodic.build().unwrap();
+        assert!((0..8).all(|c| mesh.neighbors_of(c).len() == 3));
+        let mesh = SurfaceMesh::lattice_3d(3, 3, 3, true).unwrap();
+        assert!((0..27).all(|c| mesh.neighbors_of(c).len() == 6));
+        assert!(SurfaceSpec::parse("grid3d:4x3", 1).is_err());
+        assert!(SurfaceMesh::lattice_3d(4, 0, 2, false).is_err());
+
+        // The soup runs over the lattice as over any mesh.
+        let config = SoupSurfaceConfig {
+            program_size: 16,
+            step_limit: 64,
+            ..Default::default()
+        };
+        let mut soup = SoupSurface::new(mesh, config, 3);
+        soup.run_epoch::<crate::bff::Bff>();
+        assert_eq!(soup.epoch(), 1);
+    }
+
+    #[test]
+    fn test_build_memory_estimate() {
+        let estimate = |spec: &str| {
+            let faces = SurfaceSpec::parse(spec, 1).unwrap().face_count().unwrap();
+            estimate_build_bytes(faces, DEFAULT_NEIGHBORS_ESTIMATE, 16)
+        };
+        assert!(estimate("sphere:2") < BUILD_MEMORY_LIMIT);
+        assert!(estimate("sphere:8") > BUILD_MEMORY_LIMIT);
+        // Capping the neighbor table brings it back under.
+        let sphere = SurfaceSpec::parse("sphere:8", 1).unwrap();
+        let faces = sphere.face_count().unwrap();
+        assert!(estimate_build_bytes(faces, 8, 16) < BUILD_MEMORY_LIMIT);
+        // Face counts too big to represent saturate instead of overflowing.
+        assert_eq!(estimate("sphere:40"), u64::MAX);
+        assert_eq!(
+            estimate_build_bytes(usize::MAX, usize::MAX, usize::MAX),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_population_for_budget() {
+        let (budget, k) = (256 << 20, DEFAULT_NEIGHBORS_ESTIMATE);
+        let cells = population_for_budget(budget, 64, k, 8);
+        assert!(estimate_run_bytes(cells, 64, k, 8) <= budget);
+        assert!(estimate_run_bytes(cells + 1, 64, k, 8) > budget);
+        // Bigger programs leave room for fewer cells.
+        assert!(population_for_budget(budget, 1024, k, 8) < cells);
+        assert_eq!(population_for_budget(0, 64, k, 8), 0);
+        // A budget nothing can exhaust stops at the cap instead of overflowing.
+        assert_eq!(estimate_run_bytes(usize::MAX, 64, k, 8), u64::MAX);
+        assert_eq!(population_for_budget(u64::MAX, 64, k, 8), MAX_BUDGET_CELLS);
+    }
+
+    #[test]
+    fn test_surface_spec_json_round_trip() {
+        let spec = SurfaceSpec::Torus {
+            major: 32,
+            minor: 16,
+        };
+        let json = serde_json::to_string(&spec).unwrap();
+        let back: SurfaceSpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, spec);
+    }
+
+    #[test]
+    fn test_substitution_matrix_json_round_trip() {
+        let model = MutationModel::toward_instructions(|b| b < 16, 8.0);
+        let json = serde_json::to_string(&model).unwrap();
+        let back: MutationModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, model);
+        assert!(serde_json::from_str::<MutationModel>(r#"{"Substitution":[[1.0]]}"#).is_err());
+    }
+
     #[test]
     fn test_cylinder_face_count() {
         // Body: 2 * segments * rings, caps: 2 * segments
@@ -1703,7 +5738,7 @@ f 4 1 5 8
     #[test]
     fn test_cylinder_min_params() {
         let mesh = SurfaceMesh::cylinder(3, 1).unwrap();
-        assert_eq!(mesh.faces.len(), 2 * 3 * 1 + 2 * 3);
+        assert_eq!(mesh.faces.len(), 2 * 3 + 2 * 3);
     }
 
     #[test]
@@ -1797,6 +5832,60 @@ f 4 1 5 8
         assert!(SurfaceMesh::heightmap(8, 0, 0).is_err());
     }
 
+    #[test]
+    fn test_heightmap_image_constant_gray_is_flat_grid() {
+        let path = std::env::temp_dir().join("test_heightmap_gray.png");
+        image::GrayImage::from_pixel(5, 4, image::Luma([128]))
+            .save(&path)
+            .unwrap();
+        let mesh = SurfaceMesh::from_heightmap(path.to_str().unwrap(), 0.5).unwrap();
+        let grid = SurfaceMesh::flat_grid(4, 3).unwrap();
+
+        assert_eq!(mesh.faces, grid.faces);
+        let sorted = |adj: &[Vec<usize>]| -> Vec<Vec<usize>> {
+            adj.iter()
+                .map(|n| {
+                    let mut n = n.clone();
+                    n.sort_unstable();
+                    n
+                })
+                .collect()
+        };
+        assert_eq!(sorted(&mesh.face_adjacency), sorted(&grid.face_adjacency));
+        let z0 = mesh.vertices[0][2];
+        assert!((z0 - 128.0 / 255.0 * 0.5).abs() < 1e-6);
+        for (v, g) in mesh.vertices.iter().zip(&grid.vertices) {
+            assert_eq!([v[0], v[1]], [g[0], g[1]]);
+            assert_eq!(v[2], z0, "constant gray should give a flat surface");
+        }
+    }
+
+    #[test]
+    fn test_heightmap_image_spec_parse() {
+        assert_eq!(
+            SurfaceSpec::parse("heightmap:16x8", 3).unwrap(),
+            SurfaceSpec::Heightmap {
+                width: 16,
+                height: 8,
+                seed: 3
+            }
+        );
+        assert_eq!(
+            SurfaceSpec::parse("heightmap:terrain.png", 0).unwrap(),
+            SurfaceSpec::HeightmapImage {
+                path: "terrain.png".to_string(),
+                scale: DEFAULT_HEIGHTMAP_IMAGE_SCALE
+            }
+        );
+        assert_eq!(
+            SurfaceSpec::parse("heightmap:terrain.png:0.2", 0).unwrap(),
+            SurfaceSpec::HeightmapImage {
+                path: "terrain.png".to_string(),
+                scale: 0.2
+            }
+        );
+    }
+
     #[test]
     fn test_trefoil_knot_face_count() {
         let mesh = SurfaceMesh::trefoil_knot(64, 8).unwrap();
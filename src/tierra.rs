@@ -0,0 +1,455 @@
+use crate::substrate::{
+    CLASS_ARITHMETIC, CLASS_CONTROL, CLASS_DATA, CLASS_MEMORY, CLASS_NOP, CLASS_OTHER,
+    CLASS_POINTER, Substrate,
+};
+
+/// The Tierra instruction set — a template-addressed substrate.
+///
+/// Inspired by Ray's Tierra. Jumps and address lookups carry no numeric
+/// operand: instead, the run of `NOP0`/`NOP1` bytes following the instruction
+/// is a *template*, and the instruction targets the nearest occurrence of its
+/// complement (every `NOP0` swapped for `NOP1` and vice versa). Programs
+/// label their own loop heads and boundaries rather than computing addresses.
+///
+/// State:
+/// - `pc`: instruction pointer, starts at 0
+/// - `ax`: read address, starts at 0
+/// - `bx`: write address, starts at `tape.len() / 2`
+/// - `cx`: counter, starts at 0
+///
+/// Template searches scan outward at most `SCAN_LIMIT` bytes and wrap
+/// modulo the tape length, as do all addresses. A search that finds no
+/// complement falls through: execution continues after the template.
+pub struct Tierra;
+
+// Instruction opcodes
+const NOP0: u8 = 0x00;
+const NOP1: u8 = 0x01;
+const ZERO: u8 = 0x02;
+const INC_C: u8 = 0x03;
+const DEC_C: u8 = 0x04;
+const INC_A: u8 = 0x05;
+const INC_B: u8 = 0x06;
+const MOV_AB: u8 = 0x07;
+const SUB_AB: u8 = 0x08;
+const MOV_II: u8 = 0x09;
+const IFZ: u8 = 0x0A;
+const JMP: u8 = 0x0B;
+const JMPB: u8 = 0x0C;
+const ADR: u8 = 0x0D;
+const ADRB: u8 = 0x0E;
+const ADRF: u8 = 0x0F;
+
+/// Longest template an instruction reads.
+const MAX_TEMPLATE: usize = 8;
+/// Furthest distance (in bytes, per direction) a template search looks.
+const SCAN_LIMIT: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Nearest,
+    Backward,
+    Forward,
+}
+
+struct TierraState {
+    pc: usize,
+    /// Tape positions, kept below the tape length so they reach every
+    /// byte of tapes longer than 256.
+    ax: usize,
+    bx: usize,
+    cx: u8,
+}
+
+fn tierra_init(pc: usize, ax: usize, bx: usize) -> TierraState {
+    TierraState { pc, ax, bx, cx: 0 }
+}
+
+/// Length of the template starting at `start` (no wrap, at most `MAX_TEMPLATE`).
+fn template_len(tape: &[u8], start: usize) -> usize {
+    tape.get(start..)
+        .unwrap_or(&[])
+        .iter()
+        .take(MAX_TEMPLATE)
+        .take_while(|&&b| b == NOP0 || b == NOP1)
+        .count()
+}
+
+/// Find the nearest position whose bytes complement the template at
+/// `tape[instr + 1..instr + 1 + k]`. Backward candidates end before the
+/// instruction; forward candidates start after its template. At equal
+/// distance, `Nearest` prefers forward.
+fn find_complement(tape: &[u8], instr: usize, k: usize, dir: Direction) -> Option<usize> {
+    let len = tape.len();
+    if k == 0 {
+        return None;
+    }
+    let template = &tape[instr + 1..instr + 1 + k];
+    let matches_at = |p: usize| (0..k).all(|j| tape[(p + j) % len] == template[j] ^ 1);
+    let template_end = instr + 1 + k;
+
+    for d in 1..=SCAN_LIMIT.min(len) {
+        if dir != Direction::Backward {
+            let p = (template_end + d - 1) % len;
+            if matches_at(p) {
+                return Some(p);
+            }
+        }
+        if dir != Direction::Forward {
+            let p = (instr as isize - (k + d) as isize + 1).rem_euclid(len as isize) as usize;
+            if matches_at(p) {
+                return Some(p);
+            }
+        }
+    }
+    None
+}
+
+/// Execute one Tierra instruction. Returns true if still running.
+fn tierra_step(state: &mut TierraState, tape: &mut [u8]) -> bool {
+    let len = tape.len();
+    if state.pc >= len {
+        return false;
+    }
+    let instr = tape[state.pc];
+    match instr {
+        ZERO => state.cx = 0,
+        INC_C => state.cx = state.cx.wrapping_add(1),
+        DEC_C => state.cx = state.cx.wrapping_sub(1),
+        INC_A => state.ax = (state.ax + 1) % len,
+        INC_B => state.bx = (state.bx + 1) % len,
+        MOV_AB => state.bx = state.ax,
+        SUB_AB => state.cx = state.ax.wrapping_sub(state.bx) as u8,
+        MOV_II => tape[state.bx % len] = tape[state.ax % len],
+        IFZ if state.cx != 0 => state.pc += 1,
+        JMP | JMPB | ADR | ADRB | ADRF => {
+            let k = template_len(tape, state.pc + 1);
+            let dir = match instr {
+                JMP | ADR => Direction::Nearest,
+                JMPB | ADRB => Direction::Backward,
+                _ => Direction::Forward,
+            };
+            let found = find_complement(tape, state.pc, k, dir);
+            match (instr, found) {
+                (JMP | JMPB, Some(p)) => {
+                    state.pc = p + k;
+                    return true;
+                }
+                (_, Some(p)) => state.ax = p,
+                (_, None) => {}
+            }
+            state.pc += k;
+        }
+        _ => {} // IFZ with cx == 0, NOP0, NOP1, and 0x10-0xFF
+    }
+    state.pc += 1;
+    true
+}
+
+impl Substrate for Tierra {
+    fn execute(tape: &mut [u8], step_limit: usize) -> usize {
+        let len = tape.len();
+        if len == 0 {
+            return 0;
+        }
+
+        let mut state = tierra_init(0, 0, len / 2);
+        let mut steps = 0;
+
+        while state.pc < len && steps < step_limit {
+            steps += 1;
+            if !tierra_step(&mut state, tape) {
+                break;
+            }
+        }
+
+        steps
+    }
+
+    fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
+        let len = tape.len();
+        if len == 0 {
+            return 0;
+        }
+        let mut a = tierra_init(0, 0, ps);
+        let mut b = tierra_init(ps, ps, 0);
+        let mut steps = 0;
+        let mut halted_a = false;
+        let mut halted_b = false;
+        while steps < step_limit && (!halted_a || !halted_b) {
+            if !halted_a {
+                halted_a = !tierra_step(&mut a, tape);
+                steps += 1;
+                if steps >= step_limit {
+                    break;
+                }
+            }
+            if !halted_b {
+                halted_b = !tierra_step(&mut b, tape);
+                steps += 1;
+            }
+        }
+        steps
+    }
+
+    fn is_instruction(byte: u8) -> bool {
+        byte <= ADRF
+    }
+
+    fn instruction_class(byte: u8) -> u8 {
+        match byte {
+            // Template bits only matter to the instructions that read them.
+            NOP0 | NOP1 => CLASS_OTHER,
+            ZERO | INC_C | DEC_C | INC_A | INC_B | SUB_AB => CLASS_ARITHMETIC,
+            MOV_AB => CLASS_DATA,
+            MOV_II => CLASS_MEMORY,
+            IFZ | JMP | JMPB => CLASS_CONTROL,
+            ADR | ADRB | ADRF => CLASS_POINTER,
+            _ => CLASS_NOP,
+        }
+    }
+
+    fn opcode_table() -> &'static [(u8, &'static str, &'static str)] {
+        &[
+            (NOP0, "NOP0", "Template bit 0; otherwise does nothing"),
+            (NOP1, "NOP1", "Template bit 1; otherwise does nothing"),
+            (ZERO, "ZERO", "cx = 0"),
+            (INC_C, "INC_C", "cx++"),
+            (DEC_C, "DEC_C", "cx--"),
+            (INC_A, "INC_A", "ax++"),
+            (INC_B, "INC_B", "bx++"),
+            (MOV_AB, "MOV_AB", "bx = ax"),
+            (SUB_AB, "SUB_AB", "cx = ax - bx"),
+            (MOV_II, "MOV_II", "Copy the byte at ax to bx"),
+            (IFZ, "IFZ", "Skip the next instruction unless cx is 0"),
+            (
+                JMP,
+                "JMP",
+                "Jump past the nearest complement of the template",
+            ),
+            (JMPB, "JMPB", "JMP, searching backward only"),
+            (
+                ADR,
+                "ADR",
+                "ax = address of the nearest complement of the template",
+            ),
+            (ADRB, "ADRB", "ADR, searching backward only"),
+            (ADRF, "ADRF", "ADR, searching forward only"),
+        ]
+    }
+
+    fn disassemble(tape: &[u8]) -> String {
+        use std::fmt::Write;
+        let mut out = String::new();
+        for (addr, &b) in tape.iter().enumerate() {
+            let desc = match b {
+                NOP0 => "NOP0",
+                NOP1 => "NOP1",
+                ZERO => "ZERO",
+                INC_C => "INC_C",
+                DEC_C => "DEC_C",
+                INC_A => "INC_A",
+                INC_B => "INC_B",
+                MOV_AB => "MOV_AB",
+                SUB_AB => "SUB_AB",
+                MOV_II => "MOV_II",
+                IFZ => "IFZ",
+                JMP => "JMP",
+                JMPB => "JMPB",
+                ADR => "ADR",
+                ADRB => "ADRB",
+                ADRF => "ADRF",
+                _ => "NOP",
+            };
+            let _ = writeln!(out, "{addr:04X}: {b:02X}  {desc}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Filler byte that is neither an instruction nor a template bit.
+    const FILL: u8 = 0x20;
+
+    fn make_tape(program: &[u8], size: usize) -> Vec<u8> {
+        let mut tape = vec![FILL; size];
+        tape[..program.len()].copy_from_slice(program);
+        tape
+    }
+
+    fn step(tape: &mut [u8], pc: usize) -> TierraState {
+        let mut state = tierra_init(pc, 0, tape.len() / 2);
+        tierra_step(&mut state, tape);
+        state
+    }
+
+    // --- Template matching ---
+
+    #[test]
+    fn test_jmp_lands_after_complement() {
+        let mut tape = make_tape(&[JMP, NOP1, NOP0], 128);
+        tape[20..22].copy_from_slice(&[NOP0, NOP1]);
+        assert_eq!(step(&mut tape, 0).pc, 22);
+    }
+
+    #[test]
+    fn test_jmp_prefers_nearest_match() {
+        let mut tape = make_tape(&[], 128);
+        tape[40..43].copy_from_slice(&[JMP, NOP1, NOP1]);
+        tape[34..36].copy_from_slice(&[NOP0, NOP0]); // 5 bytes back
+        tape[45..47].copy_from_slice(&[NOP0, NOP0]); // 3 bytes ahead
+        tape[10..12].copy_from_slice(&[NOP0, NOP0]);
+        assert_eq!(step(&mut tape, 40).pc, 47);
+
+        tape[45..47].fill(FILL);
+        assert_eq!(step(&mut tape, 40).pc, 36);
+    }
+
+    #[test]
+    fn test_jmpb_ignores_forward_match() {
+        let mut tape = make_tape(&[], 128);
+        tape[40..42].copy_from_slice(&[JMPB, NOP1]);
+        tape[43] = NOP0; // nearer, but forward
+        tape[30] = NOP0;
+        assert_eq!(step(&mut tape, 40).pc, 31);
+    }
+
+    #[test]
+    fn test_search_wraps_around_tape() {
+        let mut tape = make_tape(&[JMPB, NOP0], 128);
+        tape[120] = NOP1;
+        assert_eq!(step(&mut tape, 0).pc, 121);
+    }
+
+    #[test]
+    fn test_adrf_sets_ax_to_match_start() {
+        let mut tape = make_tape(&[ADRF, NOP0, NOP1, NOP0], 128);
+        tape[50..53].copy_from_slice(&[NOP1, NOP0, NOP1]);
+        let state = step(&mut tape, 0);
+        assert_eq!(state.ax, 50);
+        assert_eq!(state.pc, 4);
+    }
+
+    #[test]
+    fn test_no_match_falls_through() {
+        let mut tape = make_tape(&[JMP, NOP1, NOP1], 128);
+        let state = step(&mut tape, 0);
+        assert_eq!(state.pc, 3);
+
+        let mut tape = make_tape(&[ADR, NOP0], 128);
+        let state = step(&mut tape, 0);
+        assert_eq!((state.pc, state.ax), (2, 0));
+    }
+
+    #[test]
+    fn test_missing_template_is_noop() {
+        let mut tape = make_tape(&[JMP, INC_A], 128);
+        assert_eq!(step(&mut tape, 0).pc, 1);
+    }
+
+    // --- Registers ---
+
+    #[test]
+    fn test_mov_ii_copies_ax_to_bx() {
+        let mut tape = make_tape(&[MOV_II], 128);
+        step(&mut tape, 0);
+        assert_eq!(tape[64], MOV_II);
+    }
+
+    #[test]
+    fn test_ifz_skips_when_nonzero() {
+        let mut tape = make_tape(&[INC_C, IFZ, INC_A, INC_B], 128);
+        let mut state = tierra_init(0, 0, 64);
+        for _ in 0..3 {
+            tierra_step(&mut state, &mut tape);
+        }
+        assert_eq!((state.ax, state.bx), (0, 65));
+    }
+
+    #[test]
+    fn test_sub_ab() {
+        let mut tape = make_tape(&[INC_A, INC_A, SUB_AB], 128);
+        let mut state = tierra_init(0, 0, 1);
+        for _ in 0..3 {
+            tierra_step(&mut state, &mut tape);
+        }
+        assert_eq!(state.cx, 1);
+    }
+
+    // --- Execution ---
+
+    #[test]
+    fn test_empty_tape() {
+        let mut tape: Vec<u8> = vec![];
+        assert_eq!(Tierra::execute(&mut tape, 8192), 0);
+    }
+
+    #[test]
+    fn test_step_limit() {
+        // "0" label, then JMPB "1" loops back to it forever.
+        let mut tape = make_tape(&[NOP0, JMPB, NOP1], 128);
+        assert_eq!(Tierra::execute(&mut tape, 100), 100);
+    }
+
+    #[test]
+    fn test_copy_loop_replicates() {
+        // Label "0"; copy a byte; advance both pointers; jump back to label.
+        let replicator = [NOP0, MOV_II, INC_A, INC_B, JMPB, NOP1];
+        let mut tape = make_tape(&replicator, 128);
+        Tierra::execute(&mut tape, 8192);
+        assert_eq!(&tape[64..70], &replicator);
+        assert_eq!(tape[..64], tape[64..]);
+    }
+
+    #[test]
+    fn test_addresses_span_long_tapes() {
+        // At 256-byte programs the copy target starts at byte 256, past
+        // what a byte-wide register can hold.
+        let replicator = [NOP0, MOV_II, INC_A, INC_B, JMPB, NOP1];
+        let mut tape = make_tape(&replicator, 512);
+        Tierra::execute(&mut tape, 8192);
+        assert_eq!(&tape[256..262], &replicator);
+        assert_eq!(tape[..256], tape[256..]);
+
+        let mut tape = make_tape(&[ADRF, NOP0, NOP1], 512);
+        // Past byte 255, but within the search's reach.
+        tape[257..259].copy_from_slice(&[NOP1, NOP0]);
+        assert_eq!(step(&mut tape, 0).ax, 257);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn random_programs_never_panic(tape_data in prop::collection::vec(any::<u8>(), 1..256)) {
+            let mut tape = tape_data;
+            let steps = Tierra::execute(&mut tape, 8192);
+            prop_assert!(steps <= 8192);
+        }
+
+        #[test]
+        fn random_programs_respect_step_limit(
+            tape_data in prop::collection::vec(any::<u8>(), 1..256),
+            limit in 1usize..1000
+        ) {
+            let mut tape = tape_data;
+            let steps = Tierra::execute(&mut tape, limit);
+            prop_assert!(steps <= limit);
+        }
+
+        #[test]
+        fn battle_never_panics(tape_data in prop::collection::vec(any::<u8>(), 2..256)) {
+            let mut tape = tape_data;
+            let ps = tape.len() / 2;
+            let steps = Tierra::execute_battle(&mut tape, ps, 8192);
+            prop_assert!(steps <= 8192);
+        }
+    }
+}
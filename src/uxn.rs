@@ -3,7 +3,7 @@ use std::panic::{AssertUnwindSafe, catch_unwind};
 use baryuxn::execute_operation;
 use baryuxn::prelude::*;
 
-use crate::substrate::Substrate;
+use crate::substrate::{Substrate, validate_16bit_program_size};
 
 /// Wraps a byte-slice tape as Uxn memory with modular addressing.
 ///
@@ -78,6 +78,10 @@ impl Substrate for Uxn {
         steps
     }
 
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        validate_16bit_program_size(program_size)
+    }
+
     fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
         if tape.is_empty() {
             return 0;
@@ -1,32 +1,32 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, mpsc};
 use std::thread;
 
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::input::mouse::{MouseMotion, MouseWheel};
-use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, RayCastSettings};
+use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, RayCastBackfaces, RayCastSettings};
 use bevy::prelude::*;
 use bevy::render::mesh::PrimitiveTopology;
 use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::Face;
 use bevy_egui::{EguiContexts, EguiPlugin, egui};
-use egui_plot::{Line, Plot, PlotPoints};
+use egui_plot::{Bar, BarChart, Line, Plot, PlotPoints};
 
-use crate::bff::Bff;
-use crate::bits::Bits;
-use crate::echo::Echo;
-use crate::forth::Forth;
+use crate::headless::write_metrics_csv;
+use crate::hybrid::Hybrid;
 use crate::metrics::{
-    byte_frequency_histogram, high_order_entropy, unique_program_count, zero_byte_count,
+    CopyEvent, active_fraction, byte_frequency_histogram, consensus_program, count_quines,
+    high_order_entropy, mean_distance_to_consensus, modal_program, parasite_fraction,
+    positional_mutual_information, uniformity_chi_square, unique_program_count, zero_byte_count,
 };
-use crate::mos6502::Mos6502;
-use crate::qop::Qop;
-use crate::rig::Rig;
-use crate::skim::Skim;
-use crate::subleq::{Rsubleq4, Subleq};
-use crate::substrate::Substrate;
+use crate::organism::Organism;
+use crate::substrate::{CLASS_NOP, Halt, NUM_CLASSES, Substrate};
 use crate::surface::{
-    InteractionMode, SoupSurface, SoupSurfaceConfig, SurfaceMesh, SurfaceSpec, face_normal,
+    CellSite, DEFAULT_HEIGHTMAP_IMAGE_SCALE, InteractionMode, NeighborRadius, SoupSurface,
+    SoupSurfaceConfig, SurfaceMesh, SurfaceSpec, WATCH_LOG_CAPACITY, WatchEvent, WatchRole,
+    face_normal,
 };
-use crate::uxn::Uxn;
-use crate::z80::{I8080, Z80};
 
 const MAX_PLOT_POINTS: usize = 1000;
 
@@ -57,10 +57,15 @@ pub enum SubstrateKind {
     I8080,
     Uxn,
     Mos6502,
+    Tierra,
+    Harvard,
+    /// Two base substrates sharing a tape; the halves are chosen in
+    /// `MenuConfig::hybrid_a` / `hybrid_b`.
+    Hybrid,
 }
 
 impl SubstrateKind {
-    const ALL: [SubstrateKind; 13] = [
+    pub const ALL: [SubstrateKind; 16] = [
         SubstrateKind::Bff,
         SubstrateKind::Forth,
         SubstrateKind::Subleq,
@@ -74,6 +79,9 @@ impl SubstrateKind {
         SubstrateKind::I8080,
         SubstrateKind::Uxn,
         SubstrateKind::Mos6502,
+        SubstrateKind::Tierra,
+        SubstrateKind::Harvard,
+        SubstrateKind::Hybrid,
     ];
 
     fn label(self) -> &'static str {
@@ -91,9 +99,39 @@ impl SubstrateKind {
             SubstrateKind::I8080 => "8080",
             SubstrateKind::Uxn => "Uxn",
             SubstrateKind::Mos6502 => "6502",
+            SubstrateKind::Tierra => "Tierra",
+            SubstrateKind::Harvard => "Harvard",
+            SubstrateKind::Hybrid => "Hybrid",
         }
     }
 
+    /// Lowercase command-line name (e.g. `bff`, `rsubleq4`, `6502`), from
+    /// [`SUBSTRATES`].
+    pub fn name(self) -> &'static str {
+        SUBSTRATES
+            .iter()
+            .find(|&&(_, kind)| kind == self)
+            .map(|&(name, _)| name)
+            .expect("every substrate is registered")
+    }
+
+    /// Look up a substrate by its command-line name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        SUBSTRATES
+            .iter()
+            .find(|&&(key, _)| key == name)
+            .map(|&(_, kind)| kind)
+    }
+
+    /// [`SubstrateKind::from_name`], with an error listing the valid names.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = SUBSTRATES.iter().map(|&(name, _)| name).collect();
+            format!("Unknown substrate '{name}' (expected {})", names.join(", "))
+        })
+    }
+
     /// Paper-recommended program size for this substrate.
     fn default_program_size(self) -> usize {
         match self {
@@ -102,15 +140,103 @@ impl SubstrateKind {
             _ => 64,
         }
     }
+}
 
-    /// Paper-recommended step limit for this substrate.
-    fn default_step_limit(self) -> usize {
-        match self {
-            // Section 3.3: Z80/8080 use 256 steps.
-            SubstrateKind::Z80 | SubstrateKind::I8080 => 256,
-            _ => 1 << 13,
+/// Every substrate under its command-line name. The CLI and the viewer
+/// both look substrates up here; [`with_base_substrate!`] maps each one to
+/// its type.
+pub const SUBSTRATES: &[(&str, SubstrateKind)] = &[
+    ("bff", SubstrateKind::Bff),
+    ("forth", SubstrateKind::Forth),
+    ("subleq", SubstrateKind::Subleq),
+    ("rsubleq4", SubstrateKind::Rsubleq4),
+    ("qop", SubstrateKind::Qop),
+    ("skim", SubstrateKind::Skim),
+    ("rig", SubstrateKind::Rig),
+    ("bits", SubstrateKind::Bits),
+    ("echo", SubstrateKind::Echo),
+    ("z80", SubstrateKind::Z80),
+    ("8080", SubstrateKind::I8080),
+    ("uxn", SubstrateKind::Uxn),
+    ("6502", SubstrateKind::Mos6502),
+    ("tierra", SubstrateKind::Tierra),
+    ("harvard", SubstrateKind::Harvard),
+    ("hybrid", SubstrateKind::Hybrid),
+];
+
+/// Evaluate `$body` with `$S` aliased to the concrete type of the base
+/// (non-hybrid) substrate `$kind`. The one place a `SubstrateKind` turns
+/// into a type; the CLI's generic dispatch and the viewer's simulation
+/// threads both go through it.
+#[macro_export]
+macro_rules! with_base_substrate {
+    ($kind:expr, $S:ident => $body:expr) => {
+        match $kind {
+            $crate::viz::SubstrateKind::Bff => {
+                type $S = $crate::bff::Bff;
+                $body
+            }
+            $crate::viz::SubstrateKind::Forth => {
+                type $S = $crate::forth::Forth;
+                $body
+            }
+            $crate::viz::SubstrateKind::Subleq => {
+                type $S = $crate::subleq::Subleq;
+                $body
+            }
+            $crate::viz::SubstrateKind::Rsubleq4 => {
+                type $S = $crate::subleq::Rsubleq4;
+                $body
+            }
+            $crate::viz::SubstrateKind::Qop => {
+                type $S = $crate::qop::Qop;
+                $body
+            }
+            $crate::viz::SubstrateKind::Skim => {
+                type $S = $crate::skim::Skim;
+                $body
+            }
+            $crate::viz::SubstrateKind::Rig => {
+                type $S = $crate::rig::Rig;
+                $body
+            }
+            $crate::viz::SubstrateKind::Bits => {
+                type $S = $crate::bits::Bits;
+                $body
+            }
+            $crate::viz::SubstrateKind::Echo => {
+                type $S = $crate::echo::Echo;
+                $body
+            }
+            $crate::viz::SubstrateKind::Z80 => {
+                type $S = $crate::z80::Z80;
+                $body
+            }
+            $crate::viz::SubstrateKind::I8080 => {
+                type $S = $crate::z80::I8080;
+                $body
+            }
+            $crate::viz::SubstrateKind::Uxn => {
+                type $S = $crate::uxn::Uxn;
+                $body
+            }
+            $crate::viz::SubstrateKind::Mos6502 => {
+                type $S = $crate::mos6502::Mos6502;
+                $body
+            }
+            $crate::viz::SubstrateKind::Tierra => {
+                type $S = $crate::tierra::Tierra;
+                $body
+            }
+            $crate::viz::SubstrateKind::Harvard => {
+                type $S = $crate::harvard::Harvard;
+                $body
+            }
+            $crate::viz::SubstrateKind::Hybrid => {
+                unreachable!("hybrid halves must be base substrates")
+            }
         }
-    }
+    };
 }
 
 /// Available color modes for surface visualization.
@@ -123,10 +249,17 @@ pub enum ColorMode {
     InstructionDensity,
     UniqueBytes,
     TerritorialDominance,
+    OpcodeClass,
+    /// Cells whose program changed recently glow, fading over epochs.
+    TemporalGlow,
+    /// Why each cell's program stopped the last time it ran first.
+    TerminationCause,
+    /// Epochs since an interaction last changed each cell's program.
+    ProgramAge,
 }
 
 impl ColorMode {
-    const ALL: [ColorMode; 7] = [
+    const ALL: [ColorMode; 11] = [
         ColorMode::Hash,
         ColorMode::Entropy,
         ColorMode::Zeros,
@@ -134,6 +267,10 @@ impl ColorMode {
         ColorMode::InstructionDensity,
         ColorMode::UniqueBytes,
         ColorMode::TerritorialDominance,
+        ColorMode::OpcodeClass,
+        ColorMode::TemporalGlow,
+        ColorMode::TerminationCause,
+        ColorMode::ProgramAge,
     ];
 
     fn label(self) -> &'static str {
@@ -145,6 +282,60 @@ impl ColorMode {
             ColorMode::InstructionDensity => "Instruction Density",
             ColorMode::UniqueBytes => "Unique Bytes",
             ColorMode::TerritorialDominance => "Territorial Dominance",
+            ColorMode::OpcodeClass => "Opcode Class",
+            ColorMode::TemporalGlow => "Temporal Glow",
+            ColorMode::TerminationCause => "Termination Cause",
+            ColorMode::ProgramAge => "Program Age",
+        }
+    }
+
+    /// Name accepted by `--color-mode`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorMode::Hash => "hash",
+            ColorMode::Entropy => "entropy",
+            ColorMode::Zeros => "zeros",
+            ColorMode::NeighborSimilarity => "neighbor-similarity",
+            ColorMode::InstructionDensity => "instruction-density",
+            ColorMode::UniqueBytes => "unique-bytes",
+            ColorMode::TerritorialDominance => "territorial-dominance",
+            ColorMode::OpcodeClass => "opcode-class",
+            ColorMode::TemporalGlow => "temporal-glow",
+            ColorMode::TerminationCause => "termination-cause",
+            ColorMode::ProgramAge => "program-age",
+        }
+    }
+
+    /// Look a mode up by [`ColorMode::name`] or by its menu label, ignoring
+    /// case, with an error listing the valid names.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| {
+                mode.name().eq_ignore_ascii_case(s) || mode.label().eq_ignore_ascii_case(s)
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|mode| mode.name()).collect();
+                format!("Unknown color mode '{s}' (expected {})", names.join(", "))
+            })
+    }
+}
+
+/// Which cells the viewer's blur averages over.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlurMode {
+    /// Cells sharing an edge, weighted equally.
+    #[default]
+    Adjacency,
+    /// The geodesic neighbor table, nearer neighbors weighted more.
+    GeodesicWeighted,
+}
+
+impl BlurMode {
+    fn label(self) -> &'static str {
+        match self {
+            BlurMode::Adjacency => "Adjacent",
+            BlurMode::GeodesicWeighted => "Geodesic",
         }
     }
 }
@@ -153,6 +344,7 @@ impl ColorMode {
 #[derive(Clone)]
 pub enum SurfaceShape {
     Sphere { subdivisions: usize },
+    CubeSphere { subdivisions: usize },
     Torus { major: usize, minor: usize },
     FlatGrid { width: usize, height: usize },
     HamsterTunnel { num_spheres: usize, segments: usize },
@@ -160,7 +352,9 @@ pub enum SurfaceShape {
     KleinBottle { u_segments: usize, v_segments: usize },
     Heightmap { width: usize, height: usize },
     TrefoilKnot { rings: usize, segments: usize },
-    ObjFile { path: String },
+    Grid3d { width: usize, height: usize, depth: usize, periodic: bool },
+    ObjFile { path: String, normalize: bool, drop_degenerate: bool },
+    HeightmapImage { path: String, scale: f32 },
 }
 
 impl Default for SurfaceShape {
@@ -174,7 +368,8 @@ impl Default for SurfaceShape {
 pub struct SurfaceParams {
     pub shape: SurfaceShape,
     pub seed: u64,
-    pub neighbor_radius: Option<f32>,
+    pub neighbor_radius: Option<NeighborRadius>,
+    pub cells: CellSite,
     pub last_error: Option<String>,
 }
 
@@ -184,17 +379,25 @@ impl Default for SurfaceParams {
             shape: SurfaceShape::default(),
             seed: 42,
             neighbor_radius: None,
+            cells: CellSite::Face,
             last_error: None,
         }
     }
 }
 
 impl SurfaceParams {
-    pub fn from_spec(spec: &SurfaceSpec, seed: u64, neighbor_radius: Option<f32>) -> Self {
+    pub fn from_spec(
+        spec: &SurfaceSpec,
+        seed: u64,
+        neighbor_radius: Option<NeighborRadius>,
+    ) -> Self {
         let shape = match spec {
             SurfaceSpec::Sphere { subdivisions } => SurfaceShape::Sphere {
                 subdivisions: *subdivisions,
             },
+            SurfaceSpec::CubeSphere { subdivisions } => SurfaceShape::CubeSphere {
+                subdivisions: *subdivisions,
+            },
             SurfaceSpec::Torus { major, minor } => SurfaceShape::Torus {
                 major: *major,
                 minor: *minor,
@@ -230,12 +433,36 @@ impl SurfaceParams {
                 rings: *rings,
                 segments: *segments,
             },
-            SurfaceSpec::ObjFile { path } => SurfaceShape::ObjFile { path: path.clone() },
+            SurfaceSpec::Grid3d {
+                width,
+                height,
+                depth,
+                periodic,
+            } => SurfaceShape::Grid3d {
+                width: *width,
+                height: *height,
+                depth: *depth,
+                periodic: *periodic,
+            },
+            SurfaceSpec::ObjFile {
+                path,
+                normalize,
+                drop_degenerate,
+            } => SurfaceShape::ObjFile {
+                path: path.clone(),
+                normalize: *normalize,
+                drop_degenerate: *drop_degenerate,
+            },
+            SurfaceSpec::HeightmapImage { path, scale } => SurfaceShape::HeightmapImage {
+                path: path.clone(),
+                scale: *scale,
+            },
         };
         Self {
             shape,
             seed,
             neighbor_radius,
+            cells: CellSite::Face,
             last_error: None,
         }
     }
@@ -245,6 +472,9 @@ impl SurfaceParams {
             SurfaceShape::Sphere { subdivisions } => SurfaceSpec::Sphere {
                 subdivisions: *subdivisions,
             },
+            SurfaceShape::CubeSphere { subdivisions } => SurfaceSpec::CubeSphere {
+                subdivisions: *subdivisions,
+            },
             SurfaceShape::Torus { major, minor } => SurfaceSpec::Torus {
                 major: *major,
                 minor: *minor,
@@ -281,7 +511,30 @@ impl SurfaceParams {
                 rings: *rings,
                 segments: *segments,
             },
-            SurfaceShape::ObjFile { path } => SurfaceSpec::ObjFile { path: path.clone() },
+            SurfaceShape::Grid3d {
+                width,
+                height,
+                depth,
+                periodic,
+            } => SurfaceSpec::Grid3d {
+                width: *width,
+                height: *height,
+                depth: *depth,
+                periodic: *periodic,
+            },
+            SurfaceShape::ObjFile {
+                path,
+                normalize,
+                drop_degenerate,
+            } => SurfaceSpec::ObjFile {
+                path: path.clone(),
+                normalize: *normalize,
+                drop_degenerate: *drop_degenerate,
+            },
+            SurfaceShape::HeightmapImage { path, scale } => SurfaceSpec::HeightmapImage {
+                path: path.clone(),
+                scale: *scale,
+            },
         }
     }
 }
@@ -290,7 +543,14 @@ impl SurfaceParams {
 #[derive(Resource)]
 pub struct MenuConfig {
     pub substrate: SubstrateKind,
+    /// Instruction set for the first half of each tape under `Hybrid`.
+    pub hybrid_a: SubstrateKind,
+    /// Instruction set for the second half of each tape under `Hybrid`.
+    pub hybrid_b: SubstrateKind,
     pub surface: SurfaceParams,
+    /// Further surfaces run beside `surface`, each on its own sim thread
+    /// with the same substrate, seed, and soup settings.
+    pub extra_surfaces: Vec<SurfaceSpec>,
     pub program_size: usize,
     pub step_limit: usize,
     pub mutation_rate: f64,
@@ -299,13 +559,23 @@ pub struct MenuConfig {
     pub color_mode: ColorMode,
     pub blur: f32,
     pub interaction_mode: InteractionMode,
+    /// Draw and pick both sides of every face, lighting back faces with the
+    /// flipped normal. Needed for non-orientable surfaces and OBJs with
+    /// inconsistent winding; off by default so orientable meshes keep
+    /// back-face culling.
+    pub double_sided: bool,
+    /// Shade with averaged vertex normals instead of one normal per face.
+    pub smooth_normals: bool,
 }
 
 impl Default for MenuConfig {
     fn default() -> Self {
         Self {
             substrate: SubstrateKind::Bff,
+            hybrid_a: SubstrateKind::Forth,
+            hybrid_b: SubstrateKind::Bff,
             surface: SurfaceParams::default(),
+            extra_surfaces: Vec::new(),
             program_size: 64,
             step_limit: 1 << 13,
             mutation_rate: 0.00024,
@@ -314,36 +584,78 @@ impl Default for MenuConfig {
             color_mode: ColorMode::Hash,
             blur: 0.0,
             interaction_mode: InteractionMode::Normal,
+            double_sided: false,
+            smooth_normals: false,
         }
     }
 }
 
 impl MenuConfig {
+    /// The selected substrate's `Substrate::recommended_step_limit` for the
+    /// configured program size.
+    pub fn recommended_step_limit(&self) -> usize {
+        let ps = self.program_size;
+        match self.substrate {
+            SubstrateKind::Hybrid => with_base_substrate!(self.hybrid_a, A => {
+                with_base_substrate!(self.hybrid_b, B => Hybrid::<A, B>::recommended_step_limit(ps))
+            }),
+            kind => with_base_substrate!(kind, S => S::recommended_step_limit(ps)),
+        }
+    }
+
+    /// Soup settings the simulation starts with.
+    fn soup_config(&self) -> SoupSurfaceConfig {
+        SoupSurfaceConfig {
+            program_size: self.program_size,
+            step_limit: self.step_limit,
+            mutation_rate: self.mutation_rate,
+            interaction_mode: self.interaction_mode,
+            ..SoupSurfaceConfig::default()
+        }
+    }
+
+    /// Menu state pre-filled from command-line options. `specs` lists the
+    /// surfaces to run side by side; the first is the one the menu edits.
+    /// `step_limit` of `None` uses the substrate's recommendation.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         substrate: SubstrateKind,
-        spec: &SurfaceSpec,
+        specs: &[SurfaceSpec],
         seed: u64,
-        neighbor_radius: Option<f32>,
+        neighbor_radius: Option<NeighborRadius>,
+        cells: CellSite,
         program_size: usize,
-        step_limit: usize,
+        step_limit: Option<usize>,
         mutation_rate: f64,
         max_epochs: usize,
         metrics_interval: usize,
         blur: f32,
+        color_mode: ColorMode,
+        double_sided: bool,
+        smooth_normals: bool,
     ) -> Self {
-        Self {
+        let mut config = Self {
             substrate,
-            surface: SurfaceParams::from_spec(spec, seed, neighbor_radius),
+            hybrid_a: SubstrateKind::Forth,
+            hybrid_b: SubstrateKind::Bff,
+            surface: SurfaceParams {
+                cells,
+                ..SurfaceParams::from_spec(&specs[0], seed, neighbor_radius)
+            },
+            extra_surfaces: specs[1..].to_vec(),
             program_size,
-            step_limit,
+            step_limit: 0,
             mutation_rate,
             max_epochs,
             metrics_interval,
-            color_mode: ColorMode::Hash,
+            color_mode,
             blur,
             interaction_mode: InteractionMode::Normal,
-        }
+            double_sided,
+            smooth_normals,
+        };
+        config.step_limit = step_limit.unwrap_or_else(|| config.recommended_step_limit());
+        config
     }
 }
 
@@ -357,33 +669,74 @@ pub struct EpochMetrics {
     pub unique_count: usize,
     pub zero_count: usize,
     pub byte_histogram: [usize; 256],
+    /// Chi-square of `byte_histogram` against uniform bytes.
+    pub chi_square: f64,
+    /// Share of cells flagged by [`parasite_fraction`] since the previous
+    /// snapshot.
+    pub parasite_fraction: f64,
+    /// [`spectral_flatness`](crate::metrics::spectral_flatness) of the
+    /// population; `None` unless built with the `spectral` feature.
+    pub spectral_flatness: Option<f64>,
+    /// [`active_fraction`](crate::metrics::active_fraction) of the most
+    /// common program; `None` if the substrate can't report it.
+    pub active_fraction: Option<f64>,
+    /// [`mean_distance_to_consensus`] of the population from its
+    /// per-offset majority program.
+    pub consensus_distance: f64,
+    /// [`count_quines`] among the [`QUINE_SAMPLE`] most common programs.
+    pub quines: usize,
+    /// [`positional_mutual_information`] between each pair of adjacent
+    /// byte offsets.
+    pub positional_mi: Vec<f64>,
+    /// Smoothed simulation speed measured by the viewer's sim thread; zero
+    /// wherever metrics are computed outside it.
+    pub epochs_per_second: f64,
 }
 
 /// Per-cell color snapshot for surface visualization.
 #[derive(Clone)]
 pub struct SurfaceSnapshot {
     pub colors: Vec<u8>,
+    /// The watched cell's events since the previous snapshot.
+    pub watch_log: Vec<WatchEvent>,
 }
 
 /// Commands sent from render thread to sim thread.
+#[derive(Clone)]
 pub enum SimCommand {
     Play,
     Pause,
     SetColorMode(ColorMode),
     SetBlur(f32),
+    /// Blur over `mode`'s cells; `radius` is the geodesic falloff.
+    SetBlurMode {
+        mode: BlurMode,
+        radius: f32,
+    },
+    /// Replace the soup's mutation rate in effect.
+    SetMutationRate(f64),
+    /// Start over with a fresh soup; a `mesh` of `None` keeps the current
+    /// one.
     ResetSurface {
-        mesh: SurfaceMesh,
-        config: SoupSurfaceConfig,
+        mesh: Option<Box<SurfaceMesh>>,
+        config: Box<SoupSurfaceConfig>,
         seed: u64,
     },
     RequestProgram(usize),
+    /// Log the given cell's interactions, or stop with `None`.
+    Watch(Option<usize>),
+    /// Send a snapshot every this many epochs instead of on the
+    /// wall-clock timer; 0 goes back to the timer.
+    SetSnapshotEvery(usize),
 }
 
-/// Response carrying a cell's program bytes and disassembly.
+/// Response carrying a cell's program bytes, disassembly, and the cells it
+/// interacts with.
 pub struct ProgramResponse {
     cell: usize,
     bytes: Vec<u8>,
     disassembly: String,
+    neighbors: Vec<usize>,
 }
 
 // ─── Marker components ──────────────────────────────────────────────────────
@@ -394,51 +747,154 @@ struct SimEntity;
 #[derive(Component)]
 struct MenuEntity;
 
+/// Which of the simulated surfaces a mesh entity draws.
+#[derive(Component)]
+struct SurfaceIndex(usize);
+
 // ─── Sim-only resources ──────────────────────────────────────────────────────
 
+/// Metrics from each surface's sim thread. Only the first surface's are
+/// plotted; the rest are drained so their threads keep running.
 #[derive(Resource)]
-struct SimReceiver(Mutex<mpsc::Receiver<EpochMetrics>>);
+struct SimReceiver(Vec<Mutex<mpsc::Receiver<EpochMetrics>>>);
 
+/// Command channel to each surface's sim thread.
 #[derive(Resource)]
-struct SimCommander(mpsc::Sender<SimCommand>);
+struct SimCommander(Vec<mpsc::Sender<SimCommand>>);
+
+impl SimCommander {
+    /// Send `cmd` to every sim thread.
+    fn broadcast(&self, cmd: SimCommand) {
+        for tx in &self.0 {
+            let _ = tx.send(cmd.clone());
+        }
+    }
+}
 
 #[derive(Resource, Default)]
 struct SimulationHistory {
     entries: Vec<EpochMetrics>,
     awaiting_reset: bool,
+    /// Outcome of the last "Export metrics CSV" click.
+    export_status: Option<String>,
 }
 
+/// File "Export metrics CSV" writes, in the working directory.
+const METRICS_CSV: &str = "metrics.csv";
+
 #[derive(Resource)]
 struct PlaybackState {
     playing: bool,
     max_epochs: usize,
+    /// Mutation is switched off until unfrozen or the soups restart.
+    mutation_frozen: bool,
 }
 
 #[derive(Resource)]
 struct VizSettings {
     color_mode: ColorMode,
     blur: f32,
+    blur_mode: BlurMode,
+    /// Width of the geodesic blur's falloff, as a fraction of each cell's
+    /// farthest neighbor.
+    blur_radius: f32,
+    /// Keep the camera where it is when the surface is rebuilt instead of
+    /// re-framing it on the new mesh.
+    lock_camera: bool,
+    smooth_normals: bool,
+    /// Epochs between snapshots, or 0 to send them on a timer.
+    snapshot_every: usize,
 }
 
 #[derive(Resource)]
-struct SurfaceSnapshotReceiver(Mutex<mpsc::Receiver<SurfaceSnapshot>>);
+struct SurfaceSnapshotReceiver(Vec<Mutex<SnapshotReceiver>>);
 
-#[derive(Resource, Default)]
+/// Color buffers a render thread holds for recycling back to its sim
+/// thread. Buffers recycled beyond this are dropped, bounding the pool.
+const RECYCLED_SNAPSHOT_BUFFERS: usize = 3;
+
+/// Sim-thread end of a snapshot channel's pool of color buffers.
+struct BufferPool(mpsc::Receiver<Vec<u8>>);
+
+impl BufferPool {
+    /// A buffer to fill with the next snapshot's colors: one the render
+    /// thread is done with if any is waiting, otherwise a new one.
+    fn take(&self) -> Vec<u8> {
+        self.0.try_recv().unwrap_or_default()
+    }
+}
+
+/// Render-thread end of a sim thread's snapshots. The color buffers of
+/// snapshots it has replaced go back to the sim thread's [`BufferPool`],
+/// so a running soup's snapshots stop allocating once the pool fills.
+struct SnapshotReceiver {
+    snapshots: mpsc::Receiver<SurfaceSnapshot>,
+    recycled: mpsc::SyncSender<Vec<u8>>,
+}
+
+impl SnapshotReceiver {
+    /// Hand `colors` back for reuse, dropping it if the pool is full.
+    fn recycle(&self, colors: Vec<u8>) {
+        let _ = self.recycled.try_send(colors);
+    }
+}
+
+/// A snapshot channel whose color buffers circulate between its ends.
+fn snapshot_channel() -> (mpsc::Sender<SurfaceSnapshot>, BufferPool, SnapshotReceiver) {
+    let (snap_tx, snapshots) = mpsc::channel();
+    let (recycled, pool) = mpsc::sync_channel(RECYCLED_SNAPSHOT_BUFFERS);
+    let receiver = SnapshotReceiver {
+        snapshots,
+        recycled,
+    };
+    (snap_tx, BufferPool(pool), receiver)
+}
+
+#[derive(Default)]
 struct LatestSurfaceSnapshot {
     snapshot: Option<SurfaceSnapshot>,
     dirty: bool,
 }
 
+/// The most recent color snapshot of each surface.
 #[derive(Resource)]
-struct SimResources {
+struct LatestSurfaceSnapshots(Vec<LatestSurfaceSnapshot>);
+
+/// One simulated surface's render mesh.
+struct SimSurface {
     mesh_handle: Handle<Mesh>,
     num_cells: usize,
+    /// The cell whose color each render vertex takes.
+    render_cells: Vec<usize>,
+    /// How recorded frames lay the cells out.
+    frame_layout: FrameLayout,
+    flat_normals: Vec<[f32; 3]>,
+    smooth_normals: Vec<[f32; 3]>,
+}
+
+impl SimSurface {
+    fn normals(&self, smooth: bool) -> &[[f32; 3]] {
+        if smooth {
+            &self.smooth_normals
+        } else {
+            &self.flat_normals
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SimResources {
+    surfaces: Vec<SimSurface>,
     pending_rebuild: bool,
+    /// Whether the meshes currently carry smooth normals.
+    smooth_normals: bool,
 }
 
 #[derive(Resource)]
 struct SimSurfaceParams(SurfaceParams);
 
+/// Render data for the first surface, plus the bounding sphere of every
+/// surface in world space that the camera frames.
 #[derive(Resource)]
 struct SurfaceRenderData {
     positions: Vec<[f32; 3]>,
@@ -449,18 +905,102 @@ struct SurfaceRenderData {
 }
 
 #[derive(Resource)]
-struct ProgramResponseReceiver(Mutex<mpsc::Receiver<ProgramResponse>>);
+struct ProgramResponseReceiver(Vec<Mutex<mpsc::Receiver<ProgramResponse>>>);
+
+/// Overlay color blended into the selected cell's neighbors, and how far.
+const NEIGHBOR_TINT: [f32; 3] = [1.0, 0.85, 0.2];
+const NEIGHBOR_TINT_STRENGTH: f32 = 0.6;
 
 #[derive(Resource, Default)]
 struct SelectedCell {
+    /// Index of the surface the selected cell lies on.
+    surface: usize,
     cell_index: Option<usize>,
     program_bytes: Option<Vec<u8>>,
     disassembly: Option<String>,
+    /// Geodesic neighbors of the selected cell, tinted on the mesh.
+    neighbors: Vec<usize>,
+    /// A second cell, shift+clicked while one is selected, to diff against.
+    second: Option<ComparedCell>,
+    /// Note stored with the program by "Save Organism".
+    organism_note: String,
+    /// Outcome of the last "Save Organism" click.
+    organism_status: Option<String>,
+    /// The selected cell's interactions, oldest first.
+    watch_log: VecDeque<WatchEvent>,
+}
+
+/// The other side of a byte diff against the selected cell.
+struct ComparedCell {
+    surface: usize,
+    cell_index: usize,
+    program_bytes: Option<Vec<u8>>,
+    disassembly: Option<String>,
+}
+
+/// Highlight for bytes that differ between the two diffed cells.
+const DIFF_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 110, 80);
+
+/// Positions where `a` and `b` hold different bytes, compared up to the
+/// shorter of the two.
+fn diff_positions(a: &[u8], b: &[u8]) -> Vec<usize> {
+    a.iter()
+        .zip(b)
+        .enumerate()
+        .filter(|(_, (x, y))| x != y)
+        .map(|(i, _)| i)
+        .collect()
 }
 
 #[derive(Resource, Default)]
 struct ShowHelp(bool);
 
+/// Whether the FPS and epochs/sec overlay is shown; toggled with F3.
+#[derive(Resource, Default)]
+struct ShowPerfOverlay(bool);
+
+/// Weight of the newest sample in the sim thread's epochs/sec average.
+const RATE_SMOOTHING: f64 = 0.3;
+
+/// Fold `sample` into the exponential moving average `average`. An
+/// average of zero means no samples yet, so the first sample is taken as
+/// is rather than pulled toward zero.
+fn smooth_rate(average: f64, sample: f64, alpha: f64) -> f64 {
+    if average == 0.0 {
+        sample
+    } else {
+        average + alpha * (sample - average)
+    }
+}
+
+/// Wall-clock time between snapshots a sim thread sends when it isn't
+/// sending every K epochs.
+const SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// Whether the sim thread sends a snapshot after `epoch`: every `every`
+/// epochs when that is nonzero, so which epochs are drawn doesn't depend
+/// on machine speed, and otherwise once `since_last` reaches
+/// [`SNAPSHOT_INTERVAL`]. The final epoch is always sent.
+fn should_send_snapshot(
+    epoch: usize,
+    max_epochs: usize,
+    every: usize,
+    since_last: std::time::Duration,
+) -> bool {
+    if epoch == max_epochs {
+        return true;
+    }
+    match every {
+        0 => since_last >= SNAPSHOT_INTERVAL,
+        every => epoch.is_multiple_of(every),
+    }
+}
+
+/// Screen rect of the simulation side panel (egui points) from the last
+/// frame, so camera input can be suppressed while the cursor is over it.
+#[derive(Resource, Default)]
+struct SidePanelRect(Option<egui::Rect>);
+
 #[derive(Component)]
 struct OrbitCamera {
     focus: Vec3,
@@ -471,6 +1011,13 @@ struct OrbitCamera {
 
 // ─── Color / heatmap / blur helpers ──────────────────────────────────────────
 
+/// Round a 0–255 channel intensity to the nearest byte. Truncating with a
+/// bare `as u8` would turn a product like `0.8 * 255.0` that lands a hair
+/// under 204 into 203, so colors would hinge on the last bit of float math.
+fn channel(v: f32) -> u8 {
+    v.clamp(0.0, 255.0).round() as u8
+}
+
 #[inline]
 fn push_rgba(colors: &mut Vec<u8>, r: u8, g: u8, b: u8) {
     colors.push(r);
@@ -527,8 +1074,71 @@ fn fill_colors_zeros(programs: &[Vec<u8>], colors: &mut Vec<u8>) {
     for prog in programs {
         let zero_count = prog.iter().filter(|&&b| b == 0).count();
         let t = zero_count as f32 / prog.len() as f32;
-        let brightness = ((1.0 - t) * 255.0) as u8;
-        push_rgba(colors, brightness, brightness, brightness);
+        let [r, g, b] = grayscale_inverted(t);
+        push_rgba(colors, r, g, b);
+    }
+}
+
+/// How much of a cell's temporal glow survives each epoch.
+const GLOW_DECAY: f32 = 0.9;
+/// Glow added in an epoch that changes a cell's program.
+const GLOW_GAIN: f32 = 0.5;
+
+/// Fade every cell's glow by one epoch, then brighten the cells whose
+/// program differs between `before` and `after`.
+pub(crate) fn accumulate_glow(glow: &mut [f32], before: &[Vec<u8>], after: &[Vec<u8>]) {
+    for ((g, before), after) in glow.iter_mut().zip(before).zip(after) {
+        *g *= GLOW_DECAY;
+        if before != after {
+            *g = (*g + GLOW_GAIN).min(1.0);
+        }
+    }
+}
+
+fn fill_colors_glow(glow: &[f32], colors: &mut Vec<u8>) {
+    colors.clear();
+    for &g in glow {
+        let [r, gr, b] = heatmap(g);
+        push_rgba(colors, r, gr, b);
+    }
+}
+
+/// Age at which the Program Age mode's heatmap tops out.
+const AGE_CAP: u32 = 100;
+
+/// Heatmap of each cell's program age, saturating at [`AGE_CAP`] epochs.
+fn fill_colors_age(ages: &[u32], colors: &mut Vec<u8>) {
+    colors.clear();
+    for &age in ages {
+        let [r, g, b] = heatmap(age.min(AGE_CAP) as f32 / AGE_CAP as f32);
+        push_rgba(colors, r, g, b);
+    }
+}
+
+/// Color of a cell whose program last stopped for `halt`, or that hasn't
+/// run first in a pair yet.
+fn termination_color(halt: Option<Halt>) -> [u8; 3] {
+    match halt {
+        Some(Halt::Halted) => [60, 170, 90],
+        Some(Halt::OffTape) => [70, 130, 230],
+        Some(Halt::StepLimit) => [230, 80, 60],
+        None => [40, 40, 40],
+    }
+}
+
+/// Legend entries for [`termination_color`].
+const TERMINATION_LEGEND: [(&str, Option<Halt>); 4] = [
+    ("halted", Some(Halt::Halted)),
+    ("ran off the tape", Some(Halt::OffTape)),
+    ("hit the step limit", Some(Halt::StepLimit)),
+    ("not run yet", None),
+];
+
+fn fill_colors_termination(halts: &[Option<Halt>], colors: &mut Vec<u8>) {
+    colors.clear();
+    for &halt in halts {
+        let [r, g, b] = termination_color(halt);
+        push_rgba(colors, r, g, b);
     }
 }
 
@@ -581,6 +1191,47 @@ fn fill_colors_instruction_density(
     }
 }
 
+/// One color per `Substrate::instruction_class`, indexed by class id.
+const OPCODE_CLASS_PALETTE: [[u8; 3]; NUM_CLASSES] = [
+    [40, 40, 40],    // no-op
+    [160, 160, 160], // other
+    [230, 80, 60],   // arithmetic
+    [240, 200, 50],  // data
+    [60, 170, 90],   // memory
+    [70, 130, 230],  // pointer
+    [180, 90, 210],  // control
+];
+
+/// Legend names for [`OPCODE_CLASS_PALETTE`], indexed by class id.
+const OPCODE_CLASS_NAMES: [&str; NUM_CLASSES] = [
+    "no-op",
+    "other",
+    "arithmetic",
+    "data",
+    "memory",
+    "pointer",
+    "control",
+];
+
+/// Color each cell by the class of its first instruction byte; cells with
+/// no instruction at all get the no-op color.
+fn fill_colors_opcode_class(
+    programs: &[Vec<u8>],
+    is_instruction: fn(u8) -> bool,
+    instruction_class: fn(u8) -> u8,
+    colors: &mut Vec<u8>,
+) {
+    colors.clear();
+    for prog in programs {
+        let class = prog
+            .iter()
+            .find(|&&b| is_instruction(b))
+            .map_or(CLASS_NOP, |&b| instruction_class(b));
+        let [r, g, b] = OPCODE_CLASS_PALETTE[(class as usize).min(NUM_CLASSES - 1)];
+        push_rgba(colors, r, g, b);
+    }
+}
+
 fn fill_colors_unique_bytes(programs: &[Vec<u8>], colors: &mut Vec<u8>) {
     colors.clear();
     for prog in programs {
@@ -637,16 +1288,79 @@ fn heatmap(t: f32) -> [u8; 3] {
         let s = (t - 0.75) / 0.25;
         (1.0, 1.0 - s, 0.0)
     };
-    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+    [channel(r * 255.0), channel(g * 255.0), channel(b * 255.0)]
+}
+
+/// White at 0 to black at 1, as in the Zero Fraction mode.
+fn grayscale_inverted(t: f32) -> [u8; 3] {
+    let v = channel((1.0 - t.clamp(0.0, 1.0)) * 255.0);
+    [v, v, v]
+}
+
+/// Labeled ticks under a legend gradient.
+const LEGEND_TICKS: usize = 5;
+
+/// What a gradient color mode encodes, for the legend strip.
+struct LegendScale {
+    /// The per-cell quantity the colors show.
+    quantity: &'static str,
+    /// Values of `quantity` at the left (t = 0) and right (t = 1) ends of
+    /// `gradient`; every mode's scale is absolute, not fit to the frame.
+    left: f32,
+    right: f32,
+    gradient: fn(f32) -> [u8; 3],
+}
+
+impl ColorMode {
+    /// Legend scale for modes that map one value onto a gradient; `None`
+    /// for the categorical modes.
+    fn legend_scale(self) -> Option<LegendScale> {
+        let (quantity, left, right, gradient): (_, _, _, fn(f32) -> [u8; 3]) = match self {
+            ColorMode::Hash | ColorMode::OpcodeClass | ColorMode::TerminationCause => {
+                return None;
+            }
+            ColorMode::Entropy => ("Byte entropy (normalized)", 0.0, 1.0, heatmap),
+            ColorMode::Zeros => ("Zero-byte fraction", 0.0, 1.0, grayscale_inverted),
+            ColorMode::NeighborSimilarity => ("Bitwise similarity to neighbors", 0.0, 1.0, heatmap),
+            ColorMode::InstructionDensity => ("Instruction-byte fraction", 0.0, 1.0, heatmap),
+            ColorMode::UniqueBytes => ("Distinct-byte fraction", 1.0, 0.0, heatmap),
+            ColorMode::TerritorialDominance => ("Identical-neighbor fraction", 0.0, 1.0, heatmap),
+            ColorMode::TemporalGlow => ("Recent program change", 0.0, 1.0, heatmap),
+            ColorMode::ProgramAge => ("Epochs unchanged", 0.0, AGE_CAP as f32, heatmap),
+        };
+        Some(LegendScale {
+            quantity,
+            left,
+            right,
+            gradient,
+        })
+    }
 }
 
-fn fill_colors_for_mode<S: Substrate>(
+/// `count` evenly spaced `(t, label)` ticks along `scale`'s gradient.
+fn legend_ticks(scale: &LegendScale, count: usize) -> Vec<(f32, String)> {
+    (0..count)
+        .map(|i| {
+            let t = if count > 1 {
+                i as f32 / (count - 1) as f32
+            } else {
+                0.0
+            };
+            let value = scale.left + (scale.right - scale.left) * t;
+            (t, format!("{value:.2}"))
+        })
+        .collect()
+}
+
+pub(crate) fn fill_colors_for_mode<S: Substrate>(
     mode: ColorMode,
-    programs: &[Vec<u8>],
-    neighbor_indices: &[usize],
-    neighbor_ranges: &[(usize, usize)],
+    soup: &SoupSurface,
+    glow: &[f32],
     colors: &mut Vec<u8>,
 ) {
+    let programs = &soup.programs;
+    let (neighbor_indices, neighbor_ranges) =
+        (&soup.mesh.neighbor_indices, &soup.mesh.neighbor_ranges);
     match mode {
         ColorMode::Hash => fill_colors_hash(programs, colors),
         ColorMode::Entropy => fill_colors_entropy(programs, colors),
@@ -661,7 +1375,86 @@ fn fill_colors_for_mode<S: Substrate>(
         ColorMode::TerritorialDominance => {
             fill_colors_territorial_dominance(programs, neighbor_indices, neighbor_ranges, colors)
         }
+        ColorMode::OpcodeClass => {
+            fill_colors_opcode_class(programs, S::is_instruction, S::instruction_class, colors)
+        }
+        ColorMode::TemporalGlow => fill_colors_glow(glow, colors),
+        ColorMode::TerminationCause => fill_colors_termination(soup.halts(), colors),
+        ColorMode::ProgramAge => fill_colors_age(soup.ages(), colors),
+    }
+}
+
+/// Falloff the geodesic blur starts with.
+const DEFAULT_BLUR_RADIUS: f32 = 0.5;
+
+/// Blur `colors` by `alpha` over the cells `mode` selects: `cell_adjacency`,
+/// or `mesh`'s neighbor table with falloff `radius`.
+fn blur_colors(
+    colors: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    cell_adjacency: &[Vec<usize>],
+    mesh: &SurfaceMesh,
+    mode: BlurMode,
+    radius: f32,
+    alpha: f32,
+) {
+    match mode {
+        BlurMode::Adjacency => blur_surface_colors(colors, scratch, cell_adjacency, alpha),
+        BlurMode::GeodesicWeighted => {
+            blur_surface_colors_weighted(colors, scratch, mesh, radius, alpha)
+        }
+    }
+}
+
+/// Blend each cell toward a Gaussian-weighted average of its geodesic
+/// neighbors: one at distance `d` weighs `exp(-d² / 2σ²)`, where σ is
+/// `radius` times the cell's farthest neighbor. A zero radius, or one so
+/// small every weight underflows to zero, leaves the colors unchanged.
+fn blur_surface_colors_weighted(
+    colors: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    mesh: &SurfaceMesh,
+    radius: f32,
+    alpha: f32,
+) {
+    if alpha <= 0.0 || radius <= 0.0 {
+        return;
+    }
+    let alpha = alpha.min(1.0);
+    let num_cells = mesh.num_cells();
+    scratch.resize(num_cells * 4, 0);
+
+    for i in 0..num_cells {
+        let idx = i * 4;
+        let distances = mesh.neighbor_distances_of(i);
+        let reach = distances.iter().copied().fold(0.0f32, f32::max);
+        let sigma = radius * reach;
+        if sigma <= 0.0 {
+            scratch[idx..idx + 4].copy_from_slice(&colors[idx..idx + 4]);
+            continue;
+        }
+
+        let mut sum = [0.0f32; 3];
+        let mut total = 0.0f32;
+        for (&j, &d) in mesh.neighbors_of(i).iter().zip(distances) {
+            let weight = (-d * d / (2.0 * sigma * sigma)).exp();
+            for (c, s) in sum.iter_mut().enumerate() {
+                *s += weight * colors[j * 4 + c] as f32;
+            }
+            total += weight;
+        }
+        if total <= 0.0 {
+            scratch[idx..idx + 4].copy_from_slice(&colors[idx..idx + 4]);
+            continue;
+        }
+        for c in 0..3 {
+            let center = colors[idx + c] as f32;
+            scratch[idx + c] = channel((1.0 - alpha) * center + alpha * sum[c] / total);
+        }
+        scratch[idx + 3] = 255;
     }
+
+    std::mem::swap(colors, scratch);
 }
 
 fn blur_surface_colors(
@@ -705,9 +1498,9 @@ fn blur_surface_colors(
         let avg_g = sum_g as f32 / count;
         let avg_b = sum_b as f32 / count;
 
-        scratch[idx] = (one_minus_alpha * center_r + alpha * avg_r) as u8;
-        scratch[idx + 1] = (one_minus_alpha * center_g + alpha * avg_g) as u8;
-        scratch[idx + 2] = (one_minus_alpha * center_b + alpha * avg_b) as u8;
+        scratch[idx] = channel(one_minus_alpha * center_r + alpha * avg_r);
+        scratch[idx + 1] = channel(one_minus_alpha * center_g + alpha * avg_g);
+        scratch[idx + 2] = channel(one_minus_alpha * center_b + alpha * avg_b);
         scratch[idx + 3] = 255;
     }
 
@@ -716,8 +1509,10 @@ fn blur_surface_colors(
 
 // ─── Spawn sim thread (runtime substrate dispatch) ───────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_sim_thread(
     kind: SubstrateKind,
+    hybrid: (SubstrateKind, SubstrateKind),
     mesh: SurfaceMesh,
     config: SoupSurfaceConfig,
     seed: u64,
@@ -726,55 +1521,21 @@ fn spawn_sim_thread(
     blur: f32,
 ) -> (
     mpsc::Receiver<EpochMetrics>,
-    mpsc::Receiver<SurfaceSnapshot>,
+    SnapshotReceiver,
     mpsc::Sender<SimCommand>,
     mpsc::Receiver<ProgramResponse>,
 ) {
     let (metrics_tx, metrics_rx) = mpsc::channel();
-    let (snap_tx, snap_rx) = mpsc::channel();
+    let (snap_tx, pool, snap_rx) = snapshot_channel();
     let (cmd_tx, cmd_rx) = mpsc::channel();
     let (prog_tx, prog_rx) = mpsc::channel();
 
-    let face_adjacency = mesh.face_adjacency.clone();
+    let cell_adjacency = mesh.cell_adjacency().to_vec();
 
-    match kind {
-        SubstrateKind::Bff => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Bff>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Forth => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Forth>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Subleq => {
+    macro_rules! spawn {
+        ($S:ty) => {
             thread::spawn(move || {
-                sim_thread_loop_surface::<Subleq>(
+                sim_thread_loop_surface::<$S>(
                     mesh,
                     config,
                     seed,
@@ -782,184 +1543,22 @@ fn spawn_sim_thread(
                     metrics_interval,
                     metrics_tx,
                     snap_tx,
+                    pool,
                     cmd_rx,
-                    face_adjacency,
+                    cell_adjacency,
                     blur,
                     prog_tx,
                 );
-            });
+            })
+        };
+    }
+    match kind {
+        SubstrateKind::Hybrid => {
+            let (a, b) = hybrid;
+            with_base_substrate!(a, A => with_base_substrate!(b, B => spawn!(Hybrid<A, B>)))
         }
-        SubstrateKind::Rsubleq4 => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Rsubleq4>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Qop => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Qop>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Skim => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Skim>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Rig => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Rig>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Bits => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Bits>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Echo => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Echo>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Z80 => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Z80>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::I8080 => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<I8080>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Uxn => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Uxn>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-        SubstrateKind::Mos6502 => {
-            thread::spawn(move || {
-                sim_thread_loop_surface::<Mos6502>(
-                    mesh,
-                    config,
-                    seed,
-                    max_epochs,
-                    metrics_interval,
-                    metrics_tx,
-                    snap_tx,
-                    cmd_rx,
-                    face_adjacency,
-                    blur,
-                    prog_tx,
-                );
-            });
-        }
-    }
+        kind => with_base_substrate!(kind, S => spawn!(S)),
+    };
 
     (metrics_rx, snap_rx, cmd_tx, prog_rx)
 }
@@ -975,46 +1574,67 @@ fn sim_thread_loop_surface<S: Substrate + Sync>(
     metrics_interval: usize,
     metrics_tx: mpsc::Sender<EpochMetrics>,
     snap_tx: mpsc::Sender<SurfaceSnapshot>,
+    pool: BufferPool,
     cmd_rx: mpsc::Receiver<SimCommand>,
-    mut face_adjacency: Vec<Vec<usize>>,
+    mut cell_adjacency: Vec<Vec<usize>>,
     blur: f32,
     prog_tx: mpsc::Sender<ProgramResponse>,
 ) {
     let mut soup = SoupSurface::new(mesh, config, seed);
+    soup.record_copy_events(true);
     let mut paused = false;
     let mut epoch = 0usize;
     let mut color_mode = ColorMode::Hash;
     let mut blur = blur;
+    let (mut blur_mode, mut blur_radius) = (BlurMode::Adjacency, DEFAULT_BLUR_RADIUS);
 
     let mut num_cells = soup.mesh.num_cells();
-    let mut color_buf: Vec<u8> = Vec::with_capacity(num_cells * 4);
     let mut blur_scratch: Vec<u8> = Vec::new();
     let mut pop_buf: Vec<u8> = Vec::new();
+    // Temporal glow: per-cell intensity, and the programs before this epoch.
+    let mut glow = vec![0.0f32; num_cells];
+    let mut before_epoch: Vec<Vec<u8>> = Vec::new();
 
     // Send initial state.
-    let _ = metrics_tx.send(compute_metrics_surface(&soup, 0, &mut pop_buf));
-    fill_colors_for_mode::<S>(
-        color_mode,
-        &soup.programs,
-        &soup.mesh.neighbor_indices,
-        &soup.mesh.neighbor_ranges,
-        &mut color_buf,
+    let _ = metrics_tx.send(compute_metrics_surface::<S>(&mut soup, 0, &mut pop_buf));
+    let mut colors = pool.take();
+    fill_colors_for_mode::<S>(color_mode, &soup, &glow, &mut colors);
+    blur_colors(
+        &mut colors,
+        &mut blur_scratch,
+        &cell_adjacency,
+        &soup.mesh,
+        blur_mode,
+        blur_radius,
+        blur,
     );
-    blur_surface_colors(&mut color_buf, &mut blur_scratch, &face_adjacency, blur);
     let _ = snap_tx.send(SurfaceSnapshot {
-        colors: color_buf.clone(),
+        colors,
+        watch_log: Vec::new(),
     });
 
-    let snap_interval = std::time::Duration::from_millis(16);
+    let mut snapshot_every = 0;
     let mut last_snap_send = std::time::Instant::now();
+    // Epochs/sec: epochs run since `rate_since`, averaged across sends.
+    let mut rate_since = (std::time::Instant::now(), 0usize);
+    let mut epochs_per_second = 0.0;
 
     loop {
         while let Ok(cmd) = cmd_rx.try_recv() {
             match cmd {
                 SimCommand::Play => paused = false,
                 SimCommand::Pause => paused = true,
-                SimCommand::SetColorMode(mode) => color_mode = mode,
+                SimCommand::SetColorMode(mode) => {
+                    if mode == ColorMode::TemporalGlow && color_mode != mode {
+                        glow.fill(0.0);
+                    }
+                    color_mode = mode;
+                }
                 SimCommand::SetBlur(b) => blur = b,
+                SimCommand::SetBlurMode { mode, radius } => {
+                    (blur_mode, blur_radius) = (mode, radius);
+                }
+                SimCommand::SetMutationRate(rate) => soup.set_mutation_rate(rate),
                 SimCommand::RequestProgram(cell) => {
                     if cell < soup.programs.len() {
                         let bytes = soup.programs[cell].clone();
@@ -1023,59 +1643,91 @@ fn sim_thread_loop_surface<S: Substrate + Sync>(
                             cell,
                             bytes,
                             disassembly,
+                            neighbors: soup.mesh.neighbors_of(cell).to_vec(),
                         });
                     }
                 }
+                SimCommand::Watch(cell) => soup.set_watch(cell),
+                SimCommand::SetSnapshotEvery(every) => snapshot_every = every,
                 SimCommand::ResetSurface {
                     mesh: new_mesh,
                     config: new_config,
                     seed: new_seed,
                 } => {
-                    face_adjacency = new_mesh.face_adjacency.clone();
-                    soup = SoupSurface::new(new_mesh, new_config, new_seed);
+                    let new_mesh = match new_mesh {
+                        Some(new_mesh) => {
+                            cell_adjacency = new_mesh.cell_adjacency().to_vec();
+                            *new_mesh
+                        }
+                        None => soup.mesh.clone(),
+                    };
+                    soup = SoupSurface::new(new_mesh, *new_config, new_seed);
+                    soup.record_copy_events(true);
                     epoch = 0;
                     num_cells = soup.mesh.num_cells();
-                    color_buf = Vec::with_capacity(num_cells * 4);
                     blur_scratch = Vec::new();
                     pop_buf = Vec::new();
-                    let _ = metrics_tx.send(compute_metrics_surface(&soup, 0, &mut pop_buf));
-                    fill_colors_for_mode::<S>(
-                        color_mode,
-                        &soup.programs,
-                        &soup.mesh.neighbor_indices,
-                        &soup.mesh.neighbor_ranges,
-                        &mut color_buf,
+                    glow = vec![0.0; num_cells];
+                    rate_since = (std::time::Instant::now(), 0);
+                    epochs_per_second = 0.0;
+                    let _ =
+                        metrics_tx.send(compute_metrics_surface::<S>(&mut soup, 0, &mut pop_buf));
+                    let mut colors = pool.take();
+                    fill_colors_for_mode::<S>(color_mode, &soup, &glow, &mut colors);
+                    blur_colors(
+                        &mut colors,
+                        &mut blur_scratch,
+                        &cell_adjacency,
+                        &soup.mesh,
+                        blur_mode,
+                        blur_radius,
+                        blur,
                     );
-                    blur_surface_colors(&mut color_buf, &mut blur_scratch, &face_adjacency, blur);
                     let _ = snap_tx.send(SurfaceSnapshot {
-                        colors: color_buf.clone(),
+                        colors,
+                        watch_log: Vec::new(),
                     });
                 }
             }
         }
 
         if paused || epoch >= max_epochs {
+            // Time spent idle doesn't count against the rate.
+            rate_since = (std::time::Instant::now(), epoch);
             thread::sleep(std::time::Duration::from_millis(10));
             continue;
         }
 
+        // Glow tracks what interactions rewrite, not mutation noise.
+        let glowing = color_mode == ColorMode::TemporalGlow;
+        if glowing {
+            before_epoch.clone_from(&soup.programs);
+        }
         soup.run_epoch::<S>();
+        if glowing {
+            accumulate_glow(&mut glow, &before_epoch, &soup.programs);
+        }
         soup.mutate();
         epoch += 1;
 
         let now = std::time::Instant::now();
-        if now.duration_since(last_snap_send) >= snap_interval || epoch == max_epochs {
-            fill_colors_for_mode::<S>(
-                color_mode,
-                &soup.programs,
-                &soup.mesh.neighbor_indices,
-                &soup.mesh.neighbor_ranges,
-                &mut color_buf,
+        let since_last = now.duration_since(last_snap_send);
+        if should_send_snapshot(epoch, max_epochs, snapshot_every, since_last) {
+            let mut colors = pool.take();
+            fill_colors_for_mode::<S>(color_mode, &soup, &glow, &mut colors);
+            blur_colors(
+                &mut colors,
+                &mut blur_scratch,
+                &cell_adjacency,
+                &soup.mesh,
+                blur_mode,
+                blur_radius,
+                blur,
             );
-            blur_surface_colors(&mut color_buf, &mut blur_scratch, &face_adjacency, blur);
             if snap_tx
                 .send(SurfaceSnapshot {
-                    colors: color_buf.clone(),
+                    colors,
+                    watch_log: soup.take_watch_log(),
                 })
                 .is_err()
             {
@@ -1084,28 +1736,73 @@ fn sim_thread_loop_surface<S: Substrate + Sync>(
             last_snap_send = now;
         }
 
-        if (epoch.is_multiple_of(metrics_interval) || epoch == max_epochs)
-            && metrics_tx
-                .send(compute_metrics_surface(&soup, epoch, &mut pop_buf))
-                .is_err()
-        {
-            break;
+        if epoch.is_multiple_of(metrics_interval) || epoch == max_epochs {
+            let elapsed = now.duration_since(rate_since.0).as_secs_f64();
+            if elapsed > 0.0 {
+                let sample = (epoch - rate_since.1) as f64 / elapsed;
+                epochs_per_second = smooth_rate(epochs_per_second, sample, RATE_SMOOTHING);
+                rate_since = (now, epoch);
+            }
+            let mut metrics = compute_metrics_surface::<S>(&mut soup, epoch, &mut pop_buf);
+            metrics.epochs_per_second = epochs_per_second;
+            if metrics_tx.send(metrics).is_err() {
+                break;
+            }
         }
     }
 }
 
-fn compute_metrics_surface(
-    soup: &SoupSurface,
+/// Metrics for the current population, consuming the soup's copy events
+/// recorded since the previous call.
+pub(crate) fn compute_metrics_surface<S: Substrate>(
+    soup: &mut SoupSurface,
     epoch: usize,
     pop_buf: &mut Vec<u8>,
 ) -> EpochMetrics {
     soup.population_bytes_into(pop_buf);
+    let copy_events = soup.take_copy_events();
+    let step_limit = soup.config.step_limit;
+    compute_metrics::<S>(&soup.programs, pop_buf, &copy_events, epoch, step_limit)
+}
+
+/// Most common programs checked for exact self-replication each snapshot.
+pub const QUINE_SAMPLE: usize = 16;
+
+/// Metrics for a population given both as programs and as their
+/// concatenated bytes (`population`), plus the copy events observed since
+/// the previous snapshot. `step_limit` bounds the run of the most common
+/// program that measures its active fraction.
+pub(crate) fn compute_metrics<S: Substrate>(
+    programs: &[Vec<u8>],
+    population: &[u8],
+    copy_events: &[CopyEvent],
+    epoch: usize,
+    step_limit: usize,
+) -> EpochMetrics {
+    let byte_histogram = byte_frequency_histogram(programs);
+    let program_size = programs.first().map_or(0, Vec::len);
+    #[cfg(feature = "spectral")]
+    let spectral_flatness = Some(crate::metrics::spectral_flatness(population));
+    #[cfg(not(feature = "spectral"))]
+    let spectral_flatness = None;
     EpochMetrics {
         epoch,
-        hoe: high_order_entropy(pop_buf),
-        unique_count: unique_program_count(&soup.programs),
-        zero_count: zero_byte_count(&soup.programs),
-        byte_histogram: byte_frequency_histogram(&soup.programs),
+        hoe: high_order_entropy(population),
+        unique_count: unique_program_count(programs),
+        zero_count: zero_byte_count(programs),
+        chi_square: uniformity_chi_square(&byte_histogram),
+        parasite_fraction: parasite_fraction(copy_events, programs.len()),
+        spectral_flatness,
+        active_fraction: modal_program(programs)
+            .and_then(|program| active_fraction::<S>(program, step_limit)),
+        consensus_distance: mean_distance_to_consensus(
+            programs,
+            &consensus_program(programs, program_size),
+        ),
+        quines: count_quines::<S>(programs, step_limit, QUINE_SAMPLE),
+        positional_mi: positional_mutual_information(programs, program_size),
+        byte_histogram,
+        epochs_per_second: 0.0,
     }
 }
 
@@ -1121,6 +1818,27 @@ fn build_render_positions(mesh: &SurfaceMesh) -> Vec<[f32; 3]> {
     positions
 }
 
+/// The cell each vertex of `build_render_positions` is colored by: its
+/// face, or with vertex cells the mesh vertex itself, so colors blend
+/// across each face.
+fn build_render_cells(mesh: &SurfaceMesh) -> Vec<usize> {
+    match mesh.cell_site() {
+        CellSite::Face => (0..mesh.faces.len()).flat_map(|f| [f, f, f]).collect(),
+        CellSite::Vertex => mesh.faces.iter().flatten().copied().collect(),
+    }
+}
+
+/// The cell under a ray hit on triangle `face` at `barycentric`, which
+/// weighs the triangle's second, third, and first vertex in that order:
+/// the face's cell, or the nearest vertex's.
+fn picked_cell(render_cells: &[usize], face: usize, barycentric: Vec3) -> usize {
+    let weights = [barycentric.z, barycentric.x, barycentric.y];
+    let corner = (0..3)
+        .max_by(|&a, &b| weights[a].total_cmp(&weights[b]))
+        .unwrap_or(0);
+    render_cells[face * 3 + corner]
+}
+
 fn build_render_normals(mesh: &SurfaceMesh) -> Vec<[f32; 3]> {
     let mut normals = Vec::with_capacity(mesh.faces.len() * 3);
     for face in &mesh.faces {
@@ -1136,6 +1854,77 @@ fn build_render_normals(mesh: &SurfaceMesh) -> Vec<[f32; 3]> {
     normals
 }
 
+/// Per-corner normals like `build_render_normals`, but each corner takes
+/// its mesh vertex's normal: the area-weighted average of the faces
+/// around it. Colors stay per cell; only the shading turns smooth. A
+/// vertex whose faces cancel out (mixed winding) keeps the face normal.
+fn build_smooth_render_normals(mesh: &SurfaceMesh) -> Vec<[f32; 3]> {
+    let mut sums = vec![Vec3::ZERO; mesh.vertices.len()];
+    for face in &mesh.faces {
+        let [a, b, c] = face.map(|v| Vec3::from_array(mesh.vertices[v]));
+        let weighted = (b - a).cross(c - a);
+        for &v in face {
+            sums[v] += weighted;
+        }
+    }
+    let mut normals = Vec::with_capacity(mesh.faces.len() * 3);
+    for face in &mesh.faces {
+        let flat = face_normal(
+            &mesh.vertices[face[0]],
+            &mesh.vertices[face[1]],
+            &mesh.vertices[face[2]],
+        );
+        for &v in face {
+            normals.push(sums[v].try_normalize().map_or(flat, |n| n.to_array()));
+        }
+    }
+    normals
+}
+
+/// Gap left between neighboring surfaces, as a fraction of the larger of
+/// their bounding radii.
+const SURFACE_GAP: f32 = 0.25;
+
+/// Smallest sphere enclosing both spheres, each given as `(center, radius)`.
+fn combined_bounding_sphere(a: ([f32; 3], f32), b: ([f32; 3], f32)) -> ([f32; 3], f32) {
+    let (ca, ra) = (Vec3::from_array(a.0), a.1);
+    let (cb, rb) = (Vec3::from_array(b.0), b.1);
+    let d = ca.distance(cb);
+    if d + rb <= ra {
+        return a;
+    }
+    if d + ra <= rb {
+        return b;
+    }
+    let radius = (d + ra + rb) / 2.0;
+    let center = ca + (cb - ca) * ((radius - ra) / d);
+    (center.to_array(), radius)
+}
+
+/// World-space translations that line surfaces up along the x axis, each
+/// bounding sphere (`(center, radius)` in mesh coordinates) just clear of
+/// the previous one. The first surface stays where it is.
+fn side_by_side_offsets(spheres: &[([f32; 3], f32)]) -> Vec<[f32; 3]> {
+    let Some(&(first_center, _)) = spheres.first() else {
+        return Vec::new();
+    };
+    let mut offsets = Vec::with_capacity(spheres.len());
+    let mut prev: Option<(f32, f32)> = None;
+    for &(center, radius) in spheres {
+        let x = match prev {
+            None => first_center[0],
+            Some((prev_x, prev_r)) => prev_x + prev_r + SURFACE_GAP * prev_r.max(radius) + radius,
+        };
+        offsets.push([
+            x - center[0],
+            first_center[1] - center[1],
+            first_center[2] - center[2],
+        ]);
+        prev = Some((x, radius));
+    }
+    offsets
+}
+
 // ─── App entry point ─────────────────────────────────────────────────────────
 
 pub fn run_app(menu_config: MenuConfig) {
@@ -1151,7 +1940,9 @@ pub fn run_app(menu_config: MenuConfig) {
         .add_plugins(EguiPlugin)
         .init_state::<AppState>()
         .insert_resource(menu_config)
+        .add_plugins(FrameTimeDiagnosticsPlugin)
         .insert_resource(ShowHelp::default())
+        .insert_resource(ShowPerfOverlay::default())
         // Menu lifecycle
         .add_systems(OnEnter(AppState::Menu), enter_menu)
         .add_systems(OnExit(AppState::Menu), exit_menu)
@@ -1166,9 +1957,13 @@ pub fn run_app(menu_config: MenuConfig) {
                 drain_metrics,
                 drain_surface_snapshot,
                 drain_program_response,
-                update_surface_mesh.after(drain_surface_snapshot),
+                render_grid_minimap.after(drain_surface_snapshot),
+                update_surface_mesh
+                    .after(drain_surface_snapshot)
+                    .after(render_grid_minimap),
                 orbit_camera_system,
                 handle_mesh_click,
+                render_perf_overlay,
             )
                 .run_if(in_state(AppState::Simulating)),
         )
@@ -1181,6 +1976,7 @@ pub fn run_app(menu_config: MenuConfig) {
                     .after(drain_program_response)
                     .after(handle_mesh_click),
                 apply_mesh_rebuild.after(render_ui_surface),
+                apply_normal_mode.after(render_ui_surface),
             )
                 .run_if(in_state(AppState::Simulating)),
         )
@@ -1246,7 +2042,28 @@ fn render_menu_ui(
                 });
             if menu.substrate != prev_substrate {
                 menu.program_size = menu.substrate.default_program_size();
-                menu.step_limit = menu.substrate.default_step_limit();
+                menu.step_limit = menu.recommended_step_limit();
+            }
+            if menu.substrate == SubstrateKind::Hybrid {
+                let prev_halves = (menu.hybrid_a, menu.hybrid_b);
+                let menu = &mut *menu;
+                for (label, half) in [
+                    ("First half", &mut menu.hybrid_a),
+                    ("Second half", &mut menu.hybrid_b),
+                ] {
+                    egui::ComboBox::from_label(label)
+                        .selected_text(half.label())
+                        .show_ui(ui, |ui| {
+                            for kind in SubstrateKind::ALL {
+                                if kind != SubstrateKind::Hybrid {
+                                    ui.selectable_value(half, kind, kind.label());
+                                }
+                            }
+                        });
+                }
+                if (menu.hybrid_a, menu.hybrid_b) != prev_halves {
+                    menu.step_limit = menu.recommended_step_limit();
+                }
             }
             ui.add_space(4.0);
 
@@ -1263,6 +2080,16 @@ fn render_menu_ui(
             ui.heading("Surface");
             ui.add_space(4.0);
             render_surface_params(ui, &mut menu.surface);
+            if !menu.extra_surfaces.is_empty() {
+                ui.add_space(4.0);
+                let labels: Vec<&str> = menu.extra_surfaces.iter().map(|s| s.label()).collect();
+                ui.horizontal(|ui| {
+                    ui.label(format!("Side by side with: {}", labels.join(", ")));
+                    if ui.button("Clear").clicked() {
+                        menu.extra_surfaces.clear();
+                    }
+                });
+            }
             ui.add_space(12.0);
 
             // Simulation parameters.
@@ -1320,6 +2147,9 @@ fn render_menu_ui(
                 });
 
             ui.add(egui::Slider::new(&mut menu.blur, 0.0..=1.0).text("Blur"));
+            ui.checkbox(&mut menu.double_sided, "Double-sided faces")
+                .on_hover_text("For non-orientable surfaces and OBJs with mixed winding");
+            ui.checkbox(&mut menu.smooth_normals, "Smooth shading");
 
             ui.add_space(20.0);
 
@@ -1328,12 +2158,15 @@ fn render_menu_ui(
                 if ui.button("Start Simulation").clicked() {
                     menu.surface.last_error = None;
                     let spec = menu.surface.current_spec();
-                    match spec.build() {
-                        Ok(_) => {
+                    let built = std::iter::once(&spec)
+                        .chain(&menu.extra_surfaces)
+                        .try_for_each(|spec| spec.build().map(drop));
+                    match built {
+                        Ok(()) => {
                             next_state.set(AppState::Simulating);
                         }
                         Err(e) => {
-                            menu.surface.last_error = Some(e);
+                            menu.surface.last_error = Some(e.to_string());
                         }
                     }
                 }
@@ -1355,99 +2188,181 @@ fn enter_simulation(
     mut materials: ResMut<Assets<StandardMaterial>>,
     menu: Res<MenuConfig>,
 ) {
-    // Build mesh from spec.
-    let spec = menu.surface.current_spec();
-    let mut surface_mesh = spec.build().expect("spec was validated in menu");
-    surface_mesh.compute_neighbors(menu.surface.neighbor_radius);
-
-    let num_cells = surface_mesh.num_cells();
-
-    // Pre-compute render data.
-    let render_positions = build_render_positions(&surface_mesh);
-    let render_normals = build_render_normals(&surface_mesh);
-    let num_render_vertices = render_positions.len();
-    let (center, radius) = surface_mesh.bounding_sphere();
-
-    // Spawn sim thread.
-    let config = SoupSurfaceConfig {
-        program_size: menu.program_size,
-        step_limit: menu.step_limit,
-        mutation_rate: menu.mutation_rate,
-        interaction_mode: menu.interaction_mode,
-    };
+    // Build every mesh from its spec.
+    let specs: Vec<SurfaceSpec> = std::iter::once(menu.surface.current_spec())
+        .chain(menu.extra_surfaces.iter().cloned())
+        .collect();
+    let surface_meshes: Vec<SurfaceMesh> = specs
+        .iter()
+        .map(|spec| {
+            let mut surface_mesh = spec.build().expect("spec was validated in menu");
+            if spec.builds_neighbors() {
+                return surface_mesh;
+            }
+            surface_mesh
+                .set_cell_site(menu.surface.cells)
+                .expect("the viewer measures neighbors in euclidean distance");
+            let radius = menu
+                .surface
+                .neighbor_radius
+                .map(|r| r.resolve(&surface_mesh));
+            surface_mesh.compute_neighbors(radius, None);
+            surface_mesh
+        })
+        .collect();
+
+    // Lay the surfaces out side by side and frame them all.
+    let spheres: Vec<([f32; 3], f32)> = surface_meshes
+        .iter()
+        .map(SurfaceMesh::bounding_sphere)
+        .collect();
+    let offsets = side_by_side_offsets(&spheres);
+    let (center, radius) = spheres
+        .iter()
+        .zip(&offsets)
+        .map(|(&(c, r), o)| ([c[0] + o[0], c[1] + o[1], c[2] + o[2]], r))
+        .reduce(combined_bounding_sphere)
+        .expect("at least one surface");
+
+    let config = menu.soup_config();
 
-    let (metrics_rx, snap_rx, cmd_tx, prog_rx) = spawn_sim_thread(
-        menu.substrate,
-        surface_mesh,
-        config,
-        menu.surface.seed,
-        menu.max_epochs,
-        menu.metrics_interval,
-        menu.blur,
-    );
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 0.8,
+        metallic: 0.0,
+        reflectance: 0.1,
+        double_sided: menu.double_sided,
+        cull_mode: (!menu.double_sided).then_some(Face::Back),
+        ..default()
+    });
+
+    let mut metrics_rxs = Vec::new();
+    let mut snap_rxs = Vec::new();
+    let mut cmd_txs = Vec::new();
+    let mut prog_rxs = Vec::new();
+    let mut surfaces = Vec::new();
+    let mut render_data = None;
+
+    for (index, ((surface_mesh, offset), spec)) in surface_meshes
+        .into_iter()
+        .zip(offsets)
+        .zip(&specs)
+        .enumerate()
+    {
+        let num_cells = surface_mesh.num_cells();
+
+        // Pre-compute render data.
+        let render_cells = build_render_cells(&surface_mesh);
+        let render_positions = build_render_positions(&surface_mesh);
+        let flat_normals = build_render_normals(&surface_mesh);
+        let smooth_normals = build_smooth_render_normals(&surface_mesh);
+        let render_normals = if menu.smooth_normals {
+            smooth_normals.clone()
+        } else {
+            flat_normals.clone()
+        };
+        let num_render_vertices = render_positions.len();
+        if index == 0 {
+            render_data = Some(SurfaceRenderData {
+                positions: render_positions.clone(),
+                normals: render_normals.clone(),
+                num_render_vertices,
+                center,
+                radius,
+            });
+        }
+
+        // Spawn sim thread.
+        let (metrics_rx, snap_rx, cmd_tx, prog_rx) = spawn_sim_thread(
+            menu.substrate,
+            (menu.hybrid_a, menu.hybrid_b),
+            surface_mesh,
+            config.clone(),
+            menu.surface.seed,
+            menu.max_epochs,
+            menu.metrics_interval,
+            menu.blur,
+        );
+
+        // Set initial color mode on the sim thread.
+        if menu.color_mode != ColorMode::Hash {
+            let _ = cmd_tx.send(SimCommand::SetColorMode(menu.color_mode));
+        }
+        metrics_rxs.push(Mutex::new(metrics_rx));
+        snap_rxs.push(Mutex::new(snap_rx));
+        cmd_txs.push(cmd_tx);
+        prog_rxs.push(Mutex::new(prog_rx));
+
+        // Build bevy mesh.
+        let mut bevy_mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+        );
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, render_positions);
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, render_normals);
+        let initial_colors: Vec<[f32; 4]> = vec![[0.5, 0.5, 0.5, 1.0]; num_render_vertices];
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, initial_colors);
+
+        let mesh_handle = meshes.add(bevy_mesh);
+
+        // Spawn 3D entities with SimEntity marker.
+        let mut surface_entity = commands.spawn((
+            Mesh3d(mesh_handle.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(Vec3::from_array(offset)),
+            SurfaceIndex(index),
+            SimEntity,
+        ));
+        if menu.double_sided {
+            surface_entity.insert(RayCastBackfaces);
+        }
 
-    // Set initial color mode + blur on the sim thread.
-    if menu.color_mode != ColorMode::Hash {
-        let _ = cmd_tx.send(SimCommand::SetColorMode(menu.color_mode));
+        surfaces.push(SimSurface {
+            mesh_handle,
+            num_cells,
+            render_cells,
+            frame_layout: FrameLayout::for_surface(spec, menu.surface.cells),
+            flat_normals,
+            smooth_normals,
+        });
     }
 
     // Insert sim-only resources.
-    commands.insert_resource(SimReceiver(Mutex::new(metrics_rx)));
-    commands.insert_resource(SurfaceSnapshotReceiver(Mutex::new(snap_rx)));
-    commands.insert_resource(ProgramResponseReceiver(Mutex::new(prog_rx)));
+    commands.insert_resource(SimReceiver(metrics_rxs));
+    commands.insert_resource(SurfaceSnapshotReceiver(snap_rxs));
+    commands.insert_resource(ProgramResponseReceiver(prog_rxs));
     commands.insert_resource(SelectedCell::default());
-    commands.insert_resource(SimCommander(cmd_tx));
+    commands.insert_resource(SimCommander(cmd_txs));
     commands.insert_resource(SimulationHistory::default());
-    commands.insert_resource(LatestSurfaceSnapshot::default());
+    commands.insert_resource(LatestSurfaceSnapshots(
+        surfaces
+            .iter()
+            .map(|_| LatestSurfaceSnapshot::default())
+            .collect(),
+    ));
     commands.insert_resource(PlaybackState {
         playing: true,
         max_epochs: menu.max_epochs,
+        mutation_frozen: false,
     });
     commands.insert_resource(VizSettings {
         color_mode: menu.color_mode,
         blur: menu.blur,
+        blur_mode: BlurMode::Adjacency,
+        blur_radius: DEFAULT_BLUR_RADIUS,
+        lock_camera: false,
+        smooth_normals: menu.smooth_normals,
+        snapshot_every: 0,
     });
     commands.insert_resource(SimSurfaceParams(menu.surface.clone()));
-    commands.insert_resource(SurfaceRenderData {
-        positions: render_positions.clone(),
-        normals: render_normals.clone(),
-        num_render_vertices,
-        center,
-        radius,
-    });
-
-    // Build bevy mesh.
-    let mut bevy_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
-    );
-    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, render_positions);
-    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, render_normals);
-    let initial_colors: Vec<[f32; 4]> = vec![[0.5, 0.5, 0.5, 1.0]; num_render_vertices];
-    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, initial_colors);
-
-    let mesh_handle = meshes.add(bevy_mesh);
-
-    let material = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        perceptual_roughness: 0.8,
-        metallic: 0.0,
-        reflectance: 0.1,
-        ..default()
-    });
-
-    // Spawn 3D entities with SimEntity marker.
-    commands.spawn((
-        Mesh3d(mesh_handle.clone()),
-        MeshMaterial3d(material),
-        Transform::default(),
-        SimEntity,
-    ));
-
+    commands.insert_resource(SidePanelRect::default());
+    commands.insert_resource(FrameRecorder::default());
+    commands.insert_resource(GridMinimap::default());
+    commands.insert_resource(render_data.expect("at least one surface"));
     commands.insert_resource(SimResources {
-        mesh_handle,
-        num_cells,
+        surfaces,
         pending_rebuild: false,
+        smooth_normals: menu.smooth_normals,
     });
 
     // Camera with orbit controls.
@@ -1501,11 +2416,14 @@ fn exit_simulation(mut commands: Commands, sim_entities: Query<Entity, With<SimE
     commands.remove_resource::<SelectedCell>();
     commands.remove_resource::<SimCommander>();
     commands.remove_resource::<SimulationHistory>();
-    commands.remove_resource::<LatestSurfaceSnapshot>();
+    commands.remove_resource::<LatestSurfaceSnapshots>();
     commands.remove_resource::<PlaybackState>();
     commands.remove_resource::<VizSettings>();
     commands.remove_resource::<SimResources>();
     commands.remove_resource::<SimSurfaceParams>();
+    commands.remove_resource::<SidePanelRect>();
+    commands.remove_resource::<FrameRecorder>();
+    commands.remove_resource::<GridMinimap>();
     commands.remove_resource::<SurfaceRenderData>();
     commands.remove_resource::<AmbientLight>();
 }
@@ -1513,7 +2431,10 @@ fn exit_simulation(mut commands: Commands, sim_entities: Query<Entity, With<SimE
 // ─── Simulation update systems ───────────────────────────────────────────────
 
 fn drain_metrics(receiver: Res<SimReceiver>, mut history: ResMut<SimulationHistory>) {
-    let rx = receiver.0.lock().unwrap();
+    for rx in &receiver.0[1..] {
+        while rx.lock().unwrap().try_recv().is_ok() {}
+    }
+    let rx = receiver.0[0].lock().unwrap();
     while let Ok(metrics) = rx.try_recv() {
         if history.awaiting_reset {
             if metrics.epoch == 0 {
@@ -1528,21 +2449,62 @@ fn drain_metrics(receiver: Res<SimReceiver>, mut history: ResMut<SimulationHisto
 
 fn drain_surface_snapshot(
     receiver: Res<SurfaceSnapshotReceiver>,
-    mut latest: ResMut<LatestSurfaceSnapshot>,
+    mut latest: ResMut<LatestSurfaceSnapshots>,
+    mut selected: ResMut<SelectedCell>,
+    mut recorder: ResMut<FrameRecorder>,
+    sim: Res<SimResources>,
 ) {
-    let rx = receiver.0.lock().unwrap();
-    while let Ok(snapshot) = rx.try_recv() {
-        latest.snapshot = Some(snapshot);
-        latest.dirty = true;
+    // Appending to the log isn't a selection change the mesh should redraw for.
+    let selected = selected.bypass_change_detection();
+    for (index, (rx, latest)) in receiver.0.iter().zip(&mut latest.0).enumerate() {
+        let rx = rx.lock().unwrap();
+        while let Ok(mut snapshot) = rx.snapshots.try_recv() {
+            // Events still in flight from a previous selection are dropped.
+            let watched = selected.cell_index.filter(|_| selected.surface == index);
+            for event in std::mem::take(&mut snapshot.watch_log) {
+                if Some(event.cell) == watched {
+                    if selected.watch_log.len() == WATCH_LOG_CAPACITY {
+                        selected.watch_log.pop_front();
+                    }
+                    selected.watch_log.push_back(event);
+                }
+            }
+            // Every snapshot is recorded, not just the last one per frame.
+            if index == 0
+                && let Some(surface) = sim.surfaces.first()
+            {
+                recorder.record(&snapshot.colors, surface);
+            }
+            if let Some(old) = latest.snapshot.replace(snapshot) {
+                rx.recycle(old.colors);
+            }
+            latest.dirty = true;
+        }
     }
 }
 
 fn update_surface_mesh(
     mut meshes: ResMut<Assets<Mesh>>,
     sim: Res<SimResources>,
-    mut latest: ResMut<LatestSurfaceSnapshot>,
+    mut latest: ResMut<LatestSurfaceSnapshots>,
+    selected: Res<SelectedCell>,
+) {
+    for (index, (surface, latest)) in sim.surfaces.iter().zip(&mut latest.0).enumerate() {
+        let tinted = (selected.surface == index).then_some(&selected.neighbors[..]);
+        update_one_surface_mesh(&mut meshes, surface, latest, tinted, selected.is_changed());
+    }
+}
+
+/// Recolor one surface from its latest snapshot, tinting `neighbors` if it
+/// holds the selected cell.
+fn update_one_surface_mesh(
+    meshes: &mut Assets<Mesh>,
+    sim: &SimSurface,
+    latest: &mut LatestSurfaceSnapshot,
+    neighbors: Option<&[usize]>,
+    selection_changed: bool,
 ) {
-    if !latest.dirty {
+    if !latest.dirty && !selection_changed {
         return;
     }
     let Some(ref snap) = latest.snapshot else {
@@ -1558,15 +2520,22 @@ fn update_surface_mesh(
         return;
     }
 
-    let mut vertex_colors: Vec<[f32; 4]> = Vec::with_capacity(sim.num_cells * 3);
-    for i in 0..sim.num_cells {
-        let idx = i * 4;
+    let mut tinted = vec![false; sim.num_cells];
+    for &n in neighbors.unwrap_or_default() {
+        tinted[n] = true;
+    }
+    let mut vertex_colors: Vec<[f32; 4]> = Vec::with_capacity(sim.render_cells.len());
+    for &cell in &sim.render_cells {
+        let idx = cell * 4;
         let r = snap.colors[idx] as f32 / 255.0;
         let g = snap.colors[idx + 1] as f32 / 255.0;
         let b = snap.colors[idx + 2] as f32 / 255.0;
-        let color = [r, g, b, 1.0];
-        vertex_colors.push(color);
-        vertex_colors.push(color);
+        let mut color = [r, g, b, 1.0];
+        if tinted[cell] {
+            for c in 0..3 {
+                color[c] += (NEIGHBOR_TINT[c] - color[c]) * NEIGHBOR_TINT_STRENGTH;
+            }
+        }
         vertex_colors.push(color);
     }
 
@@ -1574,6 +2543,248 @@ fn update_surface_mesh(
     latest.dirty = false;
 }
 
+// ─── Frame recording ─────────────────────────────────────────────────────────
+
+/// Directory the "Record frames" toggle saves into, under the working
+/// directory.
+const FRAMES_DIR: &str = "frames";
+
+/// How a surface's cells become the pixels of a recorded frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameLayout {
+    /// One pixel per square of a `width`×`height` flat grid of face cells,
+    /// top row first.
+    Grid { width: usize, height: usize },
+    /// Cells in index order, row by row, in a near-square image.
+    Rows,
+}
+
+impl FrameLayout {
+    /// The layout for a surface built from `spec` with programs on `cells`.
+    pub fn for_surface(spec: &SurfaceSpec, cells: CellSite) -> Self {
+        match (spec, cells) {
+            (&SurfaceSpec::FlatGrid { width, height }, CellSite::Face) => {
+                FrameLayout::Grid { width, height }
+            }
+            _ => FrameLayout::Rows,
+        }
+    }
+
+    /// Image width and height for a surface of `num_cells` cells.
+    pub fn dimensions(self, num_cells: usize) -> (usize, usize) {
+        match self {
+            FrameLayout::Grid { width, height } => (width, height),
+            FrameLayout::Rows => {
+                let width = num_cells.isqrt().max(1);
+                (width, num_cells.div_ceil(width).max(1))
+            }
+        }
+    }
+}
+
+/// Color of square `(i, j)` of a `width`-wide flat grid, counting rows from
+/// the bottom: the average of its two triangles, faces `2(j·width + i)` and
+/// the one after, in an RGBA `colors` buffer.
+pub(crate) fn grid_square_rgb(colors: &[u8], width: usize, i: usize, j: usize) -> [u8; 3] {
+    let face = 2 * (j * width + i);
+    let (a, b) = (&colors[face * 4..], &colors[(face + 1) * 4..]);
+    [0, 1, 2].map(|c| (a[c] as u32 + b[c] as u32).div_ceil(2) as u8)
+}
+
+/// An RGBA cell `colors` buffer laid out as an image. Pixels past the last
+/// cell of a [`FrameLayout::Rows`] image stay black.
+pub fn frame_image(colors: &[u8], layout: FrameLayout) -> image::RgbImage {
+    let num_cells = colors.len() / 4;
+    let (width, height) = layout.dimensions(num_cells);
+    image::RgbImage::from_fn(width as u32, height as u32, |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        image::Rgb(match layout {
+            FrameLayout::Grid { .. } => grid_square_rgb(colors, width, x, height - 1 - y),
+            FrameLayout::Rows => {
+                let cell = y * width + x;
+                if cell < num_cells {
+                    [colors[cell * 4], colors[cell * 4 + 1], colors[cell * 4 + 2]]
+                } else {
+                    [0, 0, 0]
+                }
+            }
+        })
+    })
+}
+
+/// A flat grid's `colors` as an egui image seen head-on, one pixel per
+/// square, or `None` when `layout` isn't a grid.
+fn grid_minimap_image(colors: &[u8], layout: FrameLayout) -> Option<egui::ColorImage> {
+    let FrameLayout::Grid { width, height } = layout else {
+        return None;
+    };
+    let image = frame_image(colors, layout);
+    Some(egui::ColorImage::from_rgb([width, height], image.as_raw()))
+}
+
+/// Longest side, in points, the flat-grid minimap is drawn at.
+const MINIMAP_SIZE: f32 = 256.0;
+
+/// Texture of the first surface's latest colors while it is a flat grid.
+#[derive(Resource, Default)]
+struct GridMinimap(Option<egui::TextureHandle>);
+
+/// Show a flat-grid surface head-on in a window beside the 3D view,
+/// refreshed each time a new snapshot arrives.
+fn render_grid_minimap(
+    mut contexts: EguiContexts,
+    mut minimap: ResMut<GridMinimap>,
+    sim: Res<SimResources>,
+    latest: Res<LatestSurfaceSnapshots>,
+) {
+    let Some(surface) = sim.surfaces.first() else {
+        return;
+    };
+    let FrameLayout::Grid { width, height } = surface.frame_layout else {
+        minimap.0 = None;
+        return;
+    };
+    if let Some(latest) = latest.0.first().filter(|latest| latest.dirty)
+        && let Some(snapshot) = &latest.snapshot
+        && snapshot.colors.len() == surface.num_cells * 4
+        && let Some(image) = grid_minimap_image(&snapshot.colors, surface.frame_layout)
+    {
+        match &mut minimap.0 {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => {
+                let ctx = contexts.ctx_mut();
+                minimap.0 =
+                    Some(ctx.load_texture("grid_minimap", image, egui::TextureOptions::NEAREST));
+            }
+        }
+    }
+    let Some(texture) = &minimap.0 else {
+        return;
+    };
+    let scale = MINIMAP_SIZE / width.max(height) as f32;
+    let size = egui::vec2(width as f32 * scale, height as f32 * scale);
+    egui::Window::new("Grid")
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -8.0))
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.image((texture.id(), size));
+        });
+}
+
+/// Path of recorded frame `index`: `dir/NNNNNN.png`, zero-padded so the
+/// frames sort in order.
+fn frame_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{index:06}.png"))
+}
+
+/// A frame queued for the writer thread.
+struct QueuedFrame {
+    path: PathBuf,
+    colors: Vec<u8>,
+    layout: FrameLayout,
+}
+
+/// Channels to the thread that encodes and writes recorded frames, so PNG
+/// compression and disk writes stay off the render thread.
+struct FrameWriter {
+    frames: mpsc::Sender<QueuedFrame>,
+    errors: Mutex<mpsc::Receiver<String>>,
+}
+
+impl FrameWriter {
+    /// Spawn a writer thread. It stops at its first failed write, reporting
+    /// why, or once the sender is dropped and the queue is written out.
+    fn spawn() -> Self {
+        let (frames, queue) = mpsc::channel::<QueuedFrame>();
+        let (error_tx, errors) = mpsc::channel();
+        thread::spawn(move || {
+            for frame in queue {
+                if let Err(e) = frame_image(&frame.colors, frame.layout).save(&frame.path) {
+                    let _ = error_tx.send(format!("Failed to write {}: {e}", frame.path.display()));
+                    return;
+                }
+            }
+        });
+        Self {
+            frames,
+            errors: Mutex::new(errors),
+        }
+    }
+}
+
+/// Frames saved from the first surface while recording is on.
+#[derive(Resource, Default)]
+struct FrameRecorder {
+    recording: bool,
+    /// Index the next frame is saved under; counts on across toggles.
+    next_frame: usize,
+    /// Why the last save failed; recording stops when one does.
+    last_error: Option<String>,
+    /// Started with the first recorded frame; frames still queued when
+    /// the recorder is dropped are written out before the thread exits.
+    writer: Option<FrameWriter>,
+}
+
+impl FrameRecorder {
+    /// Queue `colors` as the next frame under [`FRAMES_DIR`].
+    fn save(&mut self, colors: &[u8], layout: FrameLayout) -> Result<(), String> {
+        let dir = Path::new(FRAMES_DIR);
+        if self.writer.is_none() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let writer = self.writer.get_or_insert_with(FrameWriter::spawn);
+        let frame = QueuedFrame {
+            path: frame_path(dir, self.next_frame),
+            colors: colors.to_vec(),
+            layout,
+        };
+        // The writer reports its error before it stops taking frames.
+        let errors = writer.errors.get_mut().unwrap();
+        if let Ok(e) = errors.try_recv() {
+            self.writer = None;
+            return Err(e);
+        }
+        if writer.frames.send(frame).is_err() {
+            let e = errors
+                .try_recv()
+                .unwrap_or_else(|_| "Frame writer stopped".into());
+            self.writer = None;
+            return Err(e);
+        }
+        self.next_frame += 1;
+        Ok(())
+    }
+
+    /// While recording, queue a snapshot of `surface` with these `colors`,
+    /// stopping the recording if an earlier write failed.
+    fn record(&mut self, colors: &[u8], surface: &SimSurface) {
+        if !self.recording || colors.len() != surface.num_cells * 4 {
+            return;
+        }
+        if let Err(e) = self.save(colors, surface.frame_layout) {
+            self.recording = false;
+            self.last_error = Some(e);
+        }
+    }
+}
+
+/// Whether a Bevy cursor position (logical pixels) lies inside `panel`
+/// (egui points). The two units differ by egui's own scale on top of the
+/// window scale factor, so convert through physical pixels.
+fn cursor_in_panel(
+    panel: Option<egui::Rect>,
+    cursor: Vec2,
+    window_scale_factor: f32,
+    pixels_per_point: f32,
+) -> bool {
+    panel.is_some_and(|rect| {
+        let to_points = window_scale_factor / pixels_per_point;
+        rect.contains(egui::pos2(cursor.x * to_points, cursor.y * to_points))
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn orbit_camera_system(
     mut contexts: EguiContexts,
     mut query: Query<(&mut OrbitCamera, &mut Transform)>,
@@ -1582,6 +2793,7 @@ fn orbit_camera_system(
     mut mouse_motion: EventReader<MouseMotion>,
     mut scroll: EventReader<MouseWheel>,
     windows: Query<&Window>,
+    panel: Res<SidePanelRect>,
 ) {
     if windows.is_empty() {
         return;
@@ -1593,10 +2805,9 @@ fn orbit_camera_system(
 
     // Also check if the cursor is in the right side-panel region, which
     // trackpad two-finger scrolls may not register as "pointer over area".
-    let cursor_over_panel = windows.single().cursor_position().is_some_and(|pos| {
-        let window_width = windows.single().width();
-        // The side panel is 350px min-width on the right side.
-        pos.x > window_width - 360.0
+    let window = windows.single();
+    let cursor_over_panel = window.cursor_position().is_some_and(|pos| {
+        cursor_in_panel(panel.0, pos, window.scale_factor(), ctx.pixels_per_point())
     });
 
     let block_input = egui_wants_pointer || cursor_over_panel;
@@ -1656,6 +2867,8 @@ fn handle_mesh_click(
     mut ray_cast: MeshRayCast,
     mut selected: ResMut<SelectedCell>,
     commander: Res<SimCommander>,
+    sim: Res<SimResources>,
+    surface_index: Query<&SurfaceIndex>,
 ) {
     let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
     if !shift_held || !mouse_buttons.just_pressed(MouseButton::Left) {
@@ -1682,13 +2895,40 @@ fn handle_mesh_click(
     let settings = RayCastSettings::default().always_early_exit();
     let hits = ray_cast.cast_ray(ray, &settings);
 
-    if let Some((_entity, hit)) = hits.first()
+    if let Some((entity, hit)) = hits.first()
         && let Some(tri_idx) = hit.triangle_index
+        && let Ok(&SurfaceIndex(surface)) = surface_index.get(*entity)
     {
-        selected.cell_index = Some(tri_idx);
-        selected.program_bytes = None;
-        selected.disassembly = None;
-        let _ = commander.0.send(SimCommand::RequestProgram(tri_idx));
+        let cell = picked_cell(
+            &sim.surfaces[surface].render_cells,
+            tri_idx,
+            hit.barycentric_coords,
+        );
+        let is_first = selected.surface == surface && selected.cell_index == Some(cell);
+        if selected.cell_index.is_some() && selected.second.is_none() && !is_first {
+            // A second cell while one is selected: diff the two.
+            selected.second = Some(ComparedCell {
+                surface,
+                cell_index: cell,
+                program_bytes: None,
+                disassembly: None,
+            });
+        } else {
+            if selected.cell_index.is_some() && selected.surface != surface {
+                let _ = commander.0[selected.surface].send(SimCommand::Watch(None));
+            }
+            *selected = SelectedCell {
+                surface,
+                cell_index: Some(cell),
+                ..SelectedCell::default()
+            };
+            let _ = commander.0[surface].send(SimCommand::Watch(Some(cell)));
+        }
+        let _ = commander.0[surface].send(SimCommand::RequestProgram(cell));
+    } else if selected.cell_index.is_some() {
+        // Shift+click off the surface clears the selection.
+        let _ = commander.0[selected.surface].send(SimCommand::Watch(None));
+        *selected = SelectedCell::default();
     }
 }
 
@@ -1696,11 +2936,20 @@ fn drain_program_response(
     receiver: Res<ProgramResponseReceiver>,
     mut selected: ResMut<SelectedCell>,
 ) {
-    let rx = receiver.0.lock().unwrap();
-    while let Ok(resp) = rx.try_recv() {
-        if selected.cell_index == Some(resp.cell) {
-            selected.program_bytes = Some(resp.bytes);
-            selected.disassembly = Some(resp.disassembly);
+    for (surface, rx) in receiver.0.iter().enumerate() {
+        let rx = rx.lock().unwrap();
+        while let Ok(resp) = rx.try_recv() {
+            if selected.surface == surface && selected.cell_index == Some(resp.cell) {
+                selected.program_bytes = Some(resp.bytes);
+                selected.disassembly = Some(resp.disassembly);
+                selected.neighbors = resp.neighbors;
+            } else if let Some(second) = selected.second.as_mut()
+                && second.surface == surface
+                && second.cell_index == resp.cell
+            {
+                second.program_bytes = Some(resp.bytes);
+                second.disassembly = Some(resp.disassembly);
+            }
         }
     }
 }
@@ -1708,7 +2957,7 @@ fn drain_program_response(
 #[allow(clippy::too_many_arguments)]
 fn render_ui_surface(
     mut contexts: EguiContexts,
-    history: ResMut<SimulationHistory>,
+    mut history: ResMut<SimulationHistory>,
     mut playback: ResMut<PlaybackState>,
     mut viz: ResMut<VizSettings>,
     commander: Res<SimCommander>,
@@ -1718,6 +2967,8 @@ fn render_ui_surface(
     mut menu: ResMut<MenuConfig>,
     mut selected: ResMut<SelectedCell>,
     mut show_help: ResMut<ShowHelp>,
+    mut panel: ResMut<SidePanelRect>,
+    mut recorder: ResMut<FrameRecorder>,
     windows: Query<&Window>,
 ) {
     if windows.is_empty() {
@@ -1727,9 +2978,7 @@ fn render_ui_surface(
 
     // Clear selection on mesh rebuild.
     if sim.pending_rebuild {
-        selected.cell_index = None;
-        selected.program_bytes = None;
-        selected.disassembly = None;
+        *selected = SelectedCell::default();
     }
 
     // Help button in top-right corner (rendered before the side panel).
@@ -1746,7 +2995,7 @@ fn render_ui_surface(
         render_help_window(ctx, &mut show_help);
     }
 
-    egui::SidePanel::right("metrics_panel")
+    let panel_response = egui::SidePanel::right("metrics_panel")
         .min_width(350.0)
         .show(ctx, |ui| {
             // Back to Menu button at the top.
@@ -1759,25 +3008,71 @@ fn render_ui_surface(
             ui.separator();
 
             egui::ScrollArea::vertical().show(ui, |ui| {
-                render_controls_section(ui, &history, &mut playback, &commander);
+                render_controls_section(
+                    ui,
+                    &history,
+                    &mut playback,
+                    menu.mutation_rate,
+                    &commander,
+                );
                 ui.separator();
-                render_viz_settings(ui, &mut viz, &commander);
+                render_viz_settings(ui, &mut viz, &mut recorder, &commander);
                 ui.separator();
-                render_selected_cell(ui, &selected);
+                if render_soup_settings(ui, &mut menu) {
+                    commander.broadcast(SimCommand::ResetSurface {
+                        mesh: None,
+                        config: Box::new(menu.soup_config()),
+                        seed: gui.0.seed,
+                    });
+                    history.entries.clear();
+                    history.awaiting_reset = true;
+                    playback.mutation_frozen = false;
+                    *selected = SelectedCell::default();
+                }
+                ui.separator();
+                let epoch = history.entries.last().map_or(0, |e| e.epoch);
+                render_selected_cell(
+                    ui,
+                    &mut selected,
+                    sim.surfaces.len(),
+                    menu.substrate.name(),
+                    epoch,
+                );
                 ui.separator();
 
-                let entries = &history.entries;
-                if !entries.is_empty() {
-                    render_plots_section(ui, entries);
+                if !history.entries.is_empty() {
+                    render_plots_section(ui, &mut history);
                 }
+                ui.separator();
+                render_color_legend(ui, viz.color_mode);
             });
         });
+    panel.0 = Some(panel_response.response.rect);
+}
+
+/// Swap every surface's normals when the shading toggle changes.
+fn apply_normal_mode(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut sim: ResMut<SimResources>,
+    viz: Res<VizSettings>,
+) {
+    if sim.smooth_normals == viz.smooth_normals {
+        return;
+    }
+    sim.smooth_normals = viz.smooth_normals;
+    for surface in &sim.surfaces {
+        if let Some(mesh) = meshes.get_mut(&surface.mesh_handle) {
+            let normals = surface.normals(viz.smooth_normals).to_vec();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        }
+    }
 }
 
 fn apply_mesh_rebuild(
     mut meshes: ResMut<Assets<Mesh>>,
     mut sim: ResMut<SimResources>,
     render_data: Res<SurfaceRenderData>,
+    viz: Res<VizSettings>,
     mut query: Query<(&mut OrbitCamera, &mut Transform)>,
 ) {
     if !sim.pending_rebuild {
@@ -1785,7 +3080,7 @@ fn apply_mesh_rebuild(
     }
     sim.pending_rebuild = false;
 
-    let Some(mesh) = meshes.get_mut(&sim.mesh_handle) else {
+    let Some(mesh) = meshes.get_mut(&sim.surfaces[0].mesh_handle) else {
         return;
     };
 
@@ -1795,6 +3090,9 @@ fn apply_mesh_rebuild(
     let initial_colors: Vec<[f32; 4]> = vec![[0.5, 0.5, 0.5, 1.0]; render_data.num_render_vertices];
     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, initial_colors);
 
+    if viz.lock_camera {
+        return;
+    }
     if let Ok((mut orbit, mut transform)) = query.get_single_mut() {
         let center = Vec3::from_array(render_data.center);
         let distance = render_data.radius * 2.5;
@@ -1821,6 +3119,9 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
         "Heightmap",
         "Trefoil Knot",
         "OBJ File",
+        "Heightmap Image",
+        "3D Lattice",
+        "Cube Sphere",
     ];
     let current = match params.shape {
         SurfaceShape::Sphere { .. } => 0,
@@ -1832,6 +3133,9 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
         SurfaceShape::Heightmap { .. } => 6,
         SurfaceShape::TrefoilKnot { .. } => 7,
         SurfaceShape::ObjFile { .. } => 8,
+        SurfaceShape::HeightmapImage { .. } => 9,
+        SurfaceShape::Grid3d { .. } => 10,
+        SurfaceShape::CubeSphere { .. } => 11,
     };
     let mut selected = current;
     egui::ComboBox::from_label("Type")
@@ -1874,7 +3178,20 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
             },
             8 => SurfaceShape::ObjFile {
                 path: String::new(),
+                normalize: false,
+                drop_degenerate: false,
+            },
+            9 => SurfaceShape::HeightmapImage {
+                path: String::new(),
+                scale: DEFAULT_HEIGHTMAP_IMAGE_SCALE,
+            },
+            10 => SurfaceShape::Grid3d {
+                width: 16,
+                height: 16,
+                depth: 16,
+                periodic: false,
             },
+            11 => SurfaceShape::CubeSphere { subdivisions: 32 },
             _ => SurfaceShape::Sphere { subdivisions: 4 },
         };
     }
@@ -1889,6 +3206,12 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
             let face_count = 20 * 4usize.pow(sub);
             ui.label(format!("Faces: {face_count}"));
         }
+        SurfaceShape::CubeSphere { subdivisions } => {
+            let mut sub = *subdivisions as u32;
+            ui.add(egui::Slider::new(&mut sub, 1..=256).text("Subdivisions"));
+            *subdivisions = sub as usize;
+            ui.label(format!("Faces: {}", 12 * *subdivisions * *subdivisions));
+        }
         SurfaceShape::Torus { major, minor } => {
             let mut maj = *major as u32;
             let mut min = *minor as u32;
@@ -1960,21 +3283,67 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
             *segments = segs as usize;
             ui.label(format!("Faces: {}", 2 * *rings * *segments));
         }
-        SurfaceShape::ObjFile { path } => {
+        SurfaceShape::Grid3d {
+            width,
+            height,
+            depth,
+            periodic,
+        } => {
+            let mut w = *width as u32;
+            let mut h = *height as u32;
+            let mut d = *depth as u32;
+            ui.add(egui::Slider::new(&mut w, 1..=128).text("Width"));
+            ui.add(egui::Slider::new(&mut h, 1..=128).text("Height"));
+            ui.add(egui::Slider::new(&mut d, 1..=128).text("Depth"));
+            ui.checkbox(periodic, "Wrap around");
+            *width = w as usize;
+            *height = h as usize;
+            *depth = d as usize;
+            ui.label(format!("Cells: {}", *width * *height * *depth));
+        }
+        SurfaceShape::ObjFile {
+            path,
+            normalize,
+            drop_degenerate,
+        } => {
             ui.horizontal(|ui| {
                 ui.label("Path:");
                 ui.text_edit_singleline(path);
             });
+            ui.checkbox(normalize, "Center and scale to [-1, 1]");
+            ui.checkbox(drop_degenerate, "Drop zero-area faces");
             if path.is_empty() {
                 ui.colored_label(egui::Color32::YELLOW, "Enter the path to a .obj file");
             } else if !std::path::Path::new(path.as_str()).exists() {
                 ui.colored_label(egui::Color32::RED, "File not found");
             }
         }
+        SurfaceShape::HeightmapImage { path, scale } => {
+            ui.horizontal(|ui| {
+                ui.label("Path:");
+                ui.text_edit_singleline(path);
+            });
+            ui.add(egui::Slider::new(scale, 0.0..=2.0).text("Height scale"));
+            if path.is_empty() {
+                ui.colored_label(egui::Color32::YELLOW, "Enter the path to a grayscale image");
+            } else if !std::path::Path::new(path.as_str()).exists() {
+                ui.colored_label(egui::Color32::RED, "File not found");
+            }
+        }
     }
 
     ui.add_space(4.0);
 
+    egui::ComboBox::from_label("Cells")
+        .selected_text(params.cells.label())
+        .show_ui(ui, |ui| {
+            for site in CellSite::ALL {
+                ui.selectable_value(&mut params.cells, site, site.label());
+            }
+        })
+        .response
+        .on_hover_text("Vertex cells interact along mesh edges");
+
     let mut seed_str = params.seed.to_string();
     ui.horizontal(|ui| {
         ui.label("Seed:");
@@ -1986,7 +3355,12 @@ fn render_surface_params(ui: &mut egui::Ui, params: &mut SurfaceParams) {
     });
 }
 
-fn render_viz_settings(ui: &mut egui::Ui, viz: &mut VizSettings, commander: &SimCommander) {
+fn render_viz_settings(
+    ui: &mut egui::Ui,
+    viz: &mut VizSettings,
+    recorder: &mut FrameRecorder,
+    commander: &SimCommander,
+) {
     egui::CollapsingHeader::new("Visualization")
         .default_open(true)
         .show(ui, |ui| {
@@ -1999,7 +3373,7 @@ fn render_viz_settings(ui: &mut egui::Ui, viz: &mut VizSettings, commander: &Sim
                     }
                 });
             if viz.color_mode != prev_mode {
-                let _ = commander.0.send(SimCommand::SetColorMode(viz.color_mode));
+                commander.broadcast(SimCommand::SetColorMode(viz.color_mode));
             }
 
             ui.add_space(4.0);
@@ -2007,15 +3381,94 @@ fn render_viz_settings(ui: &mut egui::Ui, viz: &mut VizSettings, commander: &Sim
             let prev_blur = viz.blur;
             ui.add(egui::Slider::new(&mut viz.blur, 0.0..=1.0).text("Blur"));
             if (viz.blur - prev_blur).abs() > f32::EPSILON {
-                let _ = commander.0.send(SimCommand::SetBlur(viz.blur));
+                commander.broadcast(SimCommand::SetBlur(viz.blur));
+            }
+            let (prev_mode, prev_radius) = (viz.blur_mode, viz.blur_radius);
+            egui::ComboBox::from_label("Blur over")
+                .selected_text(viz.blur_mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in [BlurMode::Adjacency, BlurMode::GeodesicWeighted] {
+                        ui.selectable_value(&mut viz.blur_mode, mode, mode.label());
+                    }
+                });
+            if viz.blur_mode == BlurMode::GeodesicWeighted {
+                ui.add(egui::Slider::new(&mut viz.blur_radius, 0.0..=1.0).text("Falloff"))
+                    .on_hover_text(
+                        "Gaussian width, as a fraction of each cell's farthest neighbor",
+                    );
+            }
+            if viz.blur_mode != prev_mode || viz.blur_radius != prev_radius {
+                commander.broadcast(SimCommand::SetBlurMode {
+                    mode: viz.blur_mode,
+                    radius: viz.blur_radius,
+                });
+            }
+            ui.checkbox(&mut viz.smooth_normals, "Smooth shading")
+                .on_hover_text("Average normals across faces; colors stay per cell");
+            ui.checkbox(&mut viz.lock_camera, "Lock camera")
+                .on_hover_text("Keep the current view when the surface is rebuilt");
+            let record = ui
+                .checkbox(&mut recorder.recording, "Record frames")
+                .on_hover_text(format!(
+                    "Save the first surface's colors to {FRAMES_DIR}/NNNNNN.png as they update"
+                ));
+            if record.changed() && recorder.recording {
+                recorder.last_error = None;
+            }
+            let prev_every = viz.snapshot_every;
+            ui.horizontal(|ui| {
+                ui.label("Snapshot every");
+                ui.add(egui::DragValue::new(&mut viz.snapshot_every).range(0..=10_000));
+                ui.label("epochs");
+            })
+            .response
+            .on_hover_text("0 redraws on a timer; N redraws the same epochs on any machine");
+            if viz.snapshot_every != prev_every {
+                commander.broadcast(SimCommand::SetSnapshotEvery(viz.snapshot_every));
+            }
+            if recorder.next_frame > 0 {
+                ui.label(format!("{} frames in {FRAMES_DIR}/", recorder.next_frame));
+            }
+            if let Some(err) = &recorder.last_error {
+                ui.colored_label(egui::Color32::RED, err);
             }
         });
 }
 
+/// Program size and step limit sliders; true once a change is let go of,
+/// when every soup should restart with the new settings.
+fn render_soup_settings(ui: &mut egui::Ui, menu: &mut MenuConfig) -> bool {
+    // A drag restarts the soups once, when released.
+    let settled = |response: egui::Response| {
+        response.drag_stopped() || (response.changed() && !response.dragged())
+    };
+    egui::CollapsingHeader::new("Soup")
+        .default_open(false)
+        .show(ui, |ui| {
+            let mut ps = menu.program_size as u32;
+            let ps_response = ui.add(egui::Slider::new(&mut ps, 8..=256).text("Program size"));
+            menu.program_size = ps as usize;
+
+            let mut sl = menu.step_limit as f64;
+            let sl_response = ui.add(
+                egui::Slider::new(&mut sl, 64.0..=1_000_000.0)
+                    .logarithmic(true)
+                    .text("Step limit"),
+            );
+            menu.step_limit = sl as usize;
+
+            ui.label("Changing either restarts the simulation.");
+            settled(ps_response) | settled(sl_response)
+        })
+        .body_returned
+        .unwrap_or(false)
+}
+
 fn render_controls_section(
     ui: &mut egui::Ui,
     history: &SimulationHistory,
     playback: &mut PlaybackState,
+    mutation_rate: f64,
     commander: &SimCommander,
 ) {
     let current_epoch = history.entries.last().map(|e| e.epoch).unwrap_or(0);
@@ -2037,7 +3490,18 @@ fn render_controls_section(
                 } else {
                     SimCommand::Pause
                 };
-                let _ = commander.0.send(cmd);
+                commander.broadcast(cmd);
+            }
+            let freeze = ui
+                .checkbox(&mut playback.mutation_frozen, "Freeze mutation")
+                .on_hover_text("Stop mutating and let the existing variants compete");
+            if freeze.changed() {
+                let rate = if playback.mutation_frozen {
+                    0.0
+                } else {
+                    mutation_rate
+                };
+                commander.broadcast(SimCommand::SetMutationRate(rate));
             }
 
             if let Some(latest) = history.entries.last() {
@@ -2045,13 +3509,40 @@ fn render_controls_section(
                 ui.label(format!("HOE: {:.4}", latest.hoe));
                 ui.label(format!("Unique programs: {}", latest.unique_count));
                 ui.label(format!("Zero bytes: {}", latest.zero_count));
+                ui.label(format!("Byte χ² vs uniform: {:.1}", latest.chi_square));
+                ui.label(format!(
+                    "Parasites: {:.1}%",
+                    100.0 * latest.parasite_fraction
+                ));
+                if let Some(flatness) = latest.spectral_flatness {
+                    ui.label(format!("Spectral flatness: {flatness:.4}"));
+                }
+                ui.label(format!(
+                    "Exact replicators in top {QUINE_SAMPLE}: {}",
+                    latest.quines
+                ));
+                if let Some(active) = latest.active_fraction {
+                    ui.label(format!(
+                        "Active bytes of top program: {:.1}%",
+                        100.0 * active
+                    ));
+                }
             }
         });
 }
 
-fn render_selected_cell(ui: &mut egui::Ui, selected: &SelectedCell) {
+fn render_selected_cell(
+    ui: &mut egui::Ui,
+    selected: &mut SelectedCell,
+    num_surfaces: usize,
+    substrate: &str,
+    epoch: usize,
+) {
     let header_text = match selected.cell_index {
         None => "Selected Cell".to_string(),
+        Some(idx) if num_surfaces > 1 => {
+            format!("Selected Cell #{idx} (surface {})", selected.surface + 1)
+        }
         Some(idx) => format!("Selected Cell #{idx}"),
     };
 
@@ -2078,9 +3569,202 @@ fn render_selected_cell(ui: &mut egui::Ui, selected: &SelectedCell) {
                 }
             },
         });
+    render_save_organism(ui, selected, substrate, epoch);
+    render_watch_log(ui, selected);
+    ui.add_space(8.0);
+    render_cell_diff(ui, selected);
+}
+
+/// One line of the interaction log.
+fn watch_event_line(event: &WatchEvent) -> String {
+    let role = match event.role {
+        WatchRole::Unpaired => "unpaired".to_string(),
+        WatchRole::SelfPaired => "self-paired".to_string(),
+        WatchRole::Aggressor { partner } => format!("aggressor vs #{partner}"),
+        WatchRole::Target { partner } => format!("target of #{partner}"),
+    };
+    match event.changed_bytes {
+        0 => format!("{}: {role}", event.epoch),
+        n => format!("{}: {role}, {n} bytes changed", event.epoch),
+    }
+}
+
+/// The selected cell's pairings and program changes, epoch by epoch,
+/// following the newest.
+fn render_watch_log(ui: &mut egui::Ui, selected: &SelectedCell) {
+    if selected.cell_index.is_none() {
+        return;
+    }
+    egui::CollapsingHeader::new("Interaction log")
+        .default_open(false)
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for event in &selected.watch_log {
+                        ui.monospace(watch_event_line(event));
+                    }
+                });
+        });
+}
+
+/// Write the plotted metric series, undecimated, to [`METRICS_CSV`].
+fn export_metrics(entries: &[EpochMetrics]) -> Result<(), String> {
+    let file = std::fs::File::create(METRICS_CSV)
+        .map_err(|e| format!("Failed to create {METRICS_CSV}: {e}"))?;
+    let mut out = std::io::BufWriter::new(file);
+    write_metrics_csv(entries, &mut out)
+        .and_then(|()| std::io::Write::flush(&mut out))
+        .map_err(|e| format!("Failed to write {METRICS_CSV}: {e}"))
+}
+
+/// Note field and "Save Organism" button for the selected cell's program,
+/// which write it to a `.life` file in the working directory.
+fn render_save_organism(
+    ui: &mut egui::Ui,
+    selected: &mut SelectedCell,
+    substrate: &str,
+    epoch: usize,
+) {
+    let (Some(cell), Some(bytes)) = (selected.cell_index, &selected.program_bytes) else {
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.label("Note:");
+        ui.text_edit_singleline(&mut selected.organism_note);
+    });
+    if ui.button("Save Organism").clicked() {
+        let organism = Organism {
+            substrate: substrate.to_string(),
+            bytes: bytes.clone(),
+            epoch,
+            note: selected.organism_note.clone(),
+        };
+        let path = PathBuf::from(format!("organism-{substrate}-cell{cell}-epoch{epoch}.life"));
+        selected.organism_status = Some(match organism.save(&path) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => e,
+        });
+    }
+    if let Some(status) = &selected.organism_status {
+        ui.label(status);
+    }
+}
+
+/// The selected cell and the second shift+clicked cell side by side, with
+/// the bytes that differ highlighted.
+fn render_cell_diff(ui: &mut egui::Ui, selected: &SelectedCell) {
+    let (Some(first), Some(second)) = (selected.cell_index, &selected.second) else {
+        return;
+    };
+    egui::CollapsingHeader::new(format!("Diff #{first} vs #{}", second.cell_index))
+        .default_open(true)
+        .show(ui, |ui| {
+            let (Some(a), Some(b), Some(disasm_a), Some(disasm_b)) = (
+                &selected.program_bytes,
+                &second.program_bytes,
+                &selected.disassembly,
+                &second.disassembly,
+            ) else {
+                ui.spinner();
+                return;
+            };
+            let diffs = diff_positions(a, b);
+            let aligned = a.len().min(b.len());
+            ui.label(format!("{} of {aligned} bytes differ", diffs.len()));
+            if a.len() != b.len() {
+                ui.label(format!(
+                    "Lengths differ ({} vs {}); aligned up to {aligned}",
+                    a.len(),
+                    b.len()
+                ));
+            }
+            let mut differs = vec![false; aligned];
+            for i in diffs {
+                differs[i] = true;
+            }
+            ui.columns(2, |columns| {
+                for (i, (ui, (bytes, disasm))) in columns
+                    .iter_mut()
+                    .zip([(a, disasm_a), (b, disasm_b)])
+                    .enumerate()
+                {
+                    ui.label(hex_diff_layout(bytes, &differs));
+                    egui::ScrollArea::vertical()
+                        .id_salt(("diff_disassembly", i))
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut disasm.as_str())
+                                    .font(egui::TextStyle::Monospace)
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                }
+            });
+        });
     ui.add_space(8.0);
 }
 
+/// `bytes` as hex, 8 to a row, with positions flagged in `differs` in
+/// [`DIFF_COLOR`].
+fn hex_diff_layout(bytes: &[u8], differs: &[bool]) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    for (i, byte) in bytes.iter().enumerate() {
+        let separator = match i {
+            0 => "",
+            _ if i % 8 == 0 => "\n",
+            _ => " ",
+        };
+        let color = if differs.get(i) == Some(&true) {
+            DIFF_COLOR
+        } else {
+            egui::Color32::GRAY
+        };
+        job.append(
+            &format!("{separator}{byte:02x}"),
+            0.0,
+            egui::TextFormat {
+                font_id: egui::FontId::monospace(12.0),
+                color,
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// Render FPS and the latest simulation epochs/sec in the top-left corner,
+/// toggled with F3.
+fn render_perf_overlay(
+    mut contexts: EguiContexts,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut show: ResMut<ShowPerfOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    history: Res<SimulationHistory>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        show.0 = !show.0;
+    }
+    if !show.0 {
+        return;
+    }
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let epochs_per_second = history.entries.last().map_or(0.0, |e| e.epochs_per_second);
+    egui::Area::new(egui::Id::new("perf_overlay"))
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(8.0, 8.0))
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.monospace(format!("{fps:6.1} fps"));
+                ui.monospace(format!("{epochs_per_second:6.1} epochs/s"));
+            });
+        });
+}
+
 fn render_help_window(ctx: &egui::Context, show_help: &mut ShowHelp) {
     egui::Window::new("Help")
         .collapsible(false)
@@ -2104,7 +3788,13 @@ fn render_help_window(ctx: &egui::Context, show_help: &mut ShowHelp) {
                 ui.label("Zoom");
                 ui.end_row();
                 ui.label("Shift+click");
-                ui.label("Inspect cell program");
+                ui.label("Inspect a cell and highlight its neighbors (off the surface: clear)");
+                ui.end_row();
+                ui.label("Shift+click again");
+                ui.label("Diff a second cell's bytes against the selected one");
+                ui.end_row();
+                ui.label("F3");
+                ui.label("Show or hide render FPS and simulation epochs/sec");
                 ui.end_row();
             });
             ui.add_space(12.0);
@@ -2136,10 +3826,91 @@ fn decimated_plot_points(
     PlotPoints::new(points)
 }
 
-fn render_plots_section(ui: &mut egui::Ui, entries: &[EpochMetrics]) {
+fn render_color_legend(ui: &mut egui::Ui, mode: ColorMode) {
+    egui::CollapsingHeader::new("Color Legend")
+        .default_open(true)
+        .show(ui, |ui| {
+            let swatches: Vec<(&str, [u8; 3])> = match mode {
+                ColorMode::OpcodeClass => OPCODE_CLASS_NAMES
+                    .into_iter()
+                    .zip(OPCODE_CLASS_PALETTE)
+                    .collect(),
+                ColorMode::TerminationCause => TERMINATION_LEGEND
+                    .into_iter()
+                    .map(|(name, halt)| (name, termination_color(halt)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            if !swatches.is_empty() {
+                for (name, [r, g, b]) in swatches {
+                    ui.horizontal(|ui| {
+                        let (swatch, _) =
+                            ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                        ui.painter()
+                            .rect_filled(swatch, 2.0, egui::Color32::from_rgb(r, g, b));
+                        ui.label(name);
+                    });
+                }
+                return;
+            }
+            let Some(scale) = mode.legend_scale() else {
+                ui.label("Each distinct program gets its own color.");
+                return;
+            };
+
+            ui.label(scale.quantity);
+            let width = ui.available_width();
+            let (strip, _) = ui.allocate_exact_size(egui::vec2(width, 14.0), egui::Sense::hover());
+            let steps = 64;
+            for i in 0..steps {
+                let x0 = strip.left() + width * i as f32 / steps as f32;
+                let x1 = strip.left() + width * (i + 1) as f32 / steps as f32;
+                let [r, g, b] = (scale.gradient)((i as f32 + 0.5) / steps as f32);
+                ui.painter().rect_filled(
+                    egui::Rect::from_x_y_ranges(x0..=x1, strip.y_range()),
+                    0.0,
+                    egui::Color32::from_rgb(r, g, b),
+                );
+            }
+
+            let (labels, _) = ui.allocate_exact_size(egui::vec2(width, 14.0), egui::Sense::hover());
+            for (t, label) in legend_ticks(&scale, LEGEND_TICKS) {
+                let anchor = if t == 0.0 {
+                    egui::Align2::LEFT_TOP
+                } else if t == 1.0 {
+                    egui::Align2::RIGHT_TOP
+                } else {
+                    egui::Align2::CENTER_TOP
+                };
+                ui.painter().text(
+                    egui::pos2(labels.left() + width * t, labels.top()),
+                    anchor,
+                    label,
+                    egui::FontId::proportional(11.0),
+                    ui.visuals().text_color(),
+                );
+            }
+        });
+}
+
+fn render_plots_section(ui: &mut egui::Ui, history: &mut SimulationHistory) {
     egui::CollapsingHeader::new("Plots")
         .default_open(true)
         .show(ui, |ui| {
+            let export = ui.button("Export metrics CSV").on_hover_text(format!(
+                "Write every epoch's metrics, as --metrics full prints them, to {METRICS_CSV}"
+            ));
+            if export.clicked() {
+                history.export_status = Some(match export_metrics(&history.entries) {
+                    Ok(()) => format!("Saved {METRICS_CSV}"),
+                    Err(e) => e,
+                });
+            }
+            if let Some(status) = &history.export_status {
+                ui.label(status);
+            }
+
+            let entries = &history.entries;
             let plot_height = 150.0;
 
             ui.label("High-Order Entropy");
@@ -2170,5 +3941,503 @@ fn render_plots_section(ui: &mut egui::Ui, entries: &[EpochMetrics]) {
                     plot_ui.line(Line::new(zero_points).name("Zeros"));
                 },
             );
+
+            ui.label("Byte χ² vs Uniform");
+            let chi_points = decimated_plot_points(entries, |e| [e.epoch as f64, e.chi_square]);
+            Plot::new("chi_square_plot").height(plot_height).show(
+                ui,
+                |plot_ui: &mut egui_plot::PlotUi| {
+                    plot_ui.line(Line::new(chi_points).name("χ²"));
+                },
+            );
+
+            ui.label("Parasite Fraction");
+            let parasite_points =
+                decimated_plot_points(entries, |e| [e.epoch as f64, e.parasite_fraction]);
+            Plot::new("parasite_plot").height(plot_height).show(
+                ui,
+                |plot_ui: &mut egui_plot::PlotUi| {
+                    plot_ui.line(Line::new(parasite_points).name("Parasites"));
+                },
+            );
+
+            ui.label(format!("Exact Replicators in Top {QUINE_SAMPLE} Programs"));
+            let quine_points =
+                decimated_plot_points(entries, |e| [e.epoch as f64, e.quines as f64]);
+            Plot::new("quine_plot").height(plot_height).show(
+                ui,
+                |plot_ui: &mut egui_plot::PlotUi| {
+                    plot_ui.line(Line::new(quine_points).name("Quines"));
+                },
+            );
+
+            if entries.iter().any(|e| e.active_fraction.is_some()) {
+                ui.label("Active Fraction of Top Program");
+                let active_points = decimated_plot_points(entries, |e| {
+                    [e.epoch as f64, e.active_fraction.unwrap_or_default()]
+                });
+                Plot::new("active_plot").height(plot_height).show(
+                    ui,
+                    |plot_ui: &mut egui_plot::PlotUi| {
+                        plot_ui.line(Line::new(active_points).name("Active"));
+                    },
+                );
+            }
+
+            if let Some(latest) = entries.last()
+                && !latest.positional_mi.is_empty()
+            {
+                ui.label(format!(
+                    "Adjacent-Offset Mutual Information (bits, epoch {})",
+                    latest.epoch
+                ));
+                let bars = latest
+                    .positional_mi
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &mi)| Bar::new(i as f64, mi).width(0.8))
+                    .collect();
+                Plot::new("positional_mi_plot").height(plot_height).show(
+                    ui,
+                    |plot_ui: &mut egui_plot::PlotUi| {
+                        plot_ui.bar_chart(BarChart::new(bars).name("MI(i, i+1)"));
+                    },
+                );
+            }
+
+            if cfg!(feature = "spectral") {
+                ui.label("Spectral Flatness");
+                let flatness_points = decimated_plot_points(entries, |e| {
+                    [e.epoch as f64, e.spectral_flatness.unwrap_or_default()]
+                });
+                Plot::new("flatness_plot").height(plot_height).show(
+                    ui,
+                    |plot_ui: &mut egui_plot::PlotUi| {
+                        plot_ui.line(Line::new(flatness_points).name("Flatness"));
+                    },
+                );
+            }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::z80::{I8080, Z80};
+
+    #[test]
+    fn test_color_mode_names_and_labels_parse_back() {
+        for mode in ColorMode::ALL {
+            assert_eq!(ColorMode::parse(mode.name()), Ok(mode));
+            assert_eq!(ColorMode::parse(mode.label()), Ok(mode));
+            assert_eq!(ColorMode::parse(&mode.label().to_uppercase()), Ok(mode));
+        }
+        let err = ColorMode::parse("plaid").unwrap_err();
+        assert!(err.contains("hash"), "{err}");
+        assert!(err.contains("temporal-glow"), "{err}");
+    }
+
+    #[test]
+    fn test_vertex_cells_color_and_pick_by_corner() {
+        let mut mesh = SurfaceMesh::flat_grid(2, 2).unwrap();
+        assert_eq!(build_render_cells(&mesh)[..6], [0, 0, 0, 1, 1, 1]);
+        mesh.set_cell_site(CellSite::Vertex).unwrap();
+        let render_cells = build_render_cells(&mesh);
+        assert_eq!(render_cells.len(), mesh.faces.len() * 3);
+        let [a, b, c] = mesh.faces[1];
+        assert_eq!(picked_cell(&render_cells, 1, Vec3::new(0.1, 0.2, 0.7)), a);
+        assert_eq!(picked_cell(&render_cells, 1, Vec3::new(0.6, 0.2, 0.2)), b);
+        assert_eq!(picked_cell(&render_cells, 1, Vec3::new(0.1, 0.8, 0.1)), c);
+    }
+
+    #[test]
+    fn test_watch_event_line() {
+        let mut event = WatchEvent {
+            epoch: 12,
+            cell: 3,
+            role: WatchRole::Target { partner: 7 },
+            changed_bytes: 5,
+        };
+        assert_eq!(
+            watch_event_line(&event),
+            "12: target of #7, 5 bytes changed"
+        );
+        event.role = WatchRole::Unpaired;
+        event.changed_bytes = 0;
+        assert_eq!(watch_event_line(&event), "12: unpaired");
+    }
+
+    #[test]
+    fn test_should_send_snapshot() {
+        use std::time::Duration;
+        let (soon, late) = (Duration::ZERO, SNAPSHOT_INTERVAL);
+        // On the timer, only elapsed time matters.
+        assert!(!should_send_snapshot(5, 100, 0, soon));
+        assert!(should_send_snapshot(5, 100, 0, late));
+        // Every K epochs, only the epoch does.
+        assert!(should_send_snapshot(10, 100, 5, soon));
+        assert!(!should_send_snapshot(11, 100, 5, late));
+        // The last epoch is always drawn.
+        assert!(should_send_snapshot(100, 100, 7, soon));
+        assert!(should_send_snapshot(100, 100, 0, soon));
+    }
+
+    #[test]
+    fn test_smooth_rate() {
+        // The first sample is taken as is.
+        assert_eq!(smooth_rate(0.0, 120.0, 0.3), 120.0);
+        // Later samples move the average part of the way.
+        assert!((smooth_rate(100.0, 200.0, 0.3) - 130.0).abs() < 1e-9);
+        // A steady rate is a fixed point, and the average converges on a
+        // new steady rate.
+        assert_eq!(smooth_rate(50.0, 50.0, 0.3), 50.0);
+        let settled = (0..50).fold(10.0, |avg, _| smooth_rate(avg, 80.0, 0.3));
+        assert!((settled - 80.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_snapshot_buffers_are_recycled() {
+        let (snap_tx, pool, rx) = snapshot_channel();
+        let mut latest: Option<SurfaceSnapshot> = None;
+        let mut buffers = std::collections::HashSet::new();
+        for frame in 0..100u8 {
+            let mut colors = pool.take();
+            colors.clear();
+            colors.resize(64, frame);
+            buffers.insert(colors.as_ptr());
+            let watch_log = Vec::new();
+            snap_tx.send(SurfaceSnapshot { colors, watch_log }).unwrap();
+            // The render thread drains every other frame.
+            if frame % 2 == 1 {
+                while let Ok(snapshot) = rx.snapshots.try_recv() {
+                    if let Some(old) = latest.replace(snapshot) {
+                        rx.recycle(old.colors);
+                    }
+                }
+                assert_eq!(latest.as_ref().unwrap().colors[0], frame);
+            }
+        }
+        // Two in flight, one held as the latest, the rest pooled.
+        let allocated = buffers.len();
+        assert!(allocated <= RECYCLED_SNAPSHOT_BUFFERS + 2, "{allocated}");
+
+        // A full pool drops what it can't hold.
+        for _ in 0..10 {
+            rx.recycle(vec![0; 64]);
+        }
+        assert_eq!(pool.0.try_iter().count(), RECYCLED_SNAPSHOT_BUFFERS);
+    }
+
+    #[test]
+    fn test_reset_keeps_mesh_with_new_config() {
+        let mut mesh = SurfaceMesh::flat_grid(4, 4).unwrap();
+        mesh.compute_neighbors(None, None);
+        let num_cells = mesh.num_cells();
+        let config = SoupSurfaceConfig {
+            program_size: 64,
+            step_limit: 256,
+            ..Default::default()
+        };
+        let kinds = (SubstrateKind::Bff, SubstrateKind::Bff);
+        let (_, _, cmd_tx, prog_rx) = spawn_sim_thread(
+            SubstrateKind::Bff,
+            kinds,
+            mesh,
+            config.clone(),
+            1,
+            10,
+            5,
+            0.0,
+        );
+        cmd_tx
+            .send(SimCommand::ResetSurface {
+                mesh: None,
+                config: Box::new(SoupSurfaceConfig {
+                    program_size: 16,
+                    ..config
+                }),
+                seed: 1,
+            })
+            .unwrap();
+        cmd_tx
+            .send(SimCommand::RequestProgram(num_cells - 1))
+            .unwrap();
+        let response = prog_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(response.cell, num_cells - 1);
+        assert_eq!(response.bytes.len(), 16);
+    }
+
+    #[test]
+    fn test_frame_paths_sort_in_order() {
+        let dir = Path::new("frames");
+        assert_eq!(frame_path(dir, 0), dir.join("000000.png"));
+        assert_eq!(frame_path(dir, 42), dir.join("000042.png"));
+        let names: Vec<PathBuf> = [9, 10, 9999, 10_000, 999_999]
+            .map(|i| frame_path(dir, i))
+            .to_vec();
+        assert!(names.is_sorted());
+    }
+
+    #[test]
+    fn test_frame_image_layouts() {
+        // A 2x1 grid: faces 0 and 1 make the left square, 2 and 3 the right.
+        let colors = [
+            [10, 0, 0, 255],
+            [20, 0, 0, 255],
+            [0, 100, 0, 255],
+            [0, 100, 0, 255],
+        ]
+        .concat();
+        let spec = SurfaceSpec::FlatGrid {
+            width: 2,
+            height: 1,
+        };
+        let layout = FrameLayout::for_surface(&spec, CellSite::Face);
+        let image = frame_image(&colors, layout);
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [15, 0, 0]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 100, 0]);
+
+        // Off a grid, five cells fill a 2x3 image row by row.
+        let layout = FrameLayout::for_surface(&spec, CellSite::Vertex);
+        assert_eq!(layout, FrameLayout::Rows);
+        let image = frame_image(&[colors.clone(), colors[..4].to_vec()].concat(), layout);
+        assert_eq!(image.dimensions(), (2, 3));
+        assert_eq!(image.get_pixel(0, 2).0, [10, 0, 0]);
+        assert_eq!(image.get_pixel(1, 2).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_grid_minimap_image() {
+        // A 1x2 grid: faces 0 and 1 make the bottom square, drawn last.
+        let colors = [
+            [10, 0, 0, 255],
+            [20, 0, 0, 255],
+            [0, 100, 0, 255],
+            [0, 100, 0, 255],
+        ]
+        .concat();
+        let layout = FrameLayout::Grid {
+            width: 1,
+            height: 2,
+        };
+        let image = grid_minimap_image(&colors, layout).unwrap();
+        assert_eq!(image.size, [1, 2]);
+        assert_eq!(image.pixels[0], egui::Color32::from_rgb(0, 100, 0));
+        assert_eq!(image.pixels[1], egui::Color32::from_rgb(15, 0, 0));
+        assert!(grid_minimap_image(&colors, FrameLayout::Rows).is_none());
+    }
+
+    #[test]
+    fn test_heatmap_rounds_channels() {
+        assert_eq!(heatmap(0.0), [0, 0, 255]);
+        // Exactly half a channel rounds up rather than truncating to 127.
+        assert_eq!(heatmap(0.125), [0, 128, 255]);
+        // 1 - 0.2 in f32 is just under 0.8, so truncation would give 203.
+        assert_eq!(heatmap(0.3), [0, 255, 204]);
+        assert_eq!(heatmap(0.6), [102, 255, 0]);
+        assert_eq!(heatmap(1.0), [255, 0, 0]);
+        assert_eq!(grayscale_inverted(0.5), [128, 128, 128]);
+    }
+
+    #[test]
+    fn test_loopers_color_as_step_limit() {
+        // NOP, JMP -1: the program loops over its first two bytes forever.
+        let mut looper = vec![0x0F; 64];
+        looper[1] = 0xC0;
+        let config = SoupSurfaceConfig {
+            step_limit: 256,
+            self_pair_fraction: 1.0,
+            ..Default::default()
+        };
+        let mesh = SurfaceMesh::flat_grid(4, 4).unwrap();
+        let mut soup = SoupSurface::new(mesh, config, 7);
+        soup.programs.fill(looper);
+        soup.run_epoch::<crate::forth::Forth>();
+        let mut colors = Vec::new();
+        fill_colors_termination(soup.halts(), &mut colors);
+        let [r, g, b] = termination_color(Some(Halt::StepLimit));
+        assert_eq!(colors.len(), soup.programs.len() * 4);
+        for rgba in colors.chunks(4) {
+            assert_eq!(rgba, [r, g, b, 255]);
+        }
+    }
+
+    #[test]
+    fn test_accumulate_glow() {
+        let before = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
+        let after = vec![vec![1, 2], vec![3, 9], vec![5, 6]];
+        let mut glow = vec![0.0, 0.0, 1.0];
+        accumulate_glow(&mut glow, &before, &after);
+        assert_eq!(glow, [0.0, GLOW_GAIN, GLOW_DECAY]);
+        // Repeated change saturates at 1; no change fades geometrically.
+        for _ in 0..10 {
+            accumulate_glow(&mut glow, &before, &after);
+        }
+        assert_eq!(glow[1], 1.0);
+        assert!((glow[2] - GLOW_DECAY.powi(11)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_diff_positions() {
+        assert_eq!(diff_positions(&[1, 2, 3, 4], &[1, 9, 3, 8]), [1, 3]);
+        assert!(diff_positions(&[5, 6], &[5, 6]).is_empty());
+        // Different lengths align up to the shorter program.
+        assert_eq!(diff_positions(&[1, 2, 3], &[0, 2]), [0]);
+        assert_eq!(diff_positions(&[], &[1, 2]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_every_substrate_is_registered() {
+        assert_eq!(SUBSTRATES.len(), SubstrateKind::ALL.len());
+        for kind in SubstrateKind::ALL {
+            let name = kind.name();
+            assert_eq!(SubstrateKind::parse(name), Ok(kind));
+            assert_eq!(SubstrateKind::parse(&name.to_uppercase()), Ok(kind));
+        }
+        let err = SubstrateKind::parse("pdp1").unwrap_err();
+        assert!(err.contains("bff, forth"), "{err}");
+    }
+
+    #[test]
+    fn test_recommended_step_limits() {
+        for kind in SubstrateKind::ALL {
+            let mut menu = MenuConfig {
+                substrate: kind,
+                ..MenuConfig::default()
+            };
+            let mut prev = 0;
+            for ps in [8, 16, 64, 256] {
+                menu.program_size = ps;
+                let limit = menu.recommended_step_limit();
+                assert!(limit > 0, "{} at {ps} bytes", kind.label());
+                assert!(limit >= prev, "{} shrinks at {ps} bytes", kind.label());
+                prev = limit;
+            }
+        }
+        // Section 3.3 of the paper.
+        assert_eq!(Z80::recommended_step_limit(16), 256);
+        assert_eq!(I8080::recommended_step_limit(16), 256);
+    }
+
+    #[test]
+    fn test_cursor_in_panel() {
+        let panel = Some(egui::Rect::from_min_max(
+            egui::pos2(800.0, 0.0),
+            egui::pos2(1200.0, 900.0),
+        ));
+        assert!(cursor_in_panel(panel, Vec2::new(900.0, 100.0), 1.0, 1.0));
+        assert!(!cursor_in_panel(panel, Vec2::new(700.0, 100.0), 1.0, 1.0));
+        assert!(!cursor_in_panel(None, Vec2::new(900.0, 100.0), 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_cursor_in_panel_with_egui_scale() {
+        // Window at 2x, egui at 2.5 px/pt: 1000 logical px = 800 points.
+        let panel = Some(egui::Rect::from_min_max(
+            egui::pos2(820.0, 0.0),
+            egui::pos2(1000.0, 900.0),
+        ));
+        assert!(!cursor_in_panel(panel, Vec2::new(1000.0, 10.0), 2.0, 2.5));
+        assert!(cursor_in_panel(panel, Vec2::new(1050.0, 10.0), 2.0, 2.5));
+    }
+
+    #[test]
+    fn test_combined_bounding_sphere() {
+        let sphere = SurfaceSpec::Sphere { subdivisions: 2 }.build().unwrap();
+        let torus = SurfaceSpec::Torus {
+            major: 24,
+            minor: 12,
+        }
+        .build()
+        .unwrap();
+        let spheres = [sphere.bounding_sphere(), torus.bounding_sphere()];
+        let offsets = side_by_side_offsets(&spheres);
+        assert_eq!(offsets[0], [0.0; 3]);
+
+        let placed: Vec<([f32; 3], f32)> = spheres
+            .iter()
+            .zip(&offsets)
+            .map(|(&(c, r), o)| ([c[0] + o[0], c[1] + o[1], c[2] + o[2]], r))
+            .collect();
+        // Side by side, not overlapping.
+        let gap = Vec3::from_array(placed[0].0).distance(Vec3::from_array(placed[1].0));
+        assert!(gap > placed[0].1 + placed[1].1);
+
+        let (center, radius) = combined_bounding_sphere(placed[0], placed[1]);
+        for &(c, r) in &placed {
+            let d = Vec3::from_array(center).distance(Vec3::from_array(c));
+            assert!(d + r <= radius + 1e-4, "{d} + {r} > {radius}");
+        }
+        // Tight: exactly spans the two spheres along the line joining them.
+        assert!((2.0 * radius - (gap + placed[0].1 + placed[1].1)).abs() < 1e-4);
+
+        // A sphere inside another adds nothing.
+        let outer = ([0.0, 0.0, 0.0], 5.0);
+        let inner = ([1.0, 0.0, 0.0], 1.0);
+        assert_eq!(combined_bounding_sphere(outer, inner), outer);
+        assert_eq!(combined_bounding_sphere(inner, outer), outer);
+    }
+
+    #[test]
+    fn test_legend_ticks() {
+        let scale = ColorMode::InstructionDensity.legend_scale().unwrap();
+        let ticks = legend_ticks(&scale, LEGEND_TICKS);
+        let labels: Vec<&str> = ticks.iter().map(|(_, l)| l.as_str()).collect();
+        assert_eq!(labels, ["0.00", "0.25", "0.50", "0.75", "1.00"]);
+        assert_eq!(ticks.first().unwrap().0, 0.0);
+        assert_eq!(ticks.last().unwrap().0, 1.0);
+
+        // Unique Bytes paints few distinct bytes hot, so its axis runs 1 → 0.
+        let scale = ColorMode::UniqueBytes.legend_scale().unwrap();
+        let ticks = legend_ticks(&scale, 3);
+        assert_eq!(ticks[0].1, "1.00");
+        assert_eq!(ticks[2].1, "0.00");
+
+        assert!(ColorMode::Hash.legend_scale().is_none());
+        assert!(ColorMode::OpcodeClass.legend_scale().is_none());
+    }
+
+    #[test]
+    fn test_weighted_blur_zero_radius_is_identity() {
+        let mut mesh = SurfaceMesh::icosphere(1).unwrap();
+        mesh.compute_neighbors(None, None);
+        let colors: Vec<u8> = (0..mesh.num_cells() * 4)
+            .map(|i| (i * 37 % 256) as u8)
+            .collect();
+        let blurred = |radius| {
+            let mut out = colors.clone();
+            let mut scratch = Vec::new();
+            blur_surface_colors_weighted(&mut out, &mut scratch, &mesh, radius, 1.0);
+            out
+        };
+        assert_eq!(blurred(0.0), colors);
+        // Every exp(-d² / 2σ²) underflows at this falloff.
+        assert_eq!(blurred(0.01), colors);
+        assert_ne!(blurred(0.5), colors);
+        assert_eq!(mesh.neighbor_distances.len(), mesh.neighbor_indices.len());
+    }
+
+    #[test]
+    fn test_smooth_normals_point_outward_from_vertices() {
+        let mesh = SurfaceMesh::icosphere(2).unwrap();
+        let smooth = build_smooth_render_normals(&mesh);
+        let flat = build_render_normals(&mesh);
+        assert_eq!(smooth.len(), flat.len());
+        let radial = |corner: usize| {
+            let v = mesh.faces[corner / 3][corner % 3];
+            Vec3::from_array(mesh.vertices[v]).normalize()
+        };
+        for (corner, normal) in smooth.iter().enumerate() {
+            assert!(Vec3::from_array(*normal).dot(radial(corner)) > 0.999);
+        }
+        assert!((0..flat.len()).any(|c| Vec3::from_array(flat[c]).dot(radial(c)) < 0.999));
+
+        let grid = SurfaceMesh::flat_grid(3, 3).unwrap();
+        for normal in build_smooth_render_normals(&grid) {
+            assert!(Vec3::from_array(normal).abs_diff_eq(Vec3::Z, 1e-6));
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::cell::{Cell, RefCell};
 
-use crate::substrate::Substrate;
+use crate::substrate::{Substrate, validate_16bit_program_size};
 use iz80::{Cpu, Machine, Reg16};
 
 /// Wrapper that presents a byte-slice tape as Z80/8080 memory with modular addressing.
@@ -159,6 +159,16 @@ impl Substrate for Z80 {
         Z80_CPU.with_borrow_mut(|cpu| execute_cpu(cpu, tape, step_limit))
     }
 
+    /// Section 3.3: 256 steps for 16-byte programs. Real CPU instructions
+    /// span one to four bytes, so the budget scales with the tape.
+    fn recommended_step_limit(program_size: usize) -> usize {
+        16 * program_size
+    }
+
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        validate_16bit_program_size(program_size)
+    }
+
     fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
         if tape.is_empty() {
             return 0;
@@ -248,6 +258,16 @@ impl Substrate for I8080 {
         I8080_CPU.with_borrow_mut(|cpu| execute_cpu(cpu, tape, step_limit))
     }
 
+    /// Section 3.3: 256 steps for 16-byte programs. Real CPU instructions
+    /// span one to four bytes, so the budget scales with the tape.
+    fn recommended_step_limit(program_size: usize) -> usize {
+        16 * program_size
+    }
+
+    fn validate_program_size(program_size: usize) -> Result<(), String> {
+        validate_16bit_program_size(program_size)
+    }
+
     fn execute_battle(tape: &mut [u8], ps: usize, step_limit: usize) -> usize {
         if tape.is_empty() {
             return 0;
@@ -633,6 +653,14 @@ mod tests {
         let steps = Z80::execute(&mut tape, 100);
         assert!(steps <= 100);
     }
+
+    #[test]
+    fn validate_program_size_fits_16bit_addresses() {
+        assert!(Z80::validate_program_size(64).is_ok());
+        assert!(I8080::validate_program_size(32768).is_ok());
+        assert!(Z80::validate_program_size(40000).is_err());
+        assert!(I8080::validate_program_size(32769).is_err());
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,78 @@
+use complife::bff::Bff;
+use complife::headless::{MetricsLevel, RunOptions, run_collecting};
+use complife::surface::{SoupSurface, SoupSurfaceConfig, SurfaceSpec};
+
+#[test]
+fn test_surface_run_reports_every_metrics_epoch() {
+    let mut mesh = SurfaceSpec::parse("sphere:1", 42).unwrap().build().unwrap();
+    mesh.compute_neighbors(None, None);
+    let config = SoupSurfaceConfig {
+        program_size: 16,
+        step_limit: 256,
+        ..SoupSurfaceConfig::default()
+    };
+    let mut soup = SoupSurface::new(mesh, config, 42);
+    let mut out = Vec::new();
+    let series = run_collecting::<Bff>(
+        &mut soup,
+        7,
+        RunOptions {
+            metrics_interval: 3,
+            ..RunOptions::default()
+        },
+        &mut out,
+    )
+    .unwrap();
+
+    let epochs: Vec<usize> = series.iter().map(|&(epoch, _)| epoch).collect();
+    assert_eq!(epochs, [0, 3, 6, 7]);
+    assert!(series.iter().all(|&(_, hoe)| hoe.is_finite()));
+
+    // The CSV carries the same series, one row per entry after the header.
+    let text = String::from_utf8(out).unwrap();
+    let rows: Vec<&str> = text.lines().skip(1).collect();
+    assert_eq!(rows.len(), series.len());
+    for (row, &(epoch, _)) in rows.iter().zip(&series) {
+        assert!(row.starts_with(&format!("{epoch},")));
+    }
+}
+
+#[test]
+fn test_full_metrics_unique_count_within_population() {
+    let mut mesh = SurfaceSpec::parse("flat:6x6", 7).unwrap().build().unwrap();
+    mesh.compute_neighbors(None, None);
+    let population = mesh.num_cells();
+    let config = SoupSurfaceConfig {
+        program_size: 16,
+        step_limit: 256,
+        ..SoupSurfaceConfig::default()
+    };
+    let mut soup = SoupSurface::new(mesh, config, 7);
+    let mut out = Vec::new();
+    run_collecting::<Bff>(
+        &mut soup,
+        4,
+        RunOptions {
+            metrics_interval: 2,
+            level: MetricsLevel::Full,
+            ..RunOptions::default()
+        },
+        &mut out,
+    )
+    .unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    let mut lines = text.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let (unique, zeros) = (
+        header.iter().position(|&c| c == "unique").unwrap(),
+        header.iter().position(|&c| c == "zeros").unwrap(),
+    );
+    for line in lines {
+        let row: Vec<&str> = line.split(',').collect();
+        assert_eq!(row.len(), header.len());
+        let count = |column: usize| row[column].parse::<usize>().unwrap();
+        assert!((1..=population).contains(&count(unique)), "{line}");
+        assert!(count(zeros) <= population * 16, "{line}");
+    }
+}